@@ -1,7 +1,7 @@
 # SPDX-License-Identifier: BSD-3-Clause
 # Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
-from typing import Any, Optional, List
+from typing import Any, Callable, Dict, Optional, List, Tuple, Union
 
 from .adaptors import RuntimeAdaptor
 
@@ -15,25 +15,183 @@ def initialize_file_logger(file_path: str):
 def initialize_commandline_logger():
     pass
 
-class Graph: ...
+class EntryPoint:
+    name: str
+    arguments: List[Tuple[str, str]]
+    """Argument names and their types, in declaration order."""
+
+    return_type: str
+    attributes: Dict[str, str]
+    required_num_qubits: Optional[int]
+    required_num_results: Optional[int]
+
+class Graph:
+    signature: EntryPoint
+    """Signature of the entry-point this graph executes."""
+
+    entry_points: List[EntryPoint]
+    """Every entry-point in the module this graph was built from."""
+
+class Pauli:
+    I: "Pauli"
+    X: "Pauli"
+    Y: "Pauli"
+    Z: "Pauli"
+
+class Qubit:
+    index: int
+
+    def __init__(self, index: int): ...
 
 class Executor:
     def trace_graphs(self): ...
     def trace_runtime(self): ...
     def trace_projections(self): ...
+    def trace_solver(self): ...
     def step_count_limit(self, limit: int): ...
+    def time_limit(self, seconds: float): ...
+    def cancel(self):
+        """Stops the run currently in progress, which then raises a ValueError."""
+
+    def subscribe(self, callback: Callable[[Dict[str, Any]], None]):
+        """Calls this with every trace event emitted during subsequent runs."""
+
+    def debug(self, handler: Callable[[Dict[str, Any]], str]):
+        """
+        Attaches a debugger, with the handler called whenever execution stops. It returns 'step', 'continue' or
+        'abort', with anything else aborting the run. See `rasqal.debugger.Debugger`.
+        """
+
+    def break_at_node(self, graph: str, node: int):
+        """Raises a ValueError if a debugger hasn't been attached."""
+
+    def break_at_label(self, label: str):
+        """Raises a ValueError if a debugger hasn't been attached."""
+
+    def break_at_function(self, name: str):
+        """Raises a ValueError if a debugger hasn't been attached."""
+
+    def clear_breakpoints(self): ...
+    def trace_to_file(self, file_path: str):
+        """Writes every trace event to this file as JSON lines. Raises a ValueError if it can't be created."""
+
+    def statistics(self) -> Optional[Dict[str, Any]]:
+        """
+        Statistics of the last run, or every run in the last batch added together. None if it failed.
+        Times are in seconds.
+        """
+
+    def activate_solver(self): ...
+    def solver_strategy(self, strategy: str):
+        """Either 'entanglement' or 'mps'. Raises a ValueError for anything else."""
+
+    def max_bond_dimension(self, max_bond_dimension: int): ...
+    def parallel_dispatch(self): ...
+    def projection_splitting(self): ...
+    def result_reduction(self, reduction: str, threshold: Optional[float]):
+        """
+        Either 'majority', 'unanimous', 'per-shot' or 'error-on-ambiguity', with threshold only used by the last.
+        Raises a ValueError for anything else.
+        """
+
+    def qubit_allocation(self, allocation: str, capacity: Optional[int]):
+        """
+        Either 'lowest-free', 'after-reset' or 'fixed', with capacity required by the last.
+        Raises a ValueError for anything else.
+        """
+
+    def cache_results(
+        self,
+        time_to_live: Optional[float],
+        max_entries: Optional[int],
+        directory: Optional[str],
+    ): ...
+    def execution_options(
+        self,
+        shots: Optional[int],
+        seed: Optional[int],
+        optimisation_level: Optional[int],
+        timeout: Optional[float],
+    ): ...
+    def projection_options(
+        self,
+        index: int,
+        shots: Optional[int],
+        seed: Optional[int],
+        optimisation_level: Optional[int],
+        timeout: Optional[float],
+    ): ...
     def run(self, file_path: str, runtimes: List[RuntimeAdaptor]) -> Any:
         """Runs this file using the automatically-detected entry-point with no arguments."""
 
     def run_with_args(
-        self, file_path: str, arguments: List[Any], runtimes: List[RuntimeAdaptor]
+        self,
+        file_path: str,
+        arguments: Union[List[Any], Dict[str, Any]],
+        runtimes: List[RuntimeAdaptor],
     ) -> Any:
-        """Runs this file using the automatically-detected entry-point."""
+        """
+        Runs this file using the automatically-detected entry-point. Arguments are either positional
+        or a dictionary of parameter names to values.
+        """
 
     def parse_file(self, file: str, entry_point: Optional[str]) -> Graph:
         """Evaluates and builds this file into the internal execution graph and returns it."""
 
+    def parse_files(self, files: List[str], entry_point: Optional[str]) -> Graph:
+        """
+        Links these files into a single module then evaluates and builds it into the internal execution graph.
+        Calls between the files are resolved as if they were one program.
+        """
+
+    def parse_ll(self, text: str, entry_point: Optional[str]) -> Graph:
+        """Evaluates and builds in-memory LLVM IR into the internal execution graph and returns it."""
+
+    def parse_bitcode(self, bitcode: bytes, entry_point: Optional[str]) -> Graph:
+        """Evaluates and builds in-memory LLVM bitcode into the internal execution graph and returns it."""
+
+    def run_ll(
+        self,
+        text: str,
+        arguments: Union[List[Any], Dict[str, Any]],
+        runtimes: List[RuntimeAdaptor],
+    ) -> Any:
+        """Runs in-memory LLVM IR using the automatically-detected entry-point."""
+
+    def run_bitcode(
+        self,
+        bitcode: bytes,
+        arguments: Union[List[Any], Dict[str, Any]],
+        runtimes: List[RuntimeAdaptor],
+    ) -> Any:
+        """Runs in-memory LLVM bitcode using the automatically-detected entry-point."""
+
     def run_graph(
-        self, graph: Graph, arguments: List[Any], runtime_adaptor: RuntimeAdaptor
+        self,
+        graph: Graph,
+        arguments: Union[List[Any], Dict[str, Any]],
+        runtime_adaptor: RuntimeAdaptor,
     ) -> Any:
         """Runs a pre-built execution graph with the passed-in arguments."""
+
+    def run_trajectories(
+        self,
+        graph: Graph,
+        arguments: Union[List[Any], Dict[str, Any]],
+        shots: int,
+    ) -> Dict[str, int]:
+        """
+        Runs a pre-built execution graph once per shot on the built-in simulator, following a single sampled
+        measurement result through every branch, and returns how often each final result came up.
+        """
+
+    def run_graph_batch(
+        self,
+        graph: Graph,
+        argument_sets: List[List[Any]],
+        runtime_adaptor: List[RuntimeAdaptor],
+    ) -> List[Any]:
+        """
+        Runs a pre-built execution graph once for each set of arguments, returning the results in order.
+        Circuits which are identical across runs are only executed once.
+        """
@@ -1,7 +1,7 @@
 # SPDX-License-Identifier: BSD-3-Clause
 # Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
-from typing import Dict
+from typing import Dict, Optional
 
 
 class BuilderAdaptor:
@@ -45,11 +45,13 @@ class RuntimeAdaptor:
     it and then use that builder/runtime combination to execute it, if applicable.
     """
 
-    def execute(self, builder) -> Dict[str, int]:
+    def execute(self, builder, options: "ExecutionOptions") -> Dict[str, int]:
         """
         Executes the passed-in builder against the backend and returns a result distribution.
 
         The builder can be expected to be the same as returned from the associated `create_builder` function.
+        Any option which has been set should be honoured, the rest are left up to the backend. Adaptors whose
+        `execute` only takes the builder are still supported, they just won't be passed any options.
         """
         return dict()
 
@@ -63,6 +65,22 @@ class RuntimeAdaptor:
         """
         return True
 
+    def identity(self) -> str:
+        """
+        Identifies this runtime when caching results, so circuits are only ever served results from
+        the same sort of backend. Defaults to the class name, so override it if differently-configured
+        instances give different results.
+        """
+        return f"{type(self).__module__}.{type(self).__name__}"
+
 
 class RequiredFeatures:
     qubit_count: int
+    control_count: int
+
+
+class ExecutionOptions:
+    shots: Optional[int]
+    seed: Optional[int]
+    optimisation_level: Optional[int]
+    timeout: Optional[float]
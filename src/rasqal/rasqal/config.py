@@ -0,0 +1,116 @@
+# SPDX-License-Identifier: BSD-3-Clause
+# Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+from dataclasses import dataclass, field
+from typing import Dict, Optional
+
+
+@dataclass
+class ExecutionSettings:
+    """Options passed to a runtime alongside each circuit. Anything left as None is up to the runtime."""
+
+    shots: Optional[int] = None
+    seed: Optional[int] = None
+    optimisation_level: Optional[int] = None
+    timeout: Optional[float] = None
+
+
+@dataclass
+class RasqalConfig:
+    """
+    Every option Rasqal has, built once and then applied to as many runners as needed.
+
+    Pass it into `RasqalRunner` on creation or call `RasqalRunner.with_config` on an existing one.
+    """
+
+    trace_graphs: bool = False
+    trace_projections: bool = False
+    trace_runtime: bool = False
+    trace_solver: bool = False
+
+    # File to write every trace event to as JSON lines.
+    trace_file: Optional[str] = None
+
+    step_count_limit: Optional[int] = None
+
+    # Seconds a single run is allowed to take.
+    time_limit: Optional[float] = None
+
+    activate_solver: bool = False
+
+    # Either 'entanglement' or 'mps'.
+    solver_strategy: Optional[str] = None
+    max_bond_dimension: Optional[int] = None
+
+    parallel_dispatch: bool = False
+    projection_splitting: bool = False
+
+    # Either 'majority', 'unanimous', 'per-shot' or 'error-on-ambiguity'.
+    result_reduction: Optional[str] = None
+
+    # Fraction of shots which need to agree when results error on ambiguity.
+    ambiguity_threshold: Optional[float] = None
+
+    # Either 'lowest-free', 'after-reset' or 'fixed'.
+    qubit_allocation: Optional[str] = None
+
+    # Most qubits live at once when allocation is fixed.
+    qubit_capacity: Optional[int] = None
+
+    cache_results: bool = False
+    cache_time_to_live: Optional[float] = None
+    cache_max_entries: Optional[int] = None
+    cache_directory: Optional[str] = None
+
+    execution_options: Optional[ExecutionSettings] = None
+
+    # Overrides of the execution options keyed by the order the projection was created in during a run.
+    projection_options: Dict[int, ExecutionSettings] = field(default_factory=dict)
+
+    def apply(self, executor):
+        """Sets every configured option on the native executor."""
+        if self.trace_graphs:
+            executor.trace_graphs()
+        if self.trace_projections:
+            executor.trace_projections()
+        if self.trace_runtime:
+            executor.trace_runtime()
+        if self.trace_solver:
+            executor.trace_solver()
+        if self.trace_file is not None:
+            executor.trace_to_file(self.trace_file)
+        if self.step_count_limit is not None:
+            executor.step_count_limit(self.step_count_limit)
+        if self.time_limit is not None:
+            executor.time_limit(self.time_limit)
+        if self.activate_solver:
+            executor.activate_solver()
+        if self.solver_strategy is not None:
+            executor.solver_strategy(self.solver_strategy)
+        if self.max_bond_dimension is not None:
+            executor.max_bond_dimension(self.max_bond_dimension)
+        if self.parallel_dispatch:
+            executor.parallel_dispatch()
+        if self.projection_splitting:
+            executor.projection_splitting()
+        if self.result_reduction is not None:
+            executor.result_reduction(self.result_reduction, self.ambiguity_threshold)
+        if self.qubit_allocation is not None:
+            executor.qubit_allocation(self.qubit_allocation, self.qubit_capacity)
+        if self.cache_results:
+            executor.cache_results(
+                self.cache_time_to_live, self.cache_max_entries, self.cache_directory
+            )
+        if self.execution_options is not None:
+            options = self.execution_options
+            executor.execution_options(
+                options.shots, options.seed, options.optimisation_level, options.timeout
+            )
+        for index, options in self.projection_options.items():
+            executor.projection_options(
+                index,
+                options.shots,
+                options.seed,
+                options.optimisation_level,
+                options.timeout,
+            )
@@ -0,0 +1,66 @@
+# SPDX-License-Identifier: BSD-3-Clause
+# Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+from typing import Any, Callable, Dict, List, Optional
+
+from .runtime import RasqalRunner
+
+
+class Debugger:
+    """
+    Step-through debugger for a runner. Execution stops at breakpoints, just before the node is executed, and
+    `on_stop` is called with a dictionary describing the state of the runtime at that point:
+
+    - reason: 'breakpoint' or 'step', and breakpoint: which breakpoint was hit.
+    - graph, node, instruction, step: the function, node order and instruction about to be executed, and how
+      many steps have been taken so far.
+    - origin: where the node came from, as the QIR function and block along with the source file, line and
+      column if the compiler emitted debug information. None for nodes the runtime made itself.
+    - call_stack: every function currently being executed, outermost first.
+    - variables: every variable in the current function, as strings. Quantum results that haven't been
+      executed yet show as deferred, and inspecting them won't execute anything.
+    - active_qubits: indexes of every qubit currently allocated.
+    - projections: every active projection, with its index, qubits, circuit so far and whether it's been executed.
+
+    `on_stop` returns what to do next: `Debugger.STEP` to stop again at the next node, `Debugger.CONTINUE` to run
+    until the next breakpoint (the default if nothing is returned), or `Debugger.ABORT` to stop the run, which
+    raises a ValueError. Every stop is also kept in `stops` for inspection after the run.
+    """
+
+    STEP = "step"
+    CONTINUE = "continue"
+    ABORT = "abort"
+
+    def __init__(
+        self,
+        runner: RasqalRunner,
+        on_stop: Optional[Callable[[Dict[str, Any]], Optional[str]]] = None,
+    ):
+        self.runner = runner
+        self.on_stop = on_stop
+        self.stops: List[Dict[str, Any]] = []
+        runner.executor.debug(self._stopped)
+
+    def break_at_node(self, graph: str, node: int) -> "Debugger":
+        """Stops at a node of a function, by its order. Orders are shown against each node when tracing graphs."""
+        self.runner.executor.break_at_node(graph, node)
+        return self
+
+    def break_at_label(self, label: str) -> "Debugger":
+        """Stops at every label with this name, which are the basic blocks of the QIR, in any function."""
+        self.runner.executor.break_at_label(label)
+        return self
+
+    def break_at_function(self, name: str) -> "Debugger":
+        """Stops at the start of every call to this function. Use the entry-point to step from the very start."""
+        self.runner.executor.break_at_function(name)
+        return self
+
+    def clear_breakpoints(self) -> "Debugger":
+        self.runner.executor.clear_breakpoints()
+        return self
+
+    def _stopped(self, stop: Dict[str, Any]) -> str:
+        self.stops.append(stop)
+        action = self.on_stop(stop) if self.on_stop is not None else None
+        return action or Debugger.CONTINUE
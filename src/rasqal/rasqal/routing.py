@@ -113,12 +113,12 @@ class TketRuntime(RuntimeAdaptor):
                 f"Invalid architecture or coupling mappings: {str(couplings)}"
             )
 
-    def execute(self, builder) -> Dict[str, int]:
+    def execute(self, builder, options) -> Dict[str, int]:
         builder: TketBuilder
 
         SequencePass([DefaultMappingPass(self.arch)]).apply(builder.circuit)
         self._apply_rebase(builder.circuit)
-        return self.forwarded.execute(self._forward_circuit(builder))
+        return self.forwarded.execute(self._forward_circuit(builder), options)
 
     def _apply_rebase(self, circuit):
         """Remaps the Tket circuit to the operations we parse via the builder APIs."""
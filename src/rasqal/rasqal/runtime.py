@@ -1,11 +1,10 @@
 # SPDX-License-Identifier: BSD-3-Clause
 # Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
-from os import remove
-from tempfile import NamedTemporaryFile
-from typing import Any, List, Union
+from typing import Any, Callable, Dict, List, Optional, Union
 
 from .adaptors import RuntimeAdaptor
+from .config import RasqalConfig
 from ._native import Executor
 
 
@@ -15,12 +14,23 @@ class RasqalRunner:
     from Python as well as utility and supporting methods.
     """
 
-    def __init__(self, runtime: Union[List[RuntimeAdaptor], RuntimeAdaptor]):
+    def __init__(
+        self,
+        runtime: Union[List[RuntimeAdaptor], RuntimeAdaptor],
+        config: Optional[RasqalConfig] = None,
+    ):
         if not isinstance(runtime, list):
             runtime = [runtime]
 
         self.runtimes: List[RuntimeAdaptor] = runtime
         self.executor = Executor()
+        if config is not None:
+            self.with_config(config)
+
+    def with_config(self, config: RasqalConfig) -> "RasqalRunner":
+        """Applies every option in this config to the runner."""
+        config.apply(self.executor)
+        return self
 
     def trace_graphs(self) -> "RasqalRunner":
         """
@@ -46,16 +56,91 @@ class RasqalRunner:
         self.executor.trace_runtime()
         return self
 
-    def run_ll(self, ll_string: str, args: List[Any] = None):
-        """Runs a .ll string. Creates temporary file and writes to it."""
-        # Need to set as string not bytes for encoding purposes.
-        with NamedTemporaryFile(suffix=".ll", delete=False, mode="w+") as fp:
-            fp.write(ll_string)
-            fp.close()
-            try:
-                return self.run(fp.name, args)
-            finally:
-                remove(fp.name)
+    def trace_solver(self) -> "RasqalRunner":
+        """
+        Activates solver logging.
+        Prints the solvers state as it analyses each circuit.
+        """
+        self.executor.trace_solver()
+        return self
+
+    def subscribe(self, callback: Callable[[Dict[str, Any]], None]) -> "RasqalRunner":
+        """
+        Calls `callback` with every trace event emitted while running, as a dictionary with its type under 'event'.
+
+        Events are node_entered, variable_changed, projection_created, projections_merged, gate_added,
+        solver_decision, projection_concretized, subgraph_called and subgraph_returned. Callbacks are called as things
+        happen, so should be quick, and any exception they raise is printed rather than stopping the run.
+        """
+        self.executor.subscribe(callback)
+        return self
+
+    def trace_to_file(self, file_path: str) -> "RasqalRunner":
+        """Writes every trace event emitted while running to this file, one JSON object per line."""
+        self.executor.trace_to_file(file_path)
+        return self
+
+    def activate_solver(self) -> "RasqalRunner":
+        """
+        Activates the circuit solver. Circuits are analysed before being executed, and if their results can be
+        computed precisely they won't be sent to the runtime at all.
+        """
+        self.executor.activate_solver()
+        return self
+
+    def solver_strategy(self, strategy: str) -> "RasqalRunner":
+        """Which solver is used when active, either 'entanglement' (the default) or 'mps'."""
+        self.executor.solver_strategy(strategy)
+        return self
+
+    def max_bond_dimension(self, max_bond_dimension: int) -> "RasqalRunner":
+        """Caps the bond dimension of the matrix-product-state solver, trading accuracy for speed and memory."""
+        self.executor.max_bond_dimension(max_bond_dimension)
+        return self
+
+    def parallel_dispatch(self) -> "RasqalRunner":
+        """
+        Splits circuits into their independent parts and dispatches them, and any other circuit needed at the same
+        time, concurrently across every capable runtime.
+        """
+        self.executor.parallel_dispatch()
+        return self
+
+    def projection_splitting(self) -> "RasqalRunner":
+        """
+        Only groups qubits into the same circuit once gates entangle them, splitting them back off when they're
+        reset, so independent parts of a program run as separate, smaller circuits.
+        """
+        self.executor.projection_splitting()
+        return self
+
+    def result_reduction(self, reduction: str, threshold: float = None) -> "RasqalRunner":
+        """
+        How measurement results are turned into a single classical value when the program branches on them.
+
+        'majority' (the default) picks whichever was seen most with ties going to zero, 'unanimous' fails unless
+        every shot agrees, 'per-shot' picks a single shot and follows it through the rest of the run, and
+        'error-on-ambiguity' fails unless at least `threshold` of the shots agree. Per-shot picks are seeded from
+        the execution options seed, if set.
+        """
+        self.executor.result_reduction(reduction, threshold)
+        return self
+
+    def qubit_allocation(self, allocation: str, capacity: int = None) -> "RasqalRunner":
+        """
+        How qubits the program releases are reused when it allocates more.
+
+        'lowest-free' (the default) hands out the lowest index not in use, 'after-reset' only reuses qubits the
+        program reset itself before releasing them, and 'fixed' reuses like 'lowest-free' but fails the run if
+        more than `capacity` qubits are live at once. Circuits are compacted before they're sent, so ask backends
+        for as many qubits as they have live at once rather than the highest index they use.
+        """
+        self.executor.qubit_allocation(allocation, capacity)
+        return self
+
+    def run_ll(self, ll_string: str, args: Union[List[Any], Dict[str, Any]] = None):
+        """Runs a .ll string. Parsed directly from memory, nothing is written to disk."""
+        return self.executor.run_ll(ll_string, args or [], self.runtimes)
 
     def step_count_limit(self, step_count: int) -> "RasqalRunner":
         """
@@ -71,20 +156,137 @@ class RasqalRunner:
         self.executor.step_count_limit(step_count)
         return self
 
-    def run_bitcode(self, bitcode: bytes, args: List[Any] = None):
-        """Runs LLVM bitcode when passed as bytes. Creates temporary file and writes to it."""
-        with NamedTemporaryFile(suffix=".bc", delete=False) as fp:
-            fp.write(bitcode)
-            fp.close()
-            try:
-                return self.run(fp.name, args)
-            finally:
-                remove(fp.name)
+    def time_limit(self, seconds: float) -> "RasqalRunner":
+        """
+        Sets a limit on how long, in seconds, a single run can take before it errors out.
+
+        Only checked between the steps the symbolic executor takes, so a circuit which is already running on a
+        backend will be waited on. Raises a ValueError if it isn't a valid number of seconds.
+        """
+        self.executor.time_limit(seconds)
+        return self
+
+    def statistics(self) -> Optional[Dict[str, Any]]:
+        """
+        Statistics about the last run, or every run in the last batch added together. None if it failed.
+
+        Includes steps taken (overall and per method), time spent in the runtime, solver and waiting on QPUs
+        (in seconds), how many projections were created and concretized, solver hit rate, cache hits and the
+        width, depth and gate counts of every circuit built.
+        """
+        return self.executor.statistics()
+
+    def cancel(self):
+        """
+        Stops the run currently in progress, such as from another thread, which will then raise a ValueError.
+        A KeyboardInterrupt will also stop a run, and is raised as-is.
+        """
+        self.executor.cancel()
+
+    def cache_results(
+        self, time_to_live: float = None, max_entries: int = None, directory: str = None
+    ) -> "RasqalRunner":
+        """
+        Caches the results of every circuit executed by this runner, so identical circuits sent to the same
+        sort of runtime aren't re-submitted in subsequent runs.
+
+        Results can expire after `time_to_live` seconds and the cache can be limited to `max_entries`, evicting the
+        oldest first. If a `directory` is passed results are also written there, so they can be shared across
+        processes.
+        """
+        self.executor.cache_results(time_to_live, max_entries, directory)
+        return self
+
+    def execution_options(
+        self,
+        shots: int = None,
+        seed: int = None,
+        optimisation_level: int = None,
+        timeout: float = None,
+    ) -> "RasqalRunner":
+        """
+        Sets the options passed to the runtime alongside every circuit, with `timeout` in seconds.
+        Anything not set is left up to the runtime.
+        """
+        self.executor.execution_options(shots, seed, optimisation_level, timeout)
+        return self
+
+    def projection_options(
+        self,
+        index: int,
+        shots: int = None,
+        seed: int = None,
+        optimisation_level: int = None,
+        timeout: float = None,
+    ) -> "RasqalRunner":
+        """
+        Overrides execution options for a single projection, with `index` being the order it was created in
+        during a run starting from 0. Useful when a particular conditional needs more statistics to be accurate.
+        """
+        self.executor.projection_options(
+            index, shots, seed, optimisation_level, timeout
+        )
+        return self
+
+    def run_bitcode(
+        self, bitcode: bytes, args: Union[List[Any], Dict[str, Any]] = None
+    ):
+        """Runs LLVM bitcode when passed as bytes. Parsed directly from memory, nothing is written to disk."""
+        return self.executor.run_bitcode(bitcode, args or [], self.runtimes)
 
-    def run(self, file_path: str, args: List[Any] = None):
+    def run(self, file_path: str, args: Union[List[Any], Dict[str, Any]] = None):
         """
         Runs an .ll or .bc file with the passed-in arguments.
         Arguments can only be Python primitives or otherwise easily transformable to Rust objects.
+
+        Arguments can be passed positionally as a list or by name as a dict, with names matching the
+        entry-points parameters (with or without the leading '%'). Missing, unknown or mistyped arguments
+        raise a ValueError.
         """
         results = self.executor.run_with_args(file_path, args or [], self.runtimes)
         return results
+
+    def run_files(
+        self,
+        file_paths: List[str],
+        args: Union[List[Any], Dict[str, Any]] = None,
+        entry_point: str = None,
+    ):
+        """
+        Links several .ll or .bc files together and runs the result, for programs split across modules such as
+        a library of shared operations plus a per-job entry module. If there are multiple entry-points across the
+        files, `entry_point` picks which one to use.
+        """
+        graph = self.executor.parse_files(file_paths, entry_point)
+        return self.executor.run_graph(graph, args or [], self.runtimes)
+
+    def run_trajectories(
+        self,
+        file_path: str,
+        shots: int,
+        args: Union[List[Any], Dict[str, Any]] = None,
+        entry_point: str = None,
+    ) -> Dict[str, int]:
+        """
+        Runs an .ll or .bc file once per shot on the built-in simulator rather than this runners runtimes,
+        returning how often each result came up.
+
+        Every measurement that gets branched on or read picks a single outcome and sticks to it, the same as
+        feed-forward on hardware, so this is a reference for what programs like teleportation should return.
+        Shots are seeded from the execution options seed, if set.
+        """
+        graph = self.executor.parse_file(file_path, entry_point)
+        return self.executor.run_trajectories(graph, args or [], shots)
+
+    def run_batch(
+        self, file_path: str, argument_sets: List[Union[List[Any], Dict[str, Any]]]
+    ) -> List[Any]:
+        """
+        Runs an .ll or .bc file once for every set of arguments, returning a list of results in the same order.
+        Each set can be positional or named, same as `run`.
+
+        The file is only parsed once, so this is much faster than repeated calls to `run` for parameter sweeps. With
+        `cache_results` any quantum circuit which doesn't change between argument sets is also only executed once.
+        """
+        graph = self.executor.parse_file(file_path, None)
+        return self.executor.run_graph_batch(graph, argument_sets, self.runtimes)
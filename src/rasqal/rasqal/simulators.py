@@ -70,7 +70,7 @@ class QASMRuntime(RuntimeAdaptor):
     def __init__(self, qubit_count=30):
         self.qubit_count = qubit_count
 
-    def execute(self, builder: QASMBuilder) -> Dict[str, int]:
+    def execute(self, builder: QASMBuilder, options) -> Dict[str, int]:
         aer_config = QasmBackendConfiguration.from_dict(
             AerSimulator._DEFAULT_CONFIGURATION
         )
@@ -80,8 +80,12 @@ class QASMRuntime(RuntimeAdaptor):
         circuit = builder.circuit
         # TODO: Needs a more nuanced try/catch. Some exceptions we should catch, others we should re-throw.
         try:
-            job = qasm_sim.run(transpile(circuit, qasm_sim), shots=builder.shot_count)
-            results = job.result()
+            shots = options.shots or builder.shot_count
+            circuit = transpile(
+                circuit, qasm_sim, optimization_level=options.optimisation_level
+            )
+            job = qasm_sim.run(circuit, shots=shots, seed_simulator=options.seed)
+            results = job.result(timeout=options.timeout)
             distribution = results.get_counts()  # Used to pass in circuit, check.
         except QiskitError as e:
             raise ValueError(f"Error while attempting to build/run circuit: {str(e)}")
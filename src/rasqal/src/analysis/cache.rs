@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::projections::AnalysisResult;
+use crate::config::{ExecutionOptions, RasqalConfig};
+use log::{log, Level};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+const RESULT_EXTENSION: &str = "result";
+
+/// Canonical text of a circuit, the target it ran against and the options that affect its
+/// results, along with its hash. Uses FNV-1a rather than the standard hasher as hashes name the
+/// files of on-disk caches, so need to be stable across processes. Keys compare on their text as
+/// well, so circuits whose hashes collide are never served each others results.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+  hash: u64,
+  text: String
+}
+
+impl CacheKey {
+  pub fn new<T: ToString>(operations: &[T], runtime: &str, options: &ExecutionOptions) -> CacheKey {
+    let mut text = String::new();
+    let mut write = |value: &str| {
+      text.push_str(value);
+      text.push('\n');
+    };
+
+    // Timeouts don't change what gets returned, so aren't part of the key.
+    let optional =
+      |value: Option<i64>| value.map_or(String::from("default"), |val| val.to_string());
+    write(runtime);
+    write(&optional(options.shots));
+    write(&optional(options.seed));
+    write(&optional(options.optimisation_level));
+    for op in operations {
+      write(&op.to_string());
+    }
+    CacheKey::from_text(text)
+  }
+
+  fn from_text(text: String) -> CacheKey {
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in text.bytes() {
+      hash ^= u64::from(byte);
+      hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    CacheKey { hash, text }
+  }
+
+  fn file_name(&self) -> String { format!("{:016x}.{RESULT_EXTENSION}", self.hash) }
+}
+
+struct CacheEntry {
+  result: AnalysisResult,
+  created: SystemTime
+}
+
+/// Results of previously-executed circuits keyed by the circuit and target that produced them, so
+/// identical circuits aren't sent to a QPU more than once.
+///
+/// Results can be mirrored into a directory so they survive across processes. How long they're
+/// kept and how many of them is up to the config of whichever execution is using the cache.
+#[derive(Default)]
+pub struct ProjectionCache {
+  results: HashMap<CacheKey, CacheEntry>,
+  directory: Option<PathBuf>,
+  hits: usize
+}
+
+impl ProjectionCache {
+  pub fn new() -> ProjectionCache { ProjectionCache::default() }
+
+  /// Persists results into this directory, loading any still-valid results already there.
+  pub fn with_directory(mut self, directory: &Path) -> ProjectionCache {
+    if let Err(err) = fs::create_dir_all(directory) {
+      log!(
+        Level::Warn,
+        "Unable to create result cache at {}: {}",
+        directory.display(),
+        err
+      );
+      return self;
+    }
+
+    self.directory = Some(directory.to_path_buf());
+    if let Ok(files) = fs::read_dir(directory) {
+      for path in files.filter_map(|val| val.ok()).map(|val| val.path()) {
+        if path.extension().and_then(|val| val.to_str()) != Some(RESULT_EXTENSION) {
+          continue;
+        }
+
+        let entry = fs::read_to_string(&path)
+          .ok()
+          .and_then(|text| ProjectionCache::deserialize(&text));
+        if let Some((key, entry)) = entry {
+          self.results.insert(key, entry);
+        }
+      }
+    }
+    self
+  }
+
+  pub fn get(&mut self, key: &CacheKey, config: &RasqalConfig) -> Option<AnalysisResult> {
+    if self
+      .results
+      .get(key)
+      .is_some_and(|entry| ProjectionCache::is_expired(entry, config))
+    {
+      self.remove(key);
+    }
+
+    let result = self.results.get(key).map(|entry| entry.result.clone());
+    if result.is_some() {
+      self.hits += 1;
+    }
+    result
+  }
+
+  pub fn insert(&mut self, key: CacheKey, result: &AnalysisResult, config: &RasqalConfig) {
+    let entry = CacheEntry {
+      result: result.clone(),
+      created: SystemTime::now()
+    };
+
+    if let Some(directory) = self.directory.as_ref() {
+      let path = directory.join(key.file_name());
+      if let Err(err) = fs::write(&path, ProjectionCache::serialize(&key, &entry)) {
+        log!(
+          Level::Warn,
+          "Unable to write cached result to {}: {}",
+          path.display(),
+          err
+        );
+      }
+    }
+
+    self.results.insert(key, entry);
+    self.enforce_limit(config);
+  }
+
+  /// How many times a result has been served from this cache.
+  pub fn hits(&self) -> usize { self.hits }
+
+  pub fn len(&self) -> usize { self.results.len() }
+
+  pub fn is_empty(&self) -> bool { self.results.is_empty() }
+
+  fn is_expired(entry: &CacheEntry, config: &RasqalConfig) -> bool {
+    config.result_cache_time_to_live.is_some_and(|ttl| {
+      entry
+        .created
+        .elapsed()
+        .map_or(false, |elapsed| elapsed > ttl)
+    })
+  }
+
+  fn remove(&mut self, key: &CacheKey) {
+    self.results.remove(key);
+    if let Some(directory) = self.directory.as_ref() {
+      let _ = fs::remove_file(directory.join(key.file_name()));
+    }
+  }
+
+  /// Evicts the oldest entries until we're within the configured size limit.
+  fn enforce_limit(&mut self, config: &RasqalConfig) {
+    let Some(max_entries) = config.result_cache_max_entries else {
+      return;
+    };
+
+    if self.results.len() <= max_entries {
+      return;
+    }
+
+    let mut by_age = self
+      .results
+      .iter()
+      .map(|(key, entry)| (key.clone(), entry.created))
+      .collect::<Vec<_>>();
+    by_age.sort_by(|(_, left), (_, right)| left.cmp(right));
+    let excess = self.results.len() - max_entries;
+    for (key, _) in by_age.into_iter().take(excess) {
+      self.remove(&key);
+    }
+  }
+
+  /// Simple text format: creation time in seconds since the epoch, how many lines of key text
+  /// follow, the key text itself, then one line per result.
+  fn serialize(key: &CacheKey, entry: &CacheEntry) -> String {
+    let created = entry
+      .created
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |val| val.as_secs());
+    let mut text = format!("{created}\n{}\n{}", key.text.lines().count(), key.text);
+    for (key, count) in entry.result.distribution.iter() {
+      text.push_str(format!("{key} {count}\n").as_str());
+    }
+    text
+  }
+
+  fn deserialize(text: &str) -> Option<(CacheKey, CacheEntry)> {
+    let mut lines = text.lines();
+    let created = UNIX_EPOCH + Duration::from_secs(lines.next()?.trim().parse().ok()?);
+    let key_lines = lines.next()?.trim().parse::<usize>().ok()?;
+    let mut key = String::new();
+    for _ in 0..key_lines {
+      key.push_str(lines.next()?);
+      key.push('\n');
+    }
+
+    let mut distribution = HashMap::new();
+    for line in lines.filter(|val| !val.trim().is_empty()) {
+      let (key, count) = line.split_once(' ')?;
+      distribution.insert(key.to_string(), count.trim().parse().ok()?);
+    }
+
+    Some((CacheKey::from_text(key), CacheEntry {
+      result: AnalysisResult::new(distribution),
+      created
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::cache::{CacheKey, ProjectionCache};
+  use crate::analysis::projections::AnalysisResult;
+  use crate::config::{ExecutionOptions, RasqalConfig};
+  use std::collections::HashMap;
+  use std::fs;
+  use std::thread::sleep;
+  use std::time::Duration;
+
+  fn result(key: &str) -> AnalysisResult {
+    AnalysisResult::new(HashMap::from([(key.to_string(), 1024)]))
+  }
+
+  fn key(op: &str) -> CacheKey {
+    CacheKey::new(&[op, "measure 0"], "mock", &ExecutionOptions::new())
+  }
+
+  #[test]
+  fn key_covers_target() {
+    let ops = ["X[qb[0]] 3.14", "measure 0"];
+    let options = ExecutionOptions::new();
+    assert_eq!(
+      CacheKey::new(&ops, "mock", &options),
+      CacheKey::new(&ops, "mock", &options)
+    );
+    assert_ne!(
+      CacheKey::new(&ops, "mock", &options),
+      CacheKey::new(&ops, "other", &options)
+    );
+    assert_ne!(
+      CacheKey::new(&ops, "mock", &options),
+      CacheKey::new(&ops, "mock", &ExecutionOptions::new().with_shots(100))
+    );
+    assert_eq!(
+      CacheKey::new(&ops, "mock", &options),
+      CacheKey::new(
+        &ops,
+        "mock",
+        &ExecutionOptions::new().with_timeout(Duration::from_secs(1))
+      )
+    );
+  }
+
+  /// Key with the same hash as this one but a different circuit behind it.
+  fn colliding(key: &CacheKey) -> CacheKey {
+    CacheKey {
+      hash: key.hash,
+      text: String::from("Y[qb[0]] 3.14\n")
+    }
+  }
+
+  #[test]
+  fn limits() {
+    let config = RasqalConfig::default().with_result_cache_limits(None, Some(2));
+    let mut cache = ProjectionCache::new();
+    cache.insert(key("X"), &result("1"), &config);
+    sleep(Duration::from_millis(5));
+    cache.insert(key("Y"), &result("1"), &config);
+    sleep(Duration::from_millis(5));
+    cache.insert(key("Z"), &result("0"), &config);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&key("X"), &config).is_none());
+    assert!(cache.get(&key("Z"), &config).is_some());
+    assert_eq!(cache.hits(), 1);
+
+    let config =
+      RasqalConfig::default().with_result_cache_limits(Some(Duration::from_millis(10)), None);
+    let mut cache = ProjectionCache::new();
+    cache.insert(key("X"), &result("1"), &config);
+    sleep(Duration::from_millis(20));
+    assert!(cache.get(&key("X"), &config).is_none());
+    assert!(cache.is_empty());
+  }
+
+  #[test]
+  fn collisions() {
+    let config = RasqalConfig::default();
+    let mut cache = ProjectionCache::new();
+    cache.insert(key("X"), &result("1"), &config);
+    assert!(cache.get(&colliding(&key("X")), &config).is_none());
+    assert!(cache.get(&key("X"), &config).is_some());
+  }
+
+  #[test]
+  fn persisted() {
+    let config = RasqalConfig::default();
+    let directory = std::env::temp_dir().join(format!("rasqal-cache-{}", std::process::id()));
+    let mut cache = ProjectionCache::new().with_directory(&directory);
+    cache.insert(key("X"), &result("01"), &config);
+
+    // Keys are checked against the circuit stored alongside the result, not just the file name.
+    let mut reloaded = ProjectionCache::new().with_directory(&directory);
+    assert!(reloaded.get(&colliding(&key("X")), &config).is_none());
+    let cached = reloaded
+      .get(&key("X"), &config)
+      .expect("Result should be on disk.");
+    assert_eq!(cached.distribution.get("01"), Some(&1024));
+    fs::remove_dir_all(directory).unwrap();
+  }
+}
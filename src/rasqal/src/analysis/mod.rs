@@ -1,2 +1,5 @@
+pub mod cache;
+pub mod mps;
 pub mod projections;
+pub mod sampling;
 pub mod solver;
@@ -0,0 +1,779 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::solver::{CircuitSolver, SolverResult};
+use crate::hardware::Qubit;
+use crate::runtime::{ActiveTracers, TracingModule};
+use crate::smart_pointers::Ptr;
+use crate::with_mutable_self;
+use log::{log, Level};
+use ndarray::{Array2, Array3};
+use num_complex::{Complex, Complex64};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+
+/// Singular values below this (relative to the largest) are considered zero and dropped.
+const SINGULAR_VALUE_CUTOFF: f64 = 1e-12;
+
+/// Any partial bitstring with a probability lower than this is not explored further.
+const PROBABILITY_CUTOFF: f64 = 1e-6;
+
+/// Maximum number of Jacobi sweeps before we accept whatever decomposition we have.
+const MAX_SWEEPS: usize = 64;
+
+fn czero() -> Complex64 { Complex::new(0., 0.) }
+
+fn cone() -> Complex64 { Complex::new(1., 0.) }
+
+#[rustfmt::skip]
+fn rx(radians: &f64) -> Array2<Complex64> {
+  let (cos, sin) = ((radians / 2.).cos(), (radians / 2.).sin());
+  Array2::from_shape_vec((2, 2), vec![
+    Complex::new(cos, 0.), Complex::new(0., -sin),
+    Complex::new(0., -sin), Complex::new(cos, 0.)
+  ]).unwrap()
+}
+
+#[rustfmt::skip]
+fn ry(radians: &f64) -> Array2<Complex64> {
+  let (cos, sin) = ((radians / 2.).cos(), (radians / 2.).sin());
+  Array2::from_shape_vec((2, 2), vec![
+    Complex::new(cos, 0.), Complex::new(-sin, 0.),
+    Complex::new(sin, 0.), Complex::new(cos, 0.)
+  ]).unwrap()
+}
+
+#[rustfmt::skip]
+fn rz(radians: &f64) -> Array2<Complex64> {
+  Array2::from_shape_vec((2, 2), vec![
+    Complex::from_polar(1., -radians / 2.), czero(),
+    czero(), Complex::from_polar(1., radians / 2.)
+  ]).unwrap()
+}
+
+/// Rotations applied under control are phase-corrected so that a rotation of PI is exactly
+/// the Pauli gate, otherwise the rotations global phase becomes a relative one on the controls.
+fn controlled_form(rotation: Array2<Complex64>, radians: &f64) -> Array2<Complex64> {
+  rotation * Complex::from_polar(1., radians / 2.)
+}
+
+#[rustfmt::skip]
+fn swap_matrix() -> Array2<Complex64> {
+  let mut matrix = Array2::zeros((4, 4));
+  matrix[[0, 0]] = cone();
+  matrix[[1, 2]] = cone();
+  matrix[[2, 1]] = cone();
+  matrix[[3, 3]] = cone();
+  matrix
+}
+
+/// Builds the full matrix for a gate acting on `width` contiguous sites where `target` is the
+/// offset the rotation is applied to and everything in `controls` needs to be 1 for it to apply.
+/// Basis ordering is big-endian, the first site is the most significant bit.
+fn controlled_matrix(
+  width: usize, controls: &Vec<usize>, target: usize, gate: &Array2<Complex64>
+) -> Array2<Complex64> {
+  let dimension = 1 << width;
+  let bit_of = |basis: usize, offset: usize| (basis >> (width - 1 - offset)) & 1;
+  let mut matrix = Array2::zeros((dimension, dimension));
+  for column in 0..dimension {
+    if controls.iter().all(|offset| bit_of(column, *offset) == 1) {
+      let target_bit = bit_of(column, target);
+      let target_mask = 1 << (width - 1 - target);
+      for output_bit in 0..2 {
+        let row = (column & !target_mask) | (output_bit << (width - 1 - target));
+        matrix[[row, column]] = gate[[output_bit, target_bit]];
+      }
+    } else {
+      matrix[[column, column]] = cone();
+    }
+  }
+  matrix
+}
+
+/// Singular value decomposition via one-sided Jacobi rotations. Returns U, the singular values in
+/// descending order and V^H, with the rank matching the smaller of the two dimensions.
+fn svd(matrix: &Array2<Complex64>) -> (Array2<Complex64>, Vec<f64>, Array2<Complex64>) {
+  let (rows, columns) = matrix.dim();
+  if columns > rows {
+    // Decompose the conjugate transpose instead so we're always orthogonalizing the smaller side.
+    let (u, values, vh) = svd(&matrix.t().mapv(|val| val.conj()));
+    return (
+      vh.t().mapv(|val| val.conj()),
+      values,
+      u.t().mapv(|val| val.conj())
+    );
+  }
+
+  let mut work = matrix.clone();
+  let mut v: Array2<Complex64> = Array2::eye(columns);
+  for _ in 0..MAX_SWEEPS {
+    let mut rotated = false;
+    for p in 0..columns {
+      for q in (p + 1)..columns {
+        let mut alpha = 0.;
+        let mut beta = 0.;
+        let mut gamma = czero();
+        for row in 0..rows {
+          alpha += work[[row, p]].norm_sqr();
+          beta += work[[row, q]].norm_sqr();
+          gamma += work[[row, p]].conj() * work[[row, q]];
+        }
+
+        let magnitude = gamma.norm();
+        if magnitude <= 1e-15 * (alpha * beta).sqrt() || magnitude < f64::MIN_POSITIVE {
+          continue;
+        }
+        rotated = true;
+
+        // Phase the second column so the overlap is real, then it's a standard Jacobi rotation.
+        let phase = (gamma / magnitude).conj();
+        let zeta = (beta - alpha) / (2. * magnitude);
+        let tangent = zeta.signum() / (zeta.abs() + (1. + zeta * zeta).sqrt());
+        let cos = 1. / (1. + tangent * tangent).sqrt();
+        let sin = cos * tangent;
+
+        for row in 0..rows {
+          let (left, right) = (work[[row, p]], work[[row, q]] * phase);
+          work[[row, p]] = left * cos - right * sin;
+          work[[row, q]] = left * sin + right * cos;
+        }
+
+        for row in 0..columns {
+          let (left, right) = (v[[row, p]], v[[row, q]] * phase);
+          v[[row, p]] = left * cos - right * sin;
+          v[[row, q]] = left * sin + right * cos;
+        }
+      }
+    }
+
+    if !rotated {
+      break;
+    }
+  }
+
+  let mut values = (0..columns)
+    .map(|column| {
+      (
+        column,
+        (0..rows)
+          .map(|row| work[[row, column]].norm_sqr())
+          .sum::<f64>()
+          .sqrt()
+      )
+    })
+    .collect::<Vec<_>>();
+  values.sort_by(|left, right| right.1.total_cmp(&left.1));
+
+  let mut u = Array2::zeros((rows, columns));
+  let mut vh = Array2::zeros((columns, columns));
+  for (new_index, (old_index, value)) in values.iter().enumerate() {
+    if *value > f64::MIN_POSITIVE {
+      for row in 0..rows {
+        u[[row, new_index]] = work[[row, *old_index]] / *value;
+      }
+    }
+
+    for column in 0..columns {
+      vh[[new_index, column]] = v[[column, *old_index]].conj();
+    }
+  }
+
+  (u, values.into_iter().map(|val| val.1).collect(), vh)
+}
+
+/// The actual tensor chain. Sites are identified by wires, which allows qubits to move around the
+/// chain when they need to be brought adjacent for multi-qubit operations.
+struct TensorChain {
+  /// Site tensors indexed by [left bond, physical, right bond].
+  sites: Vec<Array3<Complex64>>,
+
+  /// Position of each wire in the chain.
+  positions: Vec<usize>,
+
+  /// Which wire currently lives at each position.
+  wires: Vec<usize>,
+
+  /// Wire currently representing each qubit.
+  qubits: HashMap<i64, usize>,
+
+  /// Wire holding the classical copy of each measure, in the order they were made.
+  measures: Vec<usize>,
+
+  max_bond_dimension: usize,
+
+  /// Total weight of all singular values that were discarded due to the bond cap.
+  discarded_weight: f64
+}
+
+impl TensorChain {
+  fn new(max_bond_dimension: usize) -> TensorChain {
+    TensorChain {
+      sites: Vec::new(),
+      positions: Vec::new(),
+      wires: Vec::new(),
+      qubits: HashMap::new(),
+      measures: Vec::new(),
+      max_bond_dimension: max_bond_dimension.max(1),
+      discarded_weight: 0.
+    }
+  }
+
+  /// Appends a fresh site in the zero state to the end of the chain and returns its wire.
+  fn add_wire(&mut self) -> usize {
+    let mut tensor = Array3::zeros((1, 2, 1));
+    tensor[[0, 0, 0]] = cone();
+    self.sites.push(tensor);
+
+    let wire = self.positions.len();
+    self.positions.push(self.sites.len() - 1);
+    self.wires.push(wire);
+    wire
+  }
+
+  fn wire_for(&mut self, qb: &Qubit) -> usize {
+    if let Some(wire) = self.qubits.get(&qb.index) {
+      *wire
+    } else {
+      let wire = self.add_wire();
+      self.qubits.insert(qb.index, wire);
+      wire
+    }
+  }
+
+  fn bond_dimension(&self) -> usize { self.sites.iter().map(|val| val.dim().2).max().unwrap_or(1) }
+
+  fn apply_single(&mut self, wire: usize, gate: &Array2<Complex64>) {
+    let position = self.positions[wire];
+    let site = &self.sites[position];
+    let (left, _, right) = site.dim();
+    let mut result = Array3::zeros((left, 2, right));
+    for l in 0..left {
+      for r in 0..right {
+        for out in 0..2 {
+          result[[l, out, r]] = gate[[out, 0]] * site[[l, 0, r]] + gate[[out, 1]] * site[[l, 1, r]];
+        }
+      }
+    }
+    self.sites[position] = result;
+  }
+
+  /// Contracts `width` sites from `start` into one tensor, applies the gate then splits it back
+  /// apart, truncating each bond to our maximum dimension.
+  fn apply_block(&mut self, start: usize, width: usize, gate: &Array2<Complex64>) {
+    let mut theta = self.sites[start].clone();
+    for position in (start + 1)..(start + width) {
+      let site = &self.sites[position];
+      let (left, physical, bond) = theta.dim();
+      let right = site.dim().2;
+      let mut merged = Array3::zeros((left, physical * 2, right));
+      for l in 0..left {
+        for p in 0..physical {
+          for b in 0..2 {
+            for r in 0..right {
+              let mut sum = czero();
+              for m in 0..bond {
+                sum += theta[[l, p, m]] * site[[m, b, r]];
+              }
+              merged[[l, p * 2 + b, r]] = sum;
+            }
+          }
+        }
+      }
+      theta = merged;
+    }
+
+    let (left, physical, right) = theta.dim();
+    let mut applied = Array3::zeros((left, physical, right));
+    for l in 0..left {
+      for r in 0..right {
+        for out in 0..physical {
+          let mut sum = czero();
+          for inp in 0..physical {
+            sum += gate[[out, inp]] * theta[[l, inp, r]];
+          }
+          applied[[l, out, r]] = sum;
+        }
+      }
+    }
+    theta = applied;
+
+    for position in start..(start + width - 1) {
+      let (left, physical, right) = theta.dim();
+      let remainder = physical / 2;
+      let mut matrix = Array2::zeros((left * 2, remainder * right));
+      for l in 0..left {
+        for p in 0..physical {
+          for r in 0..right {
+            matrix[[l * 2 + p / remainder, (p % remainder) * right + r]] = theta[[l, p, r]];
+          }
+        }
+      }
+
+      let (u, values, vh) = svd(&matrix);
+      let largest = values.first().copied().unwrap_or(0.);
+      let mut kept = values
+        .iter()
+        .take_while(|val| **val > largest * SINGULAR_VALUE_CUTOFF)
+        .count()
+        .max(1);
+      if kept > self.max_bond_dimension {
+        self.discarded_weight += values[self.max_bond_dimension..kept]
+          .iter()
+          .map(|val| val * val)
+          .sum::<f64>();
+        kept = self.max_bond_dimension;
+      }
+
+      let mut site = Array3::zeros((left, 2, kept));
+      for l in 0..left {
+        for b in 0..2 {
+          for k in 0..kept {
+            site[[l, b, k]] = u[[l * 2 + b, k]];
+          }
+        }
+      }
+      self.sites[position] = site;
+
+      let mut next = Array3::zeros((kept, remainder, right));
+      for k in 0..kept {
+        for p in 0..remainder {
+          for r in 0..right {
+            next[[k, p, r]] = vh[[k, p * right + r]] * values[k];
+          }
+        }
+      }
+      theta = next;
+    }
+
+    self.sites[start + width - 1] = theta;
+  }
+
+  /// Swaps the sites at this position and the one after it, wires included.
+  fn swap_adjacent(&mut self, position: usize) {
+    self.apply_block(position, 2, &swap_matrix());
+    let (left_wire, right_wire) = (self.wires[position], self.wires[position + 1]);
+    self.wires.swap(position, position + 1);
+    self.positions[left_wire] = position + 1;
+    self.positions[right_wire] = position;
+  }
+
+  /// Applies a rotation to the target wire, conditional on all control wires. Wires are moved
+  /// next to each other first and are left in their new positions afterwards.
+  fn apply_controlled(&mut self, controls: &Vec<usize>, target: usize, gate: &Array2<Complex64>) {
+    let mut involved = controls.clone();
+    involved.push(target);
+    involved.sort_by_key(|wire| self.positions[*wire]);
+    involved.dedup();
+
+    // Gather everything next to the lowest wire.
+    let start = self.positions[involved[0]];
+    for (offset, wire) in involved.iter().enumerate() {
+      while self.positions[*wire] > start + offset {
+        let position = self.positions[*wire];
+        self.swap_adjacent(position - 1);
+      }
+    }
+
+    let control_offsets = controls
+      .iter()
+      .map(|wire| self.positions[*wire] - start)
+      .collect::<Vec<_>>();
+    let target_offset = self.positions[target] - start;
+    let matrix = controlled_matrix(involved.len(), &control_offsets, target_offset, gate);
+    self.apply_block(start, involved.len(), &matrix);
+  }
+
+  /// Contracts a single site into a transfer environment. If `bit` is set the physical index is
+  /// projected onto it, otherwise it's summed over.
+  fn extend_left(
+    environment: &Array2<Complex64>, site: &Array3<Complex64>, bit: Option<usize>
+  ) -> Array2<Complex64> {
+    let (left, _, right) = site.dim();
+    let bits = bit.map_or(vec![0, 1], |val| vec![val]);
+    let mut result = Array2::zeros((right, right));
+    for r in 0..right {
+      for r_prime in 0..right {
+        let mut sum = czero();
+        for b in bits.iter() {
+          for l in 0..left {
+            for l_prime in 0..left {
+              sum +=
+                site[[l, *b, r]].conj() * environment[[l, l_prime]] * site[[l_prime, *b, r_prime]];
+            }
+          }
+        }
+        result[[r, r_prime]] = sum;
+      }
+    }
+    result
+  }
+
+  /// Environments of every suffix of the chain with all physical indexes summed over.
+  fn right_environments(&self) -> Vec<Array2<Complex64>> {
+    let mut environments = vec![Array2::from_elem((1, 1), cone()); self.sites.len() + 1];
+    for position in (0..self.sites.len()).rev() {
+      let site = &self.sites[position];
+      let previous = &environments[position + 1];
+      let (left, _, right) = site.dim();
+      let mut result = Array2::zeros((left, left));
+      for l in 0..left {
+        for l_prime in 0..left {
+          let mut sum = czero();
+          for b in 0..2 {
+            for r in 0..right {
+              for r_prime in 0..right {
+                sum +=
+                  site[[l, b, r]].conj() * site[[l_prime, b, r_prime]] * previous[[r, r_prime]];
+              }
+            }
+          }
+          result[[l, l_prime]] = sum;
+        }
+      }
+      environments[position] = result;
+    }
+    environments
+  }
+
+  fn close(left: &Array2<Complex64>, right: &Array2<Complex64>) -> f64 {
+    let mut sum = czero();
+    for (key, value) in left.indexed_iter() {
+      sum += value * right[key];
+    }
+    sum.re
+  }
+
+  /// Enumerates every measured outcome above our probability cutoff. Bitstrings have one bit per
+  /// measure, in the order they were made.
+  fn distribution(&self) -> Vec<SolverResult> {
+    if self.measures.is_empty() {
+      return Vec::new();
+    }
+
+    let mut register = self
+      .measures
+      .iter()
+      .enumerate()
+      .map(|(measure, wire)| (self.positions[*wire], measure))
+      .collect::<Vec<_>>();
+    register.sort();
+
+    let right_environments = self.right_environments();
+    let norm = TensorChain::close(&Array2::from_elem((1, 1), cone()), &right_environments[0]);
+    if norm <= f64::MIN_POSITIVE {
+      return Vec::new();
+    }
+
+    let mut results = Vec::new();
+    let mut stack = vec![(0, 0, Array2::from_elem((1, 1), cone()), HashMap::new())];
+    while let Some((register_index, position, environment, assigned)) = stack.pop() {
+      if register_index == register.len() {
+        let bitstring = (0..self.measures.len())
+          .map(|measure| assigned[&measure].to_string())
+          .collect::<String>();
+
+        let probability = TensorChain::close(&environment, &right_environments[position]) / norm;
+        results.push(SolverResult {
+          bitstring,
+          probability: probability * 100.
+        });
+        continue;
+      }
+
+      // Fold every unmeasured site up until our next register entry into the environment.
+      let (target_position, measure) = register[register_index];
+      let mut open = environment;
+      for unmeasured in position..target_position {
+        open = TensorChain::extend_left(&open, &self.sites[unmeasured], None);
+      }
+
+      for bit in 0..2 {
+        let fixed = TensorChain::extend_left(&open, &self.sites[target_position], Some(bit));
+        let probability =
+          TensorChain::close(&fixed, &right_environments[target_position + 1]) / norm;
+        if probability < PROBABILITY_CUTOFF {
+          continue;
+        }
+
+        let mut next_assigned = assigned.clone();
+        next_assigned.insert(measure, bit);
+        stack.push((
+          register_index + 1,
+          target_position + 1,
+          fixed,
+          next_assigned
+        ));
+      }
+    }
+
+    results.sort_by(|left, right| left.bitstring.cmp(&right.bitstring));
+    results
+  }
+}
+
+/// Matrix-product-state solver. Fills the same role as the entanglement solver but represents the
+/// whole state as a chain of tensors, so circuits that stay lightly entangled (like QAOA) can be
+/// solved exactly, and everything else approximately within the bond-dimension cap.
+///
+/// Measures and resets are deferred: a measure copies the qubit onto a fresh wire via a CNOT
+/// and a reset moves the qubit onto a fresh wire, leaving the old one to be traced out.
+pub struct MatrixProductState {
+  chain: Ptr<TensorChain>,
+  trace_module: Ptr<TracingModule>
+}
+
+impl MatrixProductState {
+  pub fn new(max_bond_dimension: usize) -> MatrixProductState {
+    MatrixProductState::with_trace(Ptr::from(TracingModule::default()), max_bond_dimension)
+  }
+
+  pub fn with_trace(
+    trace_module: Ptr<TracingModule>, max_bond_dimension: usize
+  ) -> MatrixProductState {
+    MatrixProductState {
+      chain: Ptr::from(TensorChain::new(max_bond_dimension)),
+      trace_module
+    }
+  }
+
+  fn is_tracing(&self) -> bool { self.trace_module.has(ActiveTracers::Solver) }
+
+  /// Summed weight of every singular value thrown away due to the bond-dimension cap. Zero means
+  /// the results are exact.
+  pub fn discarded_weight(&self) -> f64 { self.chain.discarded_weight }
+
+  pub fn reset(&self, qb: &Qubit) {
+    if self.is_tracing() {
+      log!(Level::Info, "Reset[{}]", qb.index)
+    }
+
+    let wire = with_mutable_self!(self.chain.add_wire());
+    with_mutable_self!(self.chain.qubits.insert(qb.index, wire));
+  }
+
+  pub fn measure(&self, qb: &Qubit) {
+    if self.is_tracing() {
+      log!(Level::Info, "Measure[{}]", qb.index)
+    }
+
+    let wire = with_mutable_self!(self.chain.wire_for(qb));
+    let copy = with_mutable_self!(self.chain.add_wire());
+    let gate = controlled_form(rx(&PI), &PI);
+    with_mutable_self!(self.chain.apply_controlled(&vec![wire], copy, &gate));
+    with_mutable_self!(self.chain.measures.push(copy));
+  }
+
+  fn single(&self, name: &str, qb: &Qubit, gate: &Array2<Complex64>, radians: &f64) {
+    let wire = with_mutable_self!(self.chain.wire_for(qb));
+    with_mutable_self!(self.chain.apply_single(wire, gate));
+    if self.is_tracing() {
+      log!(Level::Info, "{}[{}] {:.4}", name, qb.index, radians)
+    }
+  }
+
+  fn controlled(
+    &self, name: &str, controls: &Vec<Qubit>, target: &Qubit, gate: &Array2<Complex64>,
+    radians: &f64
+  ) {
+    let control_wires = controls
+      .iter()
+      .map(|qb| with_mutable_self!(self.chain.wire_for(qb)))
+      .collect::<Vec<_>>();
+    let target_wire = with_mutable_self!(self.chain.wire_for(target));
+    with_mutable_self!(self
+      .chain
+      .apply_controlled(&control_wires, target_wire, gate));
+
+    if self.is_tracing() {
+      log!(
+        Level::Info,
+        "{}[{}->{}] {:.4}, bond dimension {}",
+        name,
+        controls
+          .iter()
+          .map(|val| val.index.to_string())
+          .collect::<Vec<_>>()
+          .join(","),
+        target.index,
+        radians,
+        self.chain.bond_dimension()
+      )
+    }
+  }
+
+  pub fn X(&self, qb: &Qubit, radians: &f64) { self.single("X", qb, &rx(radians), radians); }
+
+  pub fn Y(&self, qb: &Qubit, radians: &f64) { self.single("Y", qb, &ry(radians), radians); }
+
+  pub fn Z(&self, qb: &Qubit, radians: &f64) { self.single("Z", qb, &rz(radians), radians); }
+
+  pub fn Had(&self, qb: &Qubit) {
+    self.Z(qb, &PI);
+    self.Y(qb, &(PI / 2.0));
+  }
+
+  pub fn CX(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    let gate = controlled_form(rx(radians), radians);
+    self.controlled("CX", controls, target, &gate, radians);
+  }
+
+  pub fn CY(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    let gate = controlled_form(ry(radians), radians);
+    self.controlled("CY", controls, target, &gate, radians);
+  }
+
+  pub fn CZ(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    let gate = controlled_form(rz(radians), radians);
+    self.controlled("CZ", controls, target, &gate, radians);
+  }
+
+  pub fn solve(&self) -> Vec<SolverResult> {
+    let results = self.chain.distribution();
+    if self.is_tracing() {
+      log!(Level::Info, "{}", self.to_string());
+      log!(
+        Level::Info,
+        "Solved results:\n{}\n",
+        results
+          .iter()
+          .map(|val| val.to_string())
+          .collect::<Vec<_>>()
+          .join("\n")
+      );
+    }
+
+    results
+  }
+}
+
+impl CircuitSolver for MatrixProductState {
+  fn reset(&self, qb: &Qubit) { MatrixProductState::reset(self, qb) }
+
+  fn measure(&self, qb: &Qubit) { MatrixProductState::measure(self, qb) }
+
+  fn X(&self, qb: &Qubit, radians: &f64) { MatrixProductState::X(self, qb, radians) }
+
+  fn Y(&self, qb: &Qubit, radians: &f64) { MatrixProductState::Y(self, qb, radians) }
+
+  fn Z(&self, qb: &Qubit, radians: &f64) { MatrixProductState::Z(self, qb, radians) }
+
+  fn CX(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    MatrixProductState::CX(self, controls, target, radians)
+  }
+
+  fn CY(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    MatrixProductState::CY(self, controls, target, radians)
+  }
+
+  fn CZ(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    MatrixProductState::CZ(self, controls, target, radians)
+  }
+
+  fn solve(&self) -> Vec<SolverResult> { MatrixProductState::solve(self) }
+}
+
+impl Display for MatrixProductState {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!(
+      "\nMPS solver: {} sites, bond dimension {} (cap {}), discarded weight {:.6}\n",
+      self.chain.sites.len(),
+      self.chain.bond_dimension(),
+      self.chain.max_bond_dimension,
+      self.chain.discarded_weight
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::mps::MatrixProductState;
+  use crate::hardware::Qubit;
+  use std::f64::consts::PI;
+
+  fn probability_of(results: &Vec<crate::analysis::solver::SolverResult>, bitstring: &str) -> f64 {
+    results
+      .iter()
+      .find(|val| val.bitstring == bitstring)
+      .map_or(0., |val| val.probability)
+  }
+
+  #[test]
+  fn bell_test() {
+    let solver = MatrixProductState::new(16);
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    solver.Had(&q0);
+    solver.CX(&vec![q0.clone()], &q1, &PI);
+    solver.measure(&q0);
+    solver.measure(&q1);
+    let results = solver.solve();
+
+    assert_eq!(results.len(), 2);
+    let zeros = probability_of(&results, "00");
+    let ones = probability_of(&results, "11");
+    assert!(zeros >= 49.9 && zeros <= 50.1);
+    assert!(ones >= 49.9 && ones <= 50.1);
+    assert!(solver.discarded_weight() < 1e-12);
+  }
+
+  #[test]
+  fn non_adjacent_controls() {
+    let solver = MatrixProductState::new(16);
+    let (q0, q1, q2) = (Qubit::new(0), Qubit::new(1), Qubit::new(2));
+    solver.X(&q0, &PI);
+    solver.X(&q1, &PI);
+    solver.CX(&vec![q0.clone(), q1.clone()], &q2, &PI);
+    solver.CZ(&vec![q0.clone()], &q2, &PI);
+    solver.measure(&q0);
+    solver.measure(&q1);
+    solver.measure(&q2);
+    let results = solver.solve();
+
+    assert_eq!(results.len(), 1);
+    assert!(probability_of(&results, "111") >= 99.9);
+  }
+
+  #[test]
+  fn reset_then_measure() {
+    let solver = MatrixProductState::new(16);
+    let q0 = Qubit::new(0);
+    solver.X(&q0, &PI);
+    solver.reset(&q0);
+    solver.measure(&q0);
+    let results = solver.solve();
+
+    assert!(probability_of(&results, "0") >= 99.9);
+  }
+
+  #[test]
+  fn remeasure() {
+    let solver = MatrixProductState::new(16);
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    solver.Had(&q0);
+    solver.CX(&vec![q0.clone()], &q1, &PI);
+    solver.measure(&q0);
+    solver.reset(&q0);
+    solver.measure(&q0);
+    solver.measure(&q1);
+    let results = solver.solve();
+
+    // Each measure keeps its own bit, so the first still matches its partner after the reset.
+    assert_eq!(results.len(), 2);
+    assert!(probability_of(&results, "000") >= 49.9);
+    assert!(probability_of(&results, "101") >= 49.9);
+  }
+
+  #[test]
+  fn bond_dimension_cap() {
+    let solver = MatrixProductState::new(1);
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    solver.Had(&q0);
+    solver.CX(&vec![q0.clone()], &q1, &PI);
+    solver.measure(&q0);
+    solver.measure(&q1);
+    solver.solve();
+
+    assert!(solver.discarded_weight() > 0.);
+  }
+}
@@ -1,14 +1,24 @@
 // SPDX-License-Identifier: BSD-3-Clause
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
-use crate::analysis::solver::{QuantumSolver, SolverConfig, SolverResult};
-use crate::config::RasqalConfig;
+use crate::analysis::cache::{CacheKey, ProjectionCache};
+use crate::analysis::mps::MatrixProductState;
+use crate::analysis::sampling::ShotSampler;
+use crate::analysis::solver::{
+  CircuitSolver, QuantumSolver, SolverConfig, SolverResult, SolverStrategy
+};
+use crate::builders::{CircuitOperation, IntegrationBuilder, IntegrationRuntime};
+use crate::config::{ExecutionOptions, RasqalConfig};
+use crate::events::TraceEvent;
 use crate::execution::RuntimeCollection;
 use crate::features::QuantumFeatures;
 use crate::graphs::AnalysisGraph;
 use crate::hardware::Qubit;
+use crate::instructions::Pauli;
+use crate::qasm::{to_qasm, QasmVersion};
 use crate::runtime::{ActiveTracers, TracingModule};
 use crate::smart_pointers::Ptr;
+use crate::statistics::{ProjectionStatistics, RunStatistics};
 use crate::{with_mutable, with_mutable_self};
 use log::{log, Level};
 use ndarray::{array, Array2};
@@ -17,12 +27,12 @@ use num::traits::FloatConst;
 use num_complex::{Complex, Complex64, ComplexFloat};
 use std::cmp::Ordering;
 use std::collections::hash_map::Keys;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::f64::consts::PI;
 use std::fmt::{Display, Formatter, Write};
 use std::iter::zip;
 use std::ops::{Deref, Mul, MulAssign};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// A projected value that is either concretized and has a result, or in analysis mode and can be
 /// queried LIKE it was a result, but we haven't actually executed on the QPU yet.
@@ -32,7 +42,34 @@ pub struct QuantumProjection {
   instructions: Vec<Ptr<AnalysisOperation>>,
   cached_result: Option<AnalysisResult>,
   cached_filtered: HashMap<String, AnalysisResult>,
-  solver_config: SolverConfig
+
+  /// Positions of measures which were made together as a joint Pauli observable, so their
+  /// results are the parity of each individual measure.
+  joint_measures: HashSet<BTreeSet<usize>>,
+  solver_config: SolverConfig,
+  config: Ptr<RasqalConfig>,
+  result_cache: Option<Ptr<ProjectionCache>>,
+  options: ExecutionOptions,
+  reduction: ResultReduction,
+
+  /// Run statistics to report to, along with the order this projection was created in.
+  statistics: Option<Ptr<RunStatistics>>,
+  index: usize,
+
+  /// Picks the shot followed when results are being reduced per-shot.
+  sampler: Option<Ptr<ShotSampler>>,
+
+  /// Value of each measure in the shot being followed, by position, for every one that's been
+  /// read. Re-executions of the projection only pick shots consistent with these.
+  pinned: HashMap<usize, char>,
+
+  /// Step of the run each operation was added at, so merged projections keep their operations in
+  /// the order they ran.
+  added_at: Vec<i64>,
+  step_count: Option<Ptr<i64>>,
+
+  /// Projection this one was merged into, which answers everything asked of this one afterwards.
+  merged_into: Option<Ptr<QuantumProjection>>
 }
 
 /// A for-now list of linear gates and hardware operations that we can store and send to our
@@ -66,8 +103,90 @@ impl AnalysisOperation {
       AnalysisOperation::Measure(qbs) => qbs.iter().collect()
     }
   }
+
+  /// Lower-case name of the operation, matching its [`CircuitOperation`] counterpart.
+  pub fn name(&self) -> &'static str {
+    match self {
+      AnalysisOperation::Initialize() => "init",
+      AnalysisOperation::Reset(_) => "reset",
+      AnalysisOperation::X(..) => "x",
+      AnalysisOperation::Y(..) => "y",
+      AnalysisOperation::Z(..) => "z",
+      AnalysisOperation::CX(..) => "cx",
+      AnalysisOperation::CZ(..) => "cz",
+      AnalysisOperation::CY(..) => "cy",
+      AnalysisOperation::Measure(_) => "measure"
+    }
+  }
+
+  pub fn radians(&self) -> Option<f64> {
+    match self {
+      AnalysisOperation::X(_, radians)
+      | AnalysisOperation::Y(_, radians)
+      | AnalysisOperation::Z(_, radians)
+      | AnalysisOperation::CX(_, _, radians)
+      | AnalysisOperation::CZ(_, _, radians)
+      | AnalysisOperation::CY(_, _, radians) => Some(*radians),
+      _ => None
+    }
+  }
+
+  /// Returns every qubit this operation touches, controls included.
+  pub fn qubits(&self) -> Vec<&Qubit> {
+    match self {
+      AnalysisOperation::CX(controls, target, _)
+      | AnalysisOperation::CZ(controls, target, _)
+      | AnalysisOperation::CY(controls, target, _) => {
+        let mut qubits = controls.iter().collect::<Vec<_>>();
+        qubits.push(target);
+        qubits
+      }
+      _ => self.associated_qubits()
+    }
+  }
+}
+
+/// A slice of a projection which shares no qubits with the rest of it, so can be executed on
+/// its own.
+#[derive(Clone, Default)]
+struct CircuitPartition {
+  instructions: Vec<Ptr<AnalysisOperation>>,
+
+  /// Where each of this partitions measures sits in the whole projections results.
+  measures: Vec<usize>
+}
+
+impl CircuitPartition {
+  fn whole(instructions: &Vec<Ptr<AnalysisOperation>>) -> CircuitPartition {
+    let measure_count = instructions
+      .iter()
+      .map(|inst| match inst.deref() {
+        AnalysisOperation::Measure(qbs) => qbs.len(),
+        _ => 0
+      })
+      .sum();
+
+    CircuitPartition {
+      instructions: instructions.clone(),
+      measures: (0..measure_count).collect()
+    }
+  }
 }
 
+fn no_capable_QPU(features: &QuantumFeatures) -> ! {
+  panic!(
+    "Cannot find QPU with these features available: [{}]",
+    features
+  )
+}
+
+/// Runtime/builder pairing, and the options to run it with, ready to be sent off for execution.
+pub type DispatchJob = (
+  Ptr<IntegrationRuntime>,
+  Ptr<IntegrationBuilder>,
+  ExecutionOptions
+);
+
 impl Display for AnalysisOperation {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     f.write_str(
@@ -139,7 +258,19 @@ impl QuantumProjection {
       trace_module: Ptr::from(TracingModule::new()),
       cached_result: None,
       cached_filtered: HashMap::new(),
-      solver_config: SolverConfig::off()
+      joint_measures: HashSet::new(),
+      solver_config: SolverConfig::off(),
+      config: Ptr::from(RasqalConfig::default()),
+      result_cache: None,
+      options: ExecutionOptions::default(),
+      reduction: ResultReduction::default(),
+      statistics: None,
+      index: 0,
+      sampler: None,
+      pinned: HashMap::new(),
+      added_at: Vec::new(),
+      step_count: None,
+      merged_into: None
     }
   }
 
@@ -153,8 +284,284 @@ impl QuantumProjection {
       trace_module: tracing_module.clone(),
       cached_result: None,
       cached_filtered: HashMap::new(),
-      solver_config: SolverConfig::with_config(config)
+      joint_measures: HashSet::new(),
+      solver_config: SolverConfig::with_config(config),
+      config: config.clone(),
+      result_cache: None,
+      options: config.execution_options.clone(),
+      reduction: config.result_reduction.clone(),
+      statistics: None,
+      index: 0,
+      sampler: None,
+      pinned: HashMap::new(),
+      added_at: Vec::new(),
+      step_count: None,
+      merged_into: None
+    }
+  }
+
+  /// Options passed to the runtime when this projection gets executed.
+  pub fn set_options(&mut self, options: ExecutionOptions) { self.options = options; }
+
+  /// How results get reduced when branched on, overriding the configs.
+  pub fn set_reduction(&mut self, reduction: ResultReduction) { self.reduction = reduction; }
+
+  /// Shares results with every other projection using this cache, so identical circuits only
+  /// get executed once.
+  pub fn attach_cache(&mut self, cache: &Ptr<ProjectionCache>) {
+    self.result_cache = Some(cache.clone());
+  }
+
+  /// Reports how this projection gets concretized to these statistics. Index is the order it was
+  /// created in during the run.
+  pub fn attach_statistics(&mut self, statistics: &Ptr<RunStatistics>, index: usize) {
+    self.statistics = Some(statistics.clone());
+    self.index = index;
+  }
+
+  /// Picks the shots followed when results are reduced per-shot, shared so a seeded run is
+  /// reproducible across all its projections.
+  pub fn attach_sampler(&mut self, sampler: &Ptr<ShotSampler>) {
+    self.sampler = Some(sampler.clone());
+  }
+
+  /// Stamps every operation with the step of the run it was added at, which keeps operations in
+  /// the order they ran when projections get merged.
+  pub fn attach_step_count(&mut self, step_count: &Ptr<i64>) {
+    self.step_count = Some(step_count.clone());
+  }
+
+  /// Merges the other projection into the target, such as when a gate entangles qubits from both.
+  /// Operations from each are interleaved in the order they ran, and anything asked of the other
+  /// projection afterwards is answered by the target.
+  pub fn absorb(target: &Ptr<QuantumProjection>, other: &Ptr<QuantumProjection>) {
+    if Ptr::eq(target, other) {
+      return;
+    }
+
+    let mut projection = target.clone();
+    let mut other = other.clone();
+    if projection.is_tracing() {
+      log!(
+        Level::Info,
+        "Merging projection {} into {}.",
+        other.index,
+        projection.index
+      );
+    }
+
+    // Both sets of operations are already in order, so a stable sort keeps ties in place.
+    let mut merged = zip(projection.added_at.clone(), projection.instructions.clone())
+      .map(|(step, inst)| (step, false, inst))
+      .chain(
+        zip(other.added_at.clone(), other.instructions.clone())
+          .map(|(step, inst)| (step, true, inst))
+      )
+      .collect::<Vec<_>>();
+    merged.sort_by_key(|(step, ..)| *step);
+
+    // Interleaving moves measures about, so anything keyed by their position needs renumbering.
+    let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+    let mut position = 0;
+    for (_, is_other, inst) in &merged {
+      if let AnalysisOperation::Measure(qbs) = inst.deref() {
+        let moved = if *is_other { &mut theirs } else { &mut ours };
+        moved.extend(position..position + qbs.len());
+        position += qbs.len();
+      }
+    }
+    let joint_measures = |projection: &QuantumProjection, moved: &Vec<usize>| {
+      projection
+        .joint_measures
+        .iter()
+        .map(|positions| {
+          positions
+            .iter()
+            .map(|val| moved[*val])
+            .collect::<BTreeSet<_>>()
+        })
+        .collect::<Vec<_>>()
+    };
+    let pinned = |projection: &QuantumProjection, moved: &Vec<usize>| {
+      projection
+        .pinned
+        .iter()
+        .map(|(position, bit)| (moved[*position], *bit))
+        .collect::<Vec<_>>()
+    };
+    projection.joint_measures = joint_measures(&projection, &ours)
+      .into_iter()
+      .chain(joint_measures(&other, &theirs))
+      .collect();
+    projection.pinned = pinned(&projection, &ours)
+      .into_iter()
+      .chain(pinned(&other, &theirs))
+      .collect();
+
+    let (added_at, instructions): (Vec<_>, Vec<_>) = merged
+      .into_iter()
+      .map(|(step, _, inst)| (step, inst))
+      .unzip();
+    projection.added_at = added_at;
+    projection.instructions = instructions;
+    projection.cached_result = None;
+    projection.cached_filtered.clear();
+    other.merged_into = Some(projection.clone());
+
+    if let Some(statistics) = projection.statistics.as_ref() {
+      with_mutable!(statistics.projections_merged += 1);
+    }
+    projection
+      .config
+      .events
+      .emit(|| TraceEvent::ProjectionsMerged {
+        projection: other.index,
+        into: projection.index
+      });
+  }
+
+  /// Projection which now holds this ones operations, following any merges.
+  fn current(projection: &Ptr<QuantumProjection>) -> Ptr<QuantumProjection> {
+    match projection.merged_into.as_ref() {
+      Some(merged) => QuantumProjection::current(merged),
+      None => projection.clone()
+    }
+  }
+
+  /// How many operations have been added to this projection so far.
+  pub fn instruction_count(&self) -> usize { self.instructions.len() }
+
+  /// The circuit this projection sends to a backend, as gate-level operations.
+  pub fn operations(&self) -> Vec<CircuitOperation> {
+    let mut operations = Vec::new();
+    for inst in self.instructions.iter() {
+      match inst.deref() {
+        AnalysisOperation::Initialize() => {}
+        AnalysisOperation::Reset(qbs) => {
+          operations.extend(qbs.iter().map(|qb| CircuitOperation::Reset(qb.clone())));
+        }
+        AnalysisOperation::X(qb, radians) => {
+          operations.push(CircuitOperation::X(qb.clone(), *radians))
+        }
+        AnalysisOperation::Y(qb, radians) => {
+          operations.push(CircuitOperation::Y(qb.clone(), *radians))
+        }
+        AnalysisOperation::Z(qb, radians) => {
+          operations.push(CircuitOperation::Z(qb.clone(), *radians))
+        }
+        AnalysisOperation::CX(controls, target, radians) => {
+          operations.push(CircuitOperation::CX(
+            controls.clone(),
+            target.clone(),
+            *radians
+          ));
+        }
+        AnalysisOperation::CZ(controls, target, radians) => {
+          operations.push(CircuitOperation::CZ(
+            controls.clone(),
+            target.clone(),
+            *radians
+          ));
+        }
+        AnalysisOperation::CY(controls, target, radians) => {
+          operations.push(CircuitOperation::CY(
+            controls.clone(),
+            target.clone(),
+            *radians
+          ));
+        }
+        AnalysisOperation::Measure(qbs) => {
+          operations.extend(qbs.iter().map(|qb| CircuitOperation::Measure(qb.clone())));
+        }
+      }
+    }
+    operations
+  }
+
+  /// Emits the circuit this projection sends to a backend as OpenQASM.
+  pub fn to_qasm(&self, version: QasmVersion) -> Result<String, String> {
+    to_qasm(&self.operations(), version)
+  }
+
+  /// Order this projection was created in during its run.
+  pub fn index(&self) -> usize { self.index }
+
+  /// Whether this projection has been executed and has results.
+  pub fn is_concretized(&self) -> bool { self.cached_result.is_some() }
+
+  /// Every operation added to this projection so far, as they'd be traced.
+  pub fn circuit(&self) -> Vec<String> {
+    self
+      .instructions
+      .iter()
+      .map(|inst| inst.to_string())
+      .collect()
+  }
+
+  /// Whether this qubit is known to be in its zero state, as it's either been reset since it was
+  /// last used or never been used at all.
+  pub fn is_reset(&self, qb: &Qubit) -> bool {
+    self
+      .instructions
+      .iter()
+      .rev()
+      .find(|inst| inst.qubits().contains(&qb))
+      .map_or(true, |inst| {
+        matches!(inst.deref(), AnalysisOperation::Reset(_))
+      })
+  }
+
+  /// Features a backend needs to run this projection. Circuits are built on compacted qubits, so
+  /// it needs as many as this projection has live at once rather than the highest index it uses,
+  /// and it needs to take rotations with as many controls as the widest one here.
+  fn required_features(&self) -> QuantumFeatures {
+    let (_, width) = QuantumProjection::compact_qubits(&self.instructions);
+    let controls = self
+      .instructions
+      .iter()
+      .map(|inst| match inst.deref() {
+        AnalysisOperation::CX(controls, ..)
+        | AnalysisOperation::CY(controls, ..)
+        | AnalysisOperation::CZ(controls, ..) => controls.len(),
+        _ => 0
+      })
+      .max()
+      .unwrap_or(0);
+    QuantumFeatures::new(width as i32).with_controls(controls as i32)
+  }
+
+  /// Maps every qubit these instructions use onto as few backend qubits as possible, lowest
+  /// first. A backend qubit is handed out again once whatever was on it has been reset for the
+  /// last time, which released qubits always are. Returns the mapping and how many backend qubits
+  /// it needs.
+  fn compact_qubits(instructions: &Vec<Ptr<AnalysisOperation>>) -> (HashMap<i64, i64>, i64) {
+    let mut last_use = HashMap::new();
+    for (position, inst) in instructions.iter().enumerate() {
+      for qb in inst.qubits() {
+        last_use.insert(qb.index, position);
+      }
+    }
+
+    let mut mapping = HashMap::new();
+    let mut free = BTreeSet::new();
+    let mut width = 0;
+    for (position, inst) in instructions.iter().enumerate() {
+      for qb in inst.qubits() {
+        mapping.entry(qb.index).or_insert_with(|| {
+          free.pop_first().unwrap_or_else(|| {
+            width += 1;
+            width - 1
+          })
+        });
+      }
+
+      if let AnalysisOperation::Reset(qbs) = inst.deref() {
+        for qb in qbs.iter().filter(|qb| last_use[&qb.index] == position) {
+          free.insert(mapping[&qb.index]);
+        }
+      }
     }
+    (mapping, width)
   }
 
   /// Quick helper module as right now there's no sub-definition for projections.
@@ -190,6 +597,99 @@ impl QuantumProjection {
 
   pub fn Measure(&mut self, qbs: Vec<Qubit>) { self.add(AnalysisOperation::Measure(qbs)); }
 
+  /// Measures these qubits in the Pauli bases given, returning the qubits whose results make up
+  /// the measurement. Identities have no effect so are dropped.
+  ///
+  /// Each qubit is rotated into the Z basis, measured, then rotated back. When more than one qubit
+  /// is involved the result is the parity of the individual measures, which gives the outcome of
+  /// the joint observable, but collapses each qubit into its own eigenstate rather than only the
+  /// joint eigenspace.
+  pub fn MeasurePauli(&mut self, paulis: Vec<Pauli>, qbs: Vec<Qubit>) -> Vec<Qubit> {
+    let measured = zip(paulis, qbs)
+      .filter(|(pauli, _)| *pauli != Pauli::I)
+      .collect::<Vec<_>>();
+    if measured.is_empty() {
+      return Vec::new();
+    }
+
+    for (pauli, qb) in measured.iter() {
+      match pauli {
+        Pauli::X => {
+          self.Z(qb.clone(), PI);
+          self.Y(qb.clone(), PI / 2.);
+        }
+        Pauli::Y => {
+          self.Z(qb.clone(), PI / 2.);
+          self.Y(qb.clone(), PI / 2.);
+        }
+        Pauli::I | Pauli::Z => {}
+      }
+    }
+
+    let qubits = measured
+      .iter()
+      .map(|(_, qb)| qb.clone())
+      .collect::<Vec<_>>();
+    self.Measure(qubits.clone());
+
+    for (pauli, qb) in measured.iter().rev() {
+      match pauli {
+        Pauli::X => {
+          self.Y(qb.clone(), -PI / 2.);
+          self.Z(qb.clone(), -PI);
+        }
+        Pauli::Y => {
+          self.Y(qb.clone(), -PI / 2.);
+          self.Z(qb.clone(), -PI / 2.);
+        }
+        Pauli::I | Pauli::Z => {}
+      }
+    }
+
+    if qubits.len() > 1 {
+      let measures = self.measure_positions();
+      self
+        .joint_measures
+        .insert(qubits.iter().map(|qb| measures[&qb.index]).collect());
+    }
+    qubits
+  }
+
+  /// Whether the results for these qubits count as one when branched on, as decided by the
+  /// configured [`ResultReduction`]. Panics if it decides the results are ambiguous.
+  pub fn is_one_for(&mut self, qbs: &Vec<Qubit>) -> bool {
+    let reduction = self.reduction.clone();
+    self
+      .results_for(qbs)
+      .reduce(&reduction)
+      .unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Where the latest measure of each qubit sits in this projections results, counting from the
+  /// first measure made. Results are read right-to-left, so the first measure is the last
+  /// character.
+  fn measure_positions(&self) -> HashMap<i64, usize> {
+    let mut positions = HashMap::new();
+    let mut position = 0;
+    for inst in self.instructions.iter() {
+      if let AnalysisOperation::Measure(qbs) = inst.deref() {
+        for qb in qbs {
+          positions.insert(qb.index, position);
+          position += 1;
+        }
+      }
+    }
+    positions
+  }
+
+  fn qubits_key(qbs: &Vec<Qubit>) -> String {
+    qbs
+      .iter()
+      .map(|val| val.index.to_string())
+      .collect::<Vec<_>>()
+      .join(",")
+  }
+
   /// Adds this operation to the projection.
   fn add(&mut self, inst: AnalysisOperation) {
     // Clear any pre-computed results upon a change to the state.
@@ -197,7 +697,17 @@ impl QuantumProjection {
       self.cached_result = None;
       self.cached_filtered.clear();
     }
+
+    self.config.events.emit(|| TraceEvent::GateAdded {
+      projection: self.index,
+      gate: inst.name().to_string(),
+      qubits: inst.qubits().iter().map(|qb| qb.index).collect(),
+      radians: inst.radians()
+    });
     self.instructions.push(Ptr::from(inst));
+    self
+      .added_at
+      .push(self.step_count.as_ref().map_or(0, |val| **val));
   }
 
   /// Equality across projections for specific qubit.
@@ -250,7 +760,15 @@ impl QuantumProjection {
     }
 
     let start = Instant::now();
-    let qsolver = QuantumSolver::with_trace(self.trace_module.clone());
+    let qsolver: Box<dyn CircuitSolver> = match self.solver_config.strategy {
+      SolverStrategy::Entanglement => {
+        Box::new(QuantumSolver::with_trace(self.trace_module.clone()))
+      }
+      SolverStrategy::MatrixProductState => Box::new(MatrixProductState::with_trace(
+        self.trace_module.clone(),
+        self.solver_config.max_bond_dimension
+      ))
+    };
     for inst in self.instructions.iter() {
       match inst.deref() {
         AnalysisOperation::Initialize() => {}
@@ -286,29 +804,44 @@ impl QuantumProjection {
     }
 
     // For the projections, for now we only accept fully quantified results. Strip all
-    // unknown values.
+    // unknown values. Solver bitstrings have the first measure leftmost, whereas results have it
+    // rightmost.
     let mut solver_results = Vec::new();
-    for result in qsolver.solve() {
+    for mut result in qsolver.solve() {
       if !result.bitstring.contains("X") {
+        result.bitstring = result.bitstring.chars().rev().collect();
         solver_results.push(result);
       }
     }
 
     let took = start.elapsed();
     log!(Level::Info, "Solving took {}ms.", took.as_millis());
+    if let Some(statistics) = self.statistics.as_ref() {
+      with_mutable!(statistics.record_solver(took, !solver_results.is_empty()));
+    }
+    self.config.events.emit(|| TraceEvent::SolverDecision {
+      projection: self.index,
+      solved: !solver_results.is_empty()
+    });
     AnalysisResult::from_solver_result(solver_results)
   }
 
   /// Get results for this entire state.
-  pub fn results(&mut self) -> AnalysisResult { self.concretize().clone() }
+  pub fn results(&mut self) -> AnalysisResult {
+    if let Some(merged) = self.merged_into.as_ref() {
+      return with_mutable!(merged.results());
+    }
+    self.concretize().clone()
+  }
 
   /// Extracts the results for this particular set of qubits from the results of running this
   /// projection.
   ///
-  /// For example if your results are 01: 150, 00: 50 and ask for qubit 0 you'll get the
-  /// result 0: 200. It needs to be pointed out that this needs to be viewed as a window into the
-  /// overall result, not something that can be viewed by itself, because you lose all the nuance
-  /// around the overall state.
+  /// For example if your results are 01: 150, 00: 50 and ask for the qubit measured second you'll
+  /// get the result 0: 200, as results are read right-to-left from the first measure. It needs to
+  /// be pointed out that this needs to be viewed as a window into the overall result, not
+  /// something that can be viewed by itself, because you lose all the nuance around the overall
+  /// state.
   ///
   /// It's great for asking more brute-force questions like 'is this qubit overwhelmingly 1 in the
   /// results' and things of that sort though. This is also used for implicit conditional
@@ -318,28 +851,33 @@ impl QuantumProjection {
       return AnalysisResult::empty();
     }
 
+    if let Some(merged) = self.merged_into.as_ref() {
+      return with_mutable!(merged.results_for(qb));
+    }
+
     // Check if we have a cached value, if so, return.
-    let positions = qb.iter().map(|val| val.index as usize).collect::<Vec<_>>();
-    let cache_key = positions
-      .iter()
-      .map(|val| val.to_string())
-      .collect::<Vec<_>>()
-      .join(",");
+    let cache_key = Self::qubits_key(qb);
     if let Some(cached) = self.cached_filtered.get(&cache_key) {
       return cached.clone();
     }
 
+    // Each qubits result is wherever its latest measure sits, skipping any never measured.
+    let measures = self.measure_positions();
+    let measured = qb
+      .iter()
+      .filter(|val| measures.contains_key(&val.index))
+      .collect::<Vec<_>>();
+
     let results = self.concretize();
 
     // Strip out set qubits from the results. So if you have 01010: 50 and 01011: 7
     let mut new_distribution: HashMap<String, i64> = HashMap::new();
     for (key, value) in results.distribution.iter() {
-      // -1 for zero-indexing.
-      let key_length = key.len() - 1;
+      let bits = key.chars().rev().collect::<Vec<_>>();
       let mut new_key = String::new();
-      for index in positions.iter() {
-        if let Some(nth_value) = key.chars().nth(key_length - index) {
-          new_key.push(nth_value);
+      for qubit in measured.iter() {
+        if let Some(nth_value) = bits.get(measures[&qubit.index]) {
+          new_key.push(*nth_value);
         }
       }
 
@@ -354,7 +892,27 @@ impl QuantumProjection {
       }
     }
 
-    let new_results = AnalysisResult::new(new_distribution);
+    let positions = measured
+      .iter()
+      .map(|qb| measures[&qb.index])
+      .collect::<Vec<_>>();
+    let is_joint = self
+      .joint_measures
+      .contains(&positions.iter().copied().collect::<BTreeSet<_>>());
+    let mut new_results = AnalysisResult::new(new_distribution);
+    if is_joint {
+      new_results = new_results.parity();
+    }
+
+    // When following a single shot, whatever we've seen here has to stay true for later reads.
+    if self.reduction == ResultReduction::PerShot && !is_joint {
+      if let Some(key) = new_results.distribution.keys().next() {
+        for (position, bit) in zip(positions, key.chars()) {
+          self.pinned.insert(position, bit);
+        }
+      }
+    }
+
     self.cached_filtered.insert(cache_key, new_results.clone());
     if self.is_tracing() {
       log!(
@@ -371,6 +929,253 @@ impl QuantumProjection {
     new_results
   }
 
+  /// Splits the projection into groups of operations which share no qubits with any other group,
+  /// recording where each groups measures sit in the full results so they can be joined back
+  /// together. Groups without measures have no observable effect so are dropped.
+  ///
+  /// Resetting a qubit leaves it independent of everything it did before, so each stretch of a
+  /// qubit between resets is grouped as if it were a qubit of its own.
+  fn partition(&self) -> Vec<CircuitPartition> {
+    type Node = (i64, usize);
+    fn find(roots: &mut HashMap<Node, Node>, node: Node) -> Node {
+      let parent = *roots.entry(node).or_insert(node);
+      if parent == node {
+        node
+      } else {
+        let root = find(roots, parent);
+        roots.insert(node, root);
+        root
+      }
+    }
+
+    let mut resets = HashMap::new();
+    let mut nodes = Vec::new();
+    for inst in self.instructions.iter() {
+      let qubits = inst
+        .qubits()
+        .iter()
+        .map(|qb| (qb.index, *resets.get(&qb.index).unwrap_or(&0)))
+        .collect::<Vec<Node>>();
+      if let AnalysisOperation::Reset(qbs) = inst.deref() {
+        for qb in qbs {
+          *resets.entry(qb.index).or_insert(0) += 1;
+        }
+      }
+      nodes.push(qubits);
+    }
+
+    let mut roots = HashMap::new();
+    for qubits in nodes.iter() {
+      if let Some(first) = qubits.first() {
+        let root = find(&mut roots, *first);
+        for node in qubits.iter().skip(1) {
+          let other = find(&mut roots, *node);
+          if other != root {
+            roots.insert(other, root);
+          }
+        }
+      }
+    }
+
+    let mut partitions: Vec<CircuitPartition> = Vec::new();
+    let mut partition_for_root = HashMap::new();
+    let mut measure_index = 0;
+    for (inst, qubits) in zip(self.instructions.iter(), nodes.iter()) {
+      let Some(first) = qubits.first() else {
+        continue;
+      };
+
+      let root = find(&mut roots, *first);
+      let position = *partition_for_root.entry(root).or_insert_with(|| {
+        partitions.push(CircuitPartition::default());
+        partitions.len() - 1
+      });
+
+      let partition = &mut partitions[position];
+      partition.instructions.push(inst.clone());
+      if let AnalysisOperation::Measure(qbs) = inst.deref() {
+        for _ in qbs {
+          partition.measures.push(measure_index);
+          measure_index += 1;
+        }
+      }
+    }
+
+    partitions.retain(|val| !val.measures.is_empty());
+    if partitions.is_empty() {
+      vec![CircuitPartition::whole(&self.instructions)]
+    } else {
+      partitions
+    }
+  }
+
+  /// Joins the results of independently-executed partitions back into the distribution the whole
+  /// circuit would have returned. Since the partitions are independent the joint distribution is
+  /// just their product, scaled to the shot count of the first.
+  fn join(partitions: &Vec<CircuitPartition>, results: Vec<AnalysisResult>) -> AnalysisResult {
+    if results.len() == 1 {
+      return results.into_iter().next().unwrap();
+    }
+
+    let register_size = partitions
+      .iter()
+      .map(|val| val.measures.len())
+      .sum::<usize>();
+    let shots = results
+      .first()
+      .map_or(0, |val| val.distribution.values().sum::<i64>());
+
+    let mut combined = vec![(vec!['0'; register_size], 1.0)];
+    for (partition, result) in zip(partitions, results.iter()) {
+      let total = result.distribution.values().sum::<i64>() as f64;
+      if total <= 0. {
+        continue;
+      }
+
+      let mut next = Vec::new();
+      for (bits, probability) in combined.iter() {
+        for (key, count) in result.distribution.iter() {
+          // Results are read right-to-left, so the first measure is the last character.
+          let local = key.chars().rev().collect::<Vec<_>>();
+          let mut bits = bits.clone();
+          for (local_index, global_index) in partition.measures.iter().enumerate() {
+            bits[register_size - 1 - global_index] = *local.get(local_index).unwrap_or(&'0');
+          }
+          next.push((bits, probability * (*count as f64 / total)));
+        }
+      }
+      combined = next;
+    }
+
+    let mut probabilities: HashMap<String, f64> = HashMap::new();
+    for (bits, probability) in combined {
+      *probabilities
+        .entry(bits.into_iter().collect::<String>())
+        .or_insert(0.) += probability;
+    }
+
+    AnalysisResult::new(QuantumProjection::apportion(probabilities, shots))
+  }
+
+  /// Turns probabilities into shot counts which add up to exactly `shots`. Every outcome gets the
+  /// whole part of its share, then the shots left over go to the largest remainders, so rare
+  /// outcomes still show up rather than all being rounded away.
+  pub(crate) fn apportion(probabilities: HashMap<String, f64>, shots: i64) -> HashMap<String, i64> {
+    let mut distribution = HashMap::new();
+    let mut remainders = Vec::new();
+    let mut assigned = 0;
+    for (key, probability) in probabilities {
+      let share = probability * shots as f64;
+      let count = share.floor() as i64;
+      assigned += count;
+      remainders.push((share - count as f64, key.clone()));
+      distribution.insert(key, count);
+    }
+
+    // Ties are broken on the key so results are stable across runs.
+    remainders.sort_by(|(left, left_key), (right, right_key)| {
+      right.total_cmp(left).then_with(|| left_key.cmp(right_key))
+    });
+    for (_, key) in remainders
+      .into_iter()
+      .take((shots - assigned).max(0) as usize)
+    {
+      *distribution.get_mut(&key).unwrap() += 1;
+    }
+
+    distribution.retain(|_, count| *count > 0);
+    distribution
+  }
+
+  /// Builds up a backend circuit from these operations, with its qubits compacted.
+  fn build(
+    runtime: &Ptr<IntegrationRuntime>, instructions: &Vec<Ptr<AnalysisOperation>>
+  ) -> Ptr<IntegrationBuilder> {
+    let (mapping, _) = QuantumProjection::compact_qubits(instructions);
+    let map = |qb: &Qubit| Qubit::new(mapping[&qb.index]);
+    let map_all = |qbs: &Vec<Qubit>| qbs.iter().map(map).collect::<Vec<_>>();
+
+    let builder = runtime.create_builder();
+    for inst in instructions.iter() {
+      match inst.deref() {
+        AnalysisOperation::Initialize() => {}
+        AnalysisOperation::Reset(qbs) => {
+          for qubit in qbs {
+            builder.reset(&map(qubit));
+          }
+        }
+        AnalysisOperation::X(qb, radians) => {
+          builder.x(&map(qb), *radians);
+        }
+        AnalysisOperation::Y(qb, radians) => {
+          builder.y(&map(qb), *radians);
+        }
+        AnalysisOperation::Z(qb, radians) => {
+          builder.z(&map(qb), *radians);
+        }
+        AnalysisOperation::CX(controls, targets, radians) => {
+          builder.cx(&map_all(controls), &map(targets), *radians);
+        }
+        AnalysisOperation::CZ(controls, targets, radians) => {
+          builder.cz(&map_all(controls), &map(targets), *radians);
+        }
+        AnalysisOperation::CY(controls, targets, radians) => {
+          builder.cy(&map_all(controls), &map(targets), *radians);
+        }
+        AnalysisOperation::Measure(qbs) => {
+          for qb in qbs {
+            builder.measure(&map(qb));
+          }
+        }
+      }
+    }
+    builder
+  }
+
+  /// Builds the backend jobs for this projection. With parallel dispatch active every independent
+  /// partition becomes its own job, spread across every capable QPU. Jobs which end up on the same
+  /// QPU are still run one after the other.
+  fn create_jobs(&self) -> (Vec<CircuitPartition>, Vec<DispatchJob>) {
+    let features = self.required_features();
+    if !self.config.parallel_dispatch {
+      let runtime = self
+        .engines
+        .find_capable_QPU(&features)
+        .unwrap_or_else(|| no_capable_QPU(&features));
+      let builder = QuantumProjection::build(&runtime, &self.instructions);
+      return (vec![CircuitPartition::whole(&self.instructions)], vec![(
+        runtime,
+        builder,
+        self.options.clone()
+      )]);
+    }
+
+    let runtimes = self.engines.find_capable_QPUs(&features);
+    if runtimes.is_empty() {
+      no_capable_QPU(&features);
+    }
+
+    let partitions = self.partition();
+    if self.is_tracing() && partitions.len() > 1 {
+      log!(
+        Level::Info,
+        "Projection split into {} independent circuits.",
+        partitions.len()
+      );
+    }
+
+    let jobs = partitions
+      .iter()
+      .enumerate()
+      .map(|(index, partition)| {
+        let runtime = runtimes[index % runtimes.len()].clone();
+        let builder = QuantumProjection::build(&runtime, &partition.instructions);
+        (runtime, builder, self.options.clone())
+      })
+      .collect::<Vec<_>>();
+    (partitions, jobs)
+  }
+
   /// Take the projection so far, build up a backend execution and then execute against an
   /// available QPU.
   pub fn concretize(&mut self) -> &AnalysisResult {
@@ -378,58 +1183,218 @@ impl QuantumProjection {
       return self.cached_result.as_ref().unwrap();
     }
 
-    let mut query_result = self.solve();
-    if query_result.is_empty() {
-      let start = Instant::now();
-      let features = QuantumFeatures::default();
-      let runtime = self.engines.find_capable_QPU(&features).unwrap_or_else(|| {
-        panic!(
-          "Cannot find QPU with these features available: [{}]",
-          features
-        )
-      });
+    let solved = self.solve();
+    if !solved.is_empty() {
+      return self.complete(solved, None);
+    }
 
-      let builder = runtime.create_builder();
-      for inst in self.instructions.iter() {
-        match inst.deref() {
-          AnalysisOperation::Initialize() => {}
-          AnalysisOperation::Reset(qbs) => {
-            for qubit in qbs {
-              builder.reset(qubit);
-            }
-          }
-          AnalysisOperation::X(qb, radians) => {
-            builder.x(qb, *radians);
-          }
-          AnalysisOperation::Y(qb, radians) => {
-            builder.y(qb, *radians);
-          }
-          AnalysisOperation::Z(qb, radians) => {
-            builder.z(qb, *radians);
-          }
-          AnalysisOperation::CX(controls, targets, radians) => {
-            builder.cx(controls, targets, *radians);
-          }
-          AnalysisOperation::CZ(controls, targets, radians) => {
-            builder.cz(controls, targets, *radians);
-          }
-          AnalysisOperation::CY(controls, targets, radians) => {
-            builder.cy(controls, targets, *radians);
-          }
-          AnalysisOperation::Measure(qbs) => {
-            for qb in qbs {
-              builder.measure(qb);
-            }
-          }
+    let start = Instant::now();
+    let (partitions, jobs) = self.create_jobs();
+    let results = self.dispatch(&partitions, &jobs, self.config.parallel_dispatch);
+    let query_result = QuantumProjection::join(&partitions, results);
+    let took = start.elapsed();
+    log!(Level::Info, "QPU execution took {}ms.", took.as_millis());
+    self.complete(query_result, Some((&jobs, took)))
+  }
+
+  /// Dispatches these jobs, with any circuit that's already been executed against the same target
+  /// served from the result cache instead.
+  fn dispatch(
+    &self, partitions: &Vec<CircuitPartition>, jobs: &Vec<DispatchJob>, concurrent: bool
+  ) -> Vec<AnalysisResult> {
+    let start = Instant::now();
+    let cache = match self.result_cache.as_ref() {
+      None => {
+        let results = RuntimeCollection::dispatch(jobs, concurrent);
+        self.record_dispatch(start.elapsed(), jobs.len(), 0);
+        return results;
+      }
+      Some(val) => val
+    };
+
+    let keys = zip(partitions, jobs)
+      .map(|(partition, (runtime, builder, options))| {
+        // Builders can define their own shot count which is used if we don't override it.
+        let options = ExecutionOptions {
+          shots: options.shots.or(builder.shot_count()),
+          ..options.clone()
+        };
+        CacheKey::new(&partition.instructions, &runtime.identity(), &options)
+      })
+      .collect::<Vec<_>>();
+
+    let mut results = keys
+      .iter()
+      .map(|key| with_mutable!(cache.get(key, &self.config)))
+      .collect::<Vec<_>>();
+    let missing = range(0, results.len())
+      .filter(|index| results[*index].is_none())
+      .collect::<Vec<_>>();
+    let cache_hits = results.len() - missing.len();
+    if self.is_tracing() && missing.len() < results.len() {
+      log!(
+        Level::Info,
+        "Reusing results for {} previously-executed circuits.",
+        results.len() - missing.len()
+      );
+    }
+
+    if !missing.is_empty() {
+      let missing_jobs = missing
+        .iter()
+        .map(|index| jobs[*index].clone())
+        .collect::<Vec<_>>();
+      let executed = RuntimeCollection::dispatch(&missing_jobs, concurrent);
+      for (index, result) in zip(missing, executed) {
+        with_mutable!(cache.insert(keys[index].clone(), &result, &self.config));
+        results[index] = Some(result);
+      }
+    }
+
+    self.record_dispatch(start.elapsed(), jobs.len(), cache_hits);
+    results.into_iter().map(Option::unwrap).collect()
+  }
+
+  fn record_dispatch(&self, took: Duration, circuits: usize, cache_hits: usize) {
+    if let Some(statistics) = self.statistics.as_ref() {
+      with_mutable!(statistics.record_dispatch(took, circuits, cache_hits));
+    }
+  }
+
+  /// Concretizes every projection passed in at once, with every backend job across all of them
+  /// dispatched concurrently. Used when multiple results are needed at the same time, such as
+  /// when they're returned.
+  pub fn concretize_all(projections: &Vec<Ptr<QuantumProjection>>) {
+    let mut pending: Vec<(
+      Ptr<QuantumProjection>,
+      Vec<CircuitPartition>,
+      Vec<DispatchJob>
+    )> = Vec::new();
+    for projection in projections {
+      let projection = &QuantumProjection::current(projection);
+      if projection.cached_result.is_some()
+        || pending.iter().any(|(val, ..)| Ptr::eq(val, projection))
+      {
+        continue;
+      }
+
+      let solved = with_mutable!(projection.solve());
+      if !solved.is_empty() {
+        with_mutable!(projection.complete(solved, None));
+        continue;
+      }
+
+      let (partitions, jobs) = projection.create_jobs();
+      pending.push((projection.clone(), partitions, jobs));
+    }
+
+    if pending.is_empty() {
+      return;
+    }
+
+    let start = Instant::now();
+    let all_partitions = pending
+      .iter()
+      .flat_map(|(_, partitions, _)| partitions.iter().cloned())
+      .collect::<Vec<_>>();
+    let all_jobs = pending
+      .iter()
+      .flat_map(|(_, _, jobs)| jobs.iter().cloned())
+      .collect::<Vec<_>>();
+    // Every projection in a run shares the same cache, so any of them can dispatch for the rest.
+    let mut results = pending[0]
+      .0
+      .dispatch(&all_partitions, &all_jobs, true)
+      .into_iter();
+    let took = start.elapsed();
+    for (projection, partitions, jobs) in pending {
+      let projection_results = results.by_ref().take(jobs.len()).collect::<Vec<_>>();
+      with_mutable!(projection.complete(
+        QuantumProjection::join(&partitions, projection_results),
+        Some((&jobs, took))
+      ));
+    }
+
+    log!(
+      Level::Info,
+      "Concurrent QPU execution of {} circuits took {}ms.",
+      all_jobs.len(),
+      took.as_millis()
+    );
+  }
+
+  /// Collapses the results down to a single shot, consistent with every qubit that's already been
+  /// read from this projection.
+  fn follow_shot(&mut self, result: AnalysisResult) -> AnalysisResult {
+    let mut consistent = result.clone();
+    consistent.distribution.retain(|key, _| {
+      let bits = key.chars().rev().collect::<Vec<_>>();
+      self
+        .pinned
+        .iter()
+        .all(|(position, bit)| bits.get(*position).map_or(true, |val| val == bit))
+    });
+
+    let sampler = self
+      .sampler
+      .get_or_insert_with(|| Ptr::from(ShotSampler::with_seed(self.options.seed)));
+    match sampler.sample(&consistent) {
+      Some(shot) => AnalysisResult::new(HashMap::from([(shot, 1)])),
+      None if result.is_empty() => result,
+      None => panic!("No results are consistent with the shot being followed.")
+    }
+  }
+
+  /// Caches the final result of this projection, logging the circuit if required. Dispatched
+  /// is the jobs sent to backends and how long they took, or none if the results were solved.
+  fn complete(
+    &mut self, result: AnalysisResult, dispatched: Option<(&Vec<DispatchJob>, Duration)>
+  ) -> &AnalysisResult {
+    self.cached_result = Some(if self.reduction == ResultReduction::PerShot {
+      self.follow_shot(result)
+    } else {
+      result
+    });
+
+    let mut backends = Vec::new();
+    if let Some((jobs, _)) = dispatched {
+      for (runtime, ..) in jobs {
+        let identity = runtime.identity();
+        if !backends.contains(&identity) {
+          backends.push(identity);
         }
       }
+    }
 
-      query_result = runtime.execute(&builder);
-      let took = start.elapsed();
-      log!(Level::Info, "QPU execution took {}ms.", took.as_millis());
+    if let Some(statistics) = self.statistics.as_ref() {
+      let mut projection = ProjectionStatistics::from_operations(self.index, &self.operations());
+      if let Some((jobs, took)) = dispatched {
+        projection.circuits = jobs.len();
+        projection.qpu_time = took;
+        projection.backends = backends.clone();
+      } else {
+        projection.solved = true;
+      }
+      with_mutable!(statistics.record_projection(projection));
     }
 
-    self.cached_result = Some(query_result);
+    self.config.events.emit(|| {
+      let mut results = self
+        .cached_result
+        .as_ref()
+        .unwrap()
+        .distribution
+        .iter()
+        .map(|(key, count)| (key.clone(), *count))
+        .collect::<Vec<_>>();
+      results.sort();
+      TraceEvent::ProjectionConcretized {
+        projection: self.index,
+        solved: dispatched.is_none(),
+        backends,
+        results
+      }
+    });
 
     if self.is_tracing() {
       log!(Level::Info, "Executed circuit:");
@@ -469,7 +1434,19 @@ impl Clone for QuantumProjection {
       instructions: self.instructions.clone(),
       cached_result: self.cached_result.clone(),
       cached_filtered: self.cached_filtered.clone(),
-      solver_config: self.solver_config.clone()
+      joint_measures: self.joint_measures.clone(),
+      solver_config: self.solver_config.clone(),
+      config: self.config.clone(),
+      result_cache: self.result_cache.clone(),
+      options: self.options.clone(),
+      reduction: self.reduction.clone(),
+      statistics: self.statistics.clone(),
+      index: self.index,
+      sampler: self.sampler.clone(),
+      pinned: self.pinned.clone(),
+      added_at: self.added_at.clone(),
+      step_count: self.step_count.clone(),
+      merged_into: self.merged_into.clone()
     }
   }
 }
@@ -491,6 +1468,56 @@ impl PartialOrd for QuantumProjection {
 
 impl Eq for QuantumProjection {}
 
+/// Fraction of shots which need to agree for a result to not be ambiguous, if not specified.
+pub const DEFAULT_AMBIGUITY_THRESHOLD: f64 = 0.9;
+
+/// How a distribution of results gets reduced to a single classical bit when a program
+/// branches on, or otherwise reads, a measurement.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ResultReduction {
+  /// Whichever of zero or one was seen most, with ties going to zero.
+  #[default]
+  Majority,
+
+  /// Every shot has to agree, otherwise execution fails.
+  Unanimous,
+
+  /// Picks a single shot and follows it, so every later read of the same projection is
+  /// consistent with the shot that was picked.
+  PerShot,
+
+  /// Like majority, but fails unless the winning side makes up at least this fraction of the
+  /// shots.
+  ErrorOnAmbiguity(f64)
+}
+
+impl ResultReduction {
+  pub fn from_name(name: &str, threshold: Option<f64>) -> Option<ResultReduction> {
+    match name.to_lowercase().as_str() {
+      "majority" => Some(ResultReduction::Majority),
+      "unanimous" => Some(ResultReduction::Unanimous),
+      "per-shot" | "per_shot" => Some(ResultReduction::PerShot),
+      "error" | "error-on-ambiguity" | "error_on_ambiguity" => Some(
+        ResultReduction::ErrorOnAmbiguity(threshold.unwrap_or(DEFAULT_AMBIGUITY_THRESHOLD))
+      ),
+      _ => None
+    }
+  }
+}
+
+impl Display for ResultReduction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ResultReduction::Majority => f.write_str("majority"),
+      ResultReduction::Unanimous => f.write_str("unanimous"),
+      ResultReduction::PerShot => f.write_str("per-shot"),
+      ResultReduction::ErrorOnAmbiguity(threshold) => {
+        write!(f, "error-on-ambiguity ({threshold})")
+      }
+    }
+  }
+}
+
 /// Non-deferred result distribution from a QPU execution.
 pub struct AnalysisResult {
   pub distribution: HashMap<String, i64>
@@ -504,10 +1531,8 @@ impl AnalysisResult {
   pub fn from_solver_result(res: Vec<SolverResult>) -> AnalysisResult {
     let mut distribution = HashMap::new();
     for result in res.iter() {
-      distribution.insert(
-        result.bitstring.clone(),
-        (result.probability * 100.0) as i64
-      );
+      *distribution.entry(result.bitstring.clone()).or_insert(0) +=
+        (result.probability * 100.0) as i64;
     }
     AnalysisResult::new(distribution)
   }
@@ -523,6 +1548,20 @@ impl AnalysisResult {
 
   pub fn empty() -> AnalysisResult { AnalysisResult::default() }
 
+  /// Collapses each bitstring down to a single bit of whether it has an odd number of ones.
+  pub fn parity(&self) -> AnalysisResult {
+    let mut distribution = HashMap::new();
+    for (key, count) in self.distribution.iter() {
+      let parity = if key.matches('1').count() % 2 == 0 {
+        "0"
+      } else {
+        "1"
+      };
+      *distribution.entry(parity.to_string()).or_insert(0) += count;
+    }
+    AnalysisResult::new(distribution)
+  }
+
   /// Check if this distribution can be considered true/false.
   ///
   /// This is done by counting the instances of 0/1 in a particular bitstring, and if one
@@ -531,8 +1570,20 @@ impl AnalysisResult {
   ///
   /// If the counts of both at the end are equal it will default to false.
   pub fn is_one(&self) -> bool {
+    let (zeros, ones, _) = self.tally();
+
+    // Default to zero if equals.
+    ones > zeros
+  }
+
+  pub fn is_zero(&self) -> bool { !self.is_one() }
+
+  /// Counts of the shots which lean towards zero, towards one, and neither, as described in
+  /// [`AnalysisResult::is_one`].
+  fn tally(&self) -> (i64, i64, i64) {
     let mut zeros = 0;
     let mut ones = 0;
+    let mut neither = 0;
     for (key, val) in self.distribution.iter() {
       let length = key.len();
       let boundary = length / 2;
@@ -544,21 +1595,64 @@ impl AnalysisResult {
         zeros += val;
       } else if one_count > boundary {
         ones += val;
+      } else {
+        neither += val;
       }
     }
 
-    // Default to zero if equals.
-    ones > zeros
+    (zeros, ones, neither)
   }
 
-  pub fn is_zero(&self) -> bool { !self.is_one() }
+  /// Reduces this distribution down to a single classical bit using the policy, or errors if
+  /// the policy considers the results ambiguous.
+  pub fn reduce(&self, reduction: &ResultReduction) -> Result<bool, String> {
+    let (zeros, ones, neither) = self.tally();
+    match reduction {
+      ResultReduction::Majority | ResultReduction::PerShot => Ok(ones > zeros),
+      ResultReduction::Unanimous => {
+        let outcomes = [zeros, ones, neither]
+          .iter()
+          .filter(|val| **val > 0)
+          .count();
+        if outcomes > 1 {
+          Err(format!(
+            "Result isn't unanimous, {ones} shots were one and {zeros} zero{}.",
+            if neither > 0 {
+              format!(" with {neither} undecided")
+            } else {
+              String::new()
+            }
+          ))
+        } else {
+          Ok(ones > 0)
+        }
+      }
+      ResultReduction::ErrorOnAmbiguity(threshold) => {
+        let total = zeros + ones + neither;
+        if total == 0 {
+          return Ok(false);
+        }
+
+        let share = zeros.max(ones) as f64 / total as f64;
+        if zeros == ones || share < *threshold {
+          Err(format!(
+            "Result is ambiguous, {ones} shots were one and {zeros} zero out of {total} when \
+             {:.0}% need to agree.",
+            threshold * 100.0
+          ))
+        } else {
+          Ok(ones > zeros)
+        }
+      }
+    }
+  }
 }
 
 impl PartialEq for AnalysisResult {
   fn eq(&self, other: &Self) -> bool {
     // TODO: decide whether to do proper distribution analysis
     let self_is_one = self.is_one();
-    let other_is_one = self.is_one();
+    let other_is_one = other.is_one();
     self_is_one == other_is_one
   }
 }
@@ -578,3 +1672,240 @@ impl Display for AnalysisResult {
     f.debug_map().entries(self.distribution.iter()).finish()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::projections::{
+    AnalysisResult, QuantumProjection, ResultReduction, DEFAULT_AMBIGUITY_THRESHOLD
+  };
+  use crate::execution::RuntimeCollection;
+  use crate::hardware::Qubit;
+  use crate::instructions::Pauli;
+  use crate::qasm::QasmVersion;
+  use crate::smart_pointers::Ptr;
+  use std::collections::{BTreeSet, HashMap, HashSet};
+  use std::f64::consts::PI;
+
+  #[test]
+  fn partition_and_join() {
+    let mut projection = QuantumProjection::new(&Ptr::from(RuntimeCollection::default()));
+    let (q0, q1, q2) = (Qubit::new(0), Qubit::new(1), Qubit::new(2));
+    projection.X(q0.clone(), PI);
+    projection.X(q1.clone(), PI / 2.);
+    projection.CX(vec![q0.clone()], q2.clone(), PI);
+    projection.Measure(vec![q0]);
+    projection.Measure(vec![q1]);
+    projection.Measure(vec![q2]);
+
+    let partitions = projection.partition();
+    assert_eq!(partitions.len(), 2);
+    assert_eq!(partitions[0].measures, vec![0, 2]);
+    assert_eq!(partitions[1].measures, vec![1]);
+
+    let joined = QuantumProjection::join(&partitions, vec![
+      AnalysisResult::new(HashMap::from([("11".to_string(), 100)])),
+      AnalysisResult::new(HashMap::from([
+        ("0".to_string(), 50),
+        ("1".to_string(), 50)
+      ]))
+    ]);
+    assert_eq!(joined.distribution.len(), 2);
+    assert_eq!(joined.distribution.get("101"), Some(&50));
+    assert_eq!(joined.distribution.get("111"), Some(&50));
+  }
+
+  #[test]
+  fn join_keeps_rare_outcomes() {
+    let mut projection = QuantumProjection::new(&Ptr::from(RuntimeCollection::default()));
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    projection.X(q0.clone(), PI / 2.);
+    projection.X(q1.clone(), PI / 2.);
+    projection.Measure(vec![q0]);
+    projection.Measure(vec![q1]);
+
+    // Each rare outcome is under half a shot on its own, so rounding would drop them entirely.
+    let partitions = projection.partition();
+    let joined = QuantumProjection::join(&partitions, vec![
+      AnalysisResult::new(HashMap::from([("0".to_string(), 99), ("1".to_string(), 1)])),
+      AnalysisResult::new(HashMap::from([("0".to_string(), 97), ("1".to_string(), 3)]))
+    ]);
+    assert_eq!(joined.distribution.values().sum::<i64>(), 100);
+    assert_eq!(joined.distribution.get("00"), Some(&96));
+    assert_eq!(joined.distribution.get("10"), Some(&3));
+    assert_eq!(joined.distribution.get("01"), Some(&1));
+  }
+
+  #[test]
+  fn compact_qubits() {
+    let mut projection = QuantumProjection::new(&Ptr::from(RuntimeCollection::default()));
+    let (q5, q7, q9) = (Qubit::new(5), Qubit::new(7), Qubit::new(9));
+    projection.X(q5.clone(), PI);
+    projection.Measure(vec![q5.clone()]);
+    projection.Reset(vec![q5]);
+    projection.CX(vec![q7.clone()], q9.clone(), PI);
+    projection.Measure(vec![q7]);
+    projection.Measure(vec![q9]);
+
+    // Qubit 5 is reset for good before 7 is first used, so they can share a backend qubit.
+    let (mapping, width) = QuantumProjection::compact_qubits(&projection.instructions);
+    assert_eq!(width, 2);
+    assert_eq!(mapping, HashMap::from([(5, 0), (7, 0), (9, 1)]));
+    assert_eq!(projection.required_features().qubits, 2);
+    assert_eq!(projection.required_features().controls, 1);
+  }
+
+  #[test]
+  fn absorb_renumbers_measures() {
+    let engines = Ptr::from(RuntimeCollection::default());
+    let mut step = Ptr::from(1);
+    let mut target = Ptr::from(QuantumProjection::new(&engines));
+    let mut other = Ptr::from(QuantumProjection::new(&engines));
+    target.attach_step_count(&step);
+    other.attach_step_count(&step);
+
+    let (q0, q1, q2, q3) = (Qubit::new(0), Qubit::new(1), Qubit::new(2), Qubit::new(3));
+    target.Measure(vec![q0]);
+    *step = 2;
+    other.MeasurePauli(vec![Pauli::Z, Pauli::Z], vec![q2, q3]);
+    *step = 3;
+    target.Measure(vec![q1]);
+    target.pinned.insert(1, '1');
+    other.pinned.insert(0, '0');
+
+    // The joint measure lands between the targets two, pushing its second one back.
+    QuantumProjection::absorb(&target, &other);
+    assert_eq!(target.pinned, HashMap::from([(3, '1'), (1, '0')]));
+    assert_eq!(
+      target.joint_measures,
+      HashSet::from([BTreeSet::from([1, 2])])
+    );
+  }
+
+  #[test]
+  fn partition_on_reset() {
+    let mut projection = QuantumProjection::new(&Ptr::from(RuntimeCollection::default()));
+    let q0 = Qubit::new(0);
+    projection.X(q0.clone(), PI);
+    projection.Measure(vec![q0.clone()]);
+    projection.Reset(vec![q0.clone()]);
+    projection.X(q0.clone(), PI / 2.);
+    projection.Measure(vec![q0]);
+
+    let partitions = projection.partition();
+    assert_eq!(partitions.len(), 2);
+    assert_eq!(partitions[0].measures, vec![0]);
+    assert_eq!(partitions[1].measures, vec![1]);
+  }
+
+  #[test]
+  fn absorb_projection() {
+    let engines = Ptr::from(RuntimeCollection::default());
+    let mut step = Ptr::from(0);
+    let mut first = Ptr::from(QuantumProjection::new(&engines));
+    let mut second = Ptr::from(QuantumProjection::new(&engines));
+    first.attach_step_count(&step);
+    second.attach_step_count(&step);
+
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    *step = 1;
+    second.X(q1.clone(), PI);
+    *step = 2;
+    first.X(q0.clone(), PI);
+    *step = 3;
+    second.Measure(vec![q1.clone()]);
+    *step = 4;
+    first.Measure(vec![q0.clone()]);
+
+    QuantumProjection::absorb(&first, &second);
+    let order = first
+      .instructions
+      .iter()
+      .map(|inst| (inst.name(), inst.qubits()[0].index))
+      .collect::<Vec<_>>();
+    assert_eq!(order, vec![
+      ("x", 1),
+      ("x", 0),
+      ("measure", 1),
+      ("measure", 0)
+    ]);
+
+    // Qubit 1 was measured first so is the rightmost bit, whichever projection gets asked.
+    first.cached_result = Some(AnalysisResult::new(HashMap::from([(
+      "01".to_string(),
+      100
+    )])));
+    assert_eq!(
+      first.results_for(&vec![q1]).distribution,
+      HashMap::from([("1".to_string(), 100)])
+    );
+    assert_eq!(
+      second.results_for(&vec![q0]).distribution,
+      HashMap::from([("0".to_string(), 100)])
+    );
+  }
+  #[test]
+  fn projection_to_qasm() {
+    let mut projection = QuantumProjection::new(&Ptr::from(RuntimeCollection::default()));
+    let (q0, q1) = (Qubit::new(0), Qubit::new(1));
+    projection.Reset(vec![q0.clone(), q1.clone()]);
+    projection.Y(q0.clone(), PI / 2.);
+    projection.CZ(vec![q0.clone()], q1.clone(), PI);
+    projection.Measure(vec![q1, q0]);
+
+    assert_eq!(
+      projection.to_qasm(QasmVersion::V2).unwrap(),
+      "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[2];\ncreg c[2];\nreset q[0];\nreset q[1];\n\
+       ry(1.5707963267948966) q[0];\ncrz(3.141592653589793) q[0], q[1];\n\
+       measure q[1] -> c[0];\nmeasure q[0] -> c[1];\n"
+    );
+  }
+
+  #[test]
+  fn result_reductions() {
+    let skewed = AnalysisResult::new(HashMap::from([
+      ("1".to_string(), 51),
+      ("0".to_string(), 49)
+    ]));
+    assert_eq!(skewed.reduce(&ResultReduction::Majority), Ok(true));
+    assert!(skewed.reduce(&ResultReduction::Unanimous).is_err());
+    assert!(skewed
+      .reduce(&ResultReduction::ErrorOnAmbiguity(0.9))
+      .unwrap_err()
+      .starts_with("Result is ambiguous, 51 shots were one and 49 zero out of 100"));
+    assert_eq!(
+      skewed.reduce(&ResultReduction::ErrorOnAmbiguity(0.5)),
+      Ok(true)
+    );
+
+    let tied = AnalysisResult::new(HashMap::from([
+      ("1".to_string(), 50),
+      ("0".to_string(), 50)
+    ]));
+    assert_eq!(tied.reduce(&ResultReduction::Majority), Ok(false));
+    assert!(tied
+      .reduce(&ResultReduction::ErrorOnAmbiguity(0.0))
+      .is_err());
+
+    assert_eq!(
+      AnalysisResult::one().reduce(&ResultReduction::Unanimous),
+      Ok(true)
+    );
+    assert_eq!(
+      AnalysisResult::zero().reduce(&ResultReduction::Unanimous),
+      Ok(false)
+    );
+    assert!(AnalysisResult::one() != AnalysisResult::zero());
+
+    assert_eq!(
+      ResultReduction::from_name("error-on-ambiguity", None),
+      Some(ResultReduction::ErrorOnAmbiguity(
+        DEFAULT_AMBIGUITY_THRESHOLD
+      ))
+    );
+    assert_eq!(
+      ResultReduction::from_name("Per-Shot", None),
+      Some(ResultReduction::PerShot)
+    );
+    assert_eq!(ResultReduction::from_name("coin", None), None);
+  }
+}
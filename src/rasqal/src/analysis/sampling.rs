@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::projections::AnalysisResult;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Picks individual shots out of result distributions, weighted by how often each result was
+/// seen. Seeded, so the same seed always picks the same shots for the same results.
+pub struct ShotSampler {
+  state: u64
+}
+
+impl ShotSampler {
+  pub fn new(seed: u64) -> ShotSampler { ShotSampler { state: seed } }
+
+  /// Seeded with this if it's set, otherwise from the current time.
+  pub fn with_seed(seed: Option<i64>) -> ShotSampler {
+    ShotSampler::new(seed.map_or_else(
+      || {
+        SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .map_or(0, |val| val.as_nanos() as u64)
+      },
+      |val| val as u64
+    ))
+  }
+
+  /// Next value in the SplitMix64 sequence.
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut value = self.state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+  }
+
+  /// Seed for another sampler, so a whole set of them can be derived from one seed.
+  pub fn next_seed(&mut self) -> i64 { self.next_u64() as i64 }
+
+  /// Picks a single bitstring from these results, or none if there's nothing to pick.
+  pub fn sample(&mut self, results: &AnalysisResult) -> Option<String> {
+    // Distributions are unordered, so sort to make sure a seed always picks the same thing.
+    let mut entries = results
+      .distribution
+      .iter()
+      .filter(|(_, count)| **count > 0)
+      .collect::<Vec<_>>();
+    entries.sort();
+
+    let total = entries.iter().map(|(_, count)| **count as u64).sum::<u64>();
+    if total == 0 {
+      return None;
+    }
+
+    let mut target = self.next_u64() % total;
+    for (key, count) in entries {
+      let count = *count as u64;
+      if target < count {
+        return Some(key.clone());
+      }
+      target -= count;
+    }
+
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::projections::AnalysisResult;
+  use crate::analysis::sampling::ShotSampler;
+  use std::collections::HashMap;
+
+  #[test]
+  fn seeded_sampling() {
+    let results = AnalysisResult::new(HashMap::from([
+      ("00".to_string(), 500),
+      ("11".to_string(), 500),
+      ("01".to_string(), 0)
+    ]));
+
+    let picks = |seed| {
+      let mut sampler = ShotSampler::new(seed);
+      (0..50)
+        .map(|_| sampler.sample(&results).unwrap())
+        .collect::<Vec<_>>()
+    };
+
+    let first = picks(7);
+    assert_eq!(first, picks(7));
+    assert!(first.iter().any(|val| val == "00"));
+    assert!(first.iter().any(|val| val == "11"));
+    assert!(!first.iter().any(|val| val == "01"));
+    assert_eq!(ShotSampler::new(1).sample(&AnalysisResult::empty()), None);
+  }
+}
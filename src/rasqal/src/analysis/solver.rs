@@ -1248,23 +1248,83 @@ impl Mul for StateFragment {
   }
 }
 
+/// Which solver implementation a projection hands its circuit to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolverStrategy {
+  /// Entanglement-tracking solver, [`QuantumSolver`].
+  Entanglement,
+
+  /// Matrix-product-state solver, see [`crate::analysis::mps::MatrixProductState`].
+  MatrixProductState
+}
+
+impl SolverStrategy {
+  pub fn from_name(name: &str) -> Option<SolverStrategy> {
+    match name.to_lowercase().as_str() {
+      "entanglement" => Some(SolverStrategy::Entanglement),
+      "mps" | "matrix-product-state" => Some(SolverStrategy::MatrixProductState),
+      _ => None
+    }
+  }
+}
+
 #[derive(Clone)]
 pub struct SolverConfig {
-  pub active: bool
+  pub active: bool,
+  pub strategy: SolverStrategy,
+  pub max_bond_dimension: usize
 }
 
 impl SolverConfig {
-  pub fn new(active: bool) -> SolverConfig { SolverConfig { active } }
+  pub fn new(active: bool) -> SolverConfig {
+    SolverConfig {
+      active,
+      strategy: SolverStrategy::Entanglement,
+      max_bond_dimension: DEFAULT_BOND_DIMENSION
+    }
+  }
 
   pub fn off() -> SolverConfig { SolverConfig::new(false) }
 
   pub fn on() -> SolverConfig { SolverConfig::new(true) }
 
   pub fn with_config(config: &Ptr<RasqalConfig>) -> SolverConfig {
-    SolverConfig::new(config.solver_active)
+    SolverConfig {
+      active: config.solver_active,
+      strategy: config.solver_strategy,
+      max_bond_dimension: config.max_bond_dimension
+    }
   }
 }
 
+/// Bond dimension the matrix-product-state solver is capped at unless configured otherwise.
+pub const DEFAULT_BOND_DIMENSION: usize = 64;
+
+/// Shared interface for all circuit solvers so projections can swap between them.
+///
+/// Every measure gets its own classical bit, so a qubit measured multiple times keeps each result.
+/// Solved bitstrings hold one bit per measure in the order they were made, with X for any bit the
+/// solver couldn't resolve.
+pub trait CircuitSolver {
+  fn reset(&self, qb: &Qubit);
+
+  fn measure(&self, qb: &Qubit);
+
+  fn X(&self, qb: &Qubit, radians: &f64);
+
+  fn Y(&self, qb: &Qubit, radians: &f64);
+
+  fn Z(&self, qb: &Qubit, radians: &f64);
+
+  fn CX(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64);
+
+  fn CY(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64);
+
+  fn CZ(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64);
+
+  fn solve(&self) -> Vec<SolverResult>;
+}
+
 #[derive(Clone)]
 pub struct SolverResult {
   pub bitstring: String,
@@ -1561,6 +1621,12 @@ pub struct QuantumSolver {
   qubits: Ptr<HashMap<i64, Ptr<AnalysisQubit>>>,
   clusters: Ptr<HashMap<i64, Ptr<EntanglementCluster>>>,
   measures: Ptr<HashMap<i64, MeasureAnalysis>>,
+
+  /// Solver qubit currently standing in for each circuit qubit when driven as a [CircuitSolver].
+  aliases: Ptr<HashMap<i64, i64>>,
+
+  /// Solver qubit holding the copy made by each circuit measure, in the order they were made.
+  measured: Ptr<Vec<i64>>,
   trace_module: Ptr<TracingModule>,
   probability_range: f64,
   max_entanglements: usize
@@ -1572,6 +1638,8 @@ impl QuantumSolver {
       qubits: Ptr::from(HashMap::default()),
       clusters: Ptr::from(HashMap::default()),
       measures: Ptr::from(HashMap::default()),
+      aliases: Ptr::from(HashMap::default()),
+      measured: Ptr::from(Vec::new()),
       trace_module: Ptr::from(TracingModule::default()),
       probability_range: 0.25,
       max_entanglements: 20
@@ -1585,6 +1653,8 @@ impl QuantumSolver {
       qubits: Ptr::from(HashMap::default()),
       measures: Ptr::from(HashMap::default()),
       clusters: Ptr::from(HashMap::default()),
+      aliases: Ptr::from(HashMap::default()),
+      measured: Ptr::from(Vec::new()),
       trace_module,
       probability_range: 0.25,
       max_entanglements: 20
@@ -1604,6 +1674,28 @@ impl QuantumSolver {
     }
   }
 
+  /// Adds a qubit in its default state at the next unused index.
+  fn fresh_qubit(&self) -> Qubit {
+    let index = self.qubits.len() as i64;
+    self.qubit_for(&index);
+    Qubit::new(index)
+  }
+
+  /// Gets the solver qubit standing in for this circuit qubit, allocating one if it's new.
+  fn alias_for(&self, qb: &Qubit) -> Qubit {
+    if let Some(index) = self.aliases.get(&qb.index) {
+      Qubit::new(*index)
+    } else {
+      let alias = self.fresh_qubit();
+      with_mutable_self!(self.aliases.insert(qb.index, alias.index));
+      alias
+    }
+  }
+
+  fn aliases_for(&self, qbs: &Vec<Qubit>) -> Vec<Qubit> {
+    qbs.iter().map(|qb| self.alias_for(qb)).collect()
+  }
+
   /// Gets the cluster for this index. Inserts the qubit into both solver and cluster, creating a
   /// new cluster if required. Don't use this if you only want to fetch a cluster without modifying
   /// it.
@@ -2017,6 +2109,72 @@ impl QuantumSolver {
   }
 }
 
+/// Circuit qubits are aliased onto solver qubits so every measure can be copied onto a qubit of
+/// its own and every reset can start from a fresh one, the same as the wires of the MPS solver.
+impl CircuitSolver for QuantumSolver {
+  fn reset(&self, qb: &Qubit) {
+    let fresh = self.fresh_qubit();
+    with_mutable_self!(self.aliases.insert(qb.index, fresh.index));
+  }
+
+  fn measure(&self, qb: &Qubit) {
+    let copy = self.fresh_qubit();
+    QuantumSolver::CX(self, &vec![self.alias_for(qb)], &copy, &PI);
+    QuantumSolver::measure(self, &copy);
+    with_mutable_self!(self.measured.push(copy.index));
+  }
+
+  fn X(&self, qb: &Qubit, radians: &f64) { QuantumSolver::X(self, &self.alias_for(qb), radians) }
+
+  fn Y(&self, qb: &Qubit, radians: &f64) { QuantumSolver::Y(self, &self.alias_for(qb), radians) }
+
+  fn Z(&self, qb: &Qubit, radians: &f64) { QuantumSolver::Z(self, &self.alias_for(qb), radians) }
+
+  fn CX(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    QuantumSolver::CX(
+      self,
+      &self.aliases_for(controls),
+      &self.alias_for(target),
+      radians
+    )
+  }
+
+  fn CY(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    QuantumSolver::CY(
+      self,
+      &self.aliases_for(controls),
+      &self.alias_for(target),
+      radians
+    )
+  }
+
+  fn CZ(&self, controls: &Vec<Qubit>, target: &Qubit, radians: &f64) {
+    QuantumSolver::CZ(
+      self,
+      &self.aliases_for(controls),
+      &self.alias_for(target),
+      radians
+    )
+  }
+
+  fn solve(&self) -> Vec<SolverResult> {
+    QuantumSolver::solve(self)
+      .into_iter()
+      .map(|result| {
+        let bits = result.bitstring.chars().collect::<Vec<_>>();
+        SolverResult {
+          bitstring: self
+            .measured
+            .iter()
+            .map(|index| bits.get(*index as usize).copied().unwrap_or('X'))
+            .collect(),
+          probability: result.probability
+        }
+      })
+      .collect()
+  }
+}
+
 impl Display for QuantumSolver {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     f.write_str("\nSolver:\n");
@@ -2058,7 +2216,7 @@ impl Display for QuantumSolver {
 
 #[cfg(test)]
 mod tests {
-  use crate::analysis::solver::{GateFragment, QuantumSolver, QubitFragment};
+  use crate::analysis::solver::{CircuitSolver, GateFragment, QuantumSolver, QubitFragment};
   use crate::hardware::Qubit;
   use crate::runtime::{ActiveTracers, TracingModule};
   use crate::smart_pointers::Ptr;
@@ -2085,6 +2243,22 @@ mod tests {
     assert!(results[1].probability >= 49.9 && results[1].probability <= 50.1);
   }
 
+  #[test]
+  fn remeasure() {
+    let solver: Box<dyn CircuitSolver> = Box::new(QuantumSolver::new());
+    let q0 = Qubit::new(0);
+    solver.X(&q0, &PI);
+    solver.measure(&q0);
+    solver.reset(&q0);
+    solver.measure(&q0);
+    let results = solver.solve();
+
+    // Each measure keeps its own bit, rather than both reading the final state of the qubit.
+    assert!(results
+      .iter()
+      .any(|val| val.bitstring == "10" && val.probability >= 99.9));
+  }
+
   #[test]
   fn X() {
     let mut qubit = QubitFragment::DefaultQubit();
@@ -0,0 +1,386 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::projections::AnalysisResult;
+use crate::builders::{CircuitBuilder, CircuitOperation, CircuitRecorder, QuantumBackend};
+use crate::config::ExecutionOptions;
+use crate::execution::text_to_module;
+use crate::features::QuantumFeatures;
+use crate::hardware::Qubit;
+use inkwell::context::Context;
+use std::f64::consts::PI;
+
+/// Circuit rendered as a standalone Base Profile QIR module.
+#[derive(Clone, Debug)]
+pub struct BaseProfileModule {
+  /// Name of the entry-point function.
+  pub name: String,
+
+  /// Textual LLVM IR of the module.
+  pub ir: String,
+  pub required_qubits: i64,
+  pub required_results: i64
+}
+
+impl BaseProfileModule {
+  /// Verifies the module with LLVM and returns it as bitcode.
+  pub fn bitcode(&self) -> Result<Vec<u8>, String> {
+    let context = Context::create();
+    let module = text_to_module(&self.ir, &context)?;
+    module.verify().map_err(|err| err.to_string())?;
+    Ok(module.write_bitcode_to_memory().as_slice().to_vec())
+  }
+}
+
+/// Builds up the body and declarations of a base profile entry-point.
+struct BaseProfileEmitter {
+  body: String,
+  declarations: Vec<(String, String)>
+}
+
+impl BaseProfileEmitter {
+  fn new() -> BaseProfileEmitter {
+    BaseProfileEmitter {
+      body: String::new(),
+      declarations: Vec::new()
+    }
+  }
+
+  fn call(&mut self, function: &str, parameters: &str, arguments: &[String]) {
+    if !self.declarations.iter().any(|(name, _)| name == function) {
+      self
+        .declarations
+        .push((function.to_string(), parameters.to_string()));
+    }
+
+    self.body.push_str(&format!(
+      "  call void @{function}({})\n",
+      arguments.join(", ")
+    ));
+  }
+
+  fn qubit(qb: &Qubit) -> String { format!("%Qubit* inttoptr (i64 {} to %Qubit*)", qb.index) }
+
+  fn result(index: i64) -> String { format!("%Result* inttoptr (i64 {index} to %Result*)") }
+
+  /// Doubles are written as their hex bit-pattern, which LLVM reads back exactly.
+  fn double(radians: f64) -> String { format!("double 0x{:016X}", radians.to_bits()) }
+
+  fn rotation(&mut self, gate: &str, qb: &Qubit, radians: f64) {
+    self.call(
+      &format!("__quantum__qis__{gate}__body"),
+      "double, %Qubit*",
+      &[Self::double(radians), Self::qubit(qb)]
+    );
+  }
+
+  fn gate(&mut self, gate: &str, qubits: &[&Qubit]) {
+    self.call(
+      &format!("__quantum__qis__{gate}__body"),
+      &vec!["%Qubit*"; qubits.len()].join(", "),
+      &qubits.iter().map(|qb| Self::qubit(qb)).collect::<Vec<_>>()
+    );
+  }
+
+  /// Controlled rotations aren't part of the base profile, so they're decomposed into single-qubit
+  /// rotations and CNOTs. Half-turns use the dedicated gates.
+  fn controlled(
+    &mut self, axis: &str, controls: &Vec<Qubit>, target: &Qubit, radians: f64
+  ) -> Result<(), String> {
+    let control = match controls.as_slice() {
+      [] => {
+        self.rotation(&format!("r{axis}"), target, radians);
+        return Ok(());
+      }
+      [control] => control,
+      [first, second] if axis == "x" && is_half_turn(radians) => {
+        self.gate("ccx", &[first, second, target]);
+        return Ok(());
+      }
+      _ => {
+        return Err(format!(
+          "Base Profile QIR can't express r{axis} with {} controls.",
+          controls.len()
+        ))
+      }
+    };
+
+    match axis {
+      "x" if is_half_turn(radians) => self.gate("cnot", &[control, target]),
+      "z" if is_half_turn(radians) => self.gate("cz", &[control, target]),
+      "x" => {
+        self.gate("h", &[target]);
+        self.controlled("z", controls, target, radians)?;
+        self.gate("h", &[target]);
+      }
+      _ => {
+        let rotation = format!("r{axis}");
+        self.rotation(&rotation, target, radians / 2.0);
+        self.gate("cnot", &[control, target]);
+        self.rotation(&rotation, target, -radians / 2.0);
+        self.gate("cnot", &[control, target]);
+      }
+    }
+    Ok(())
+  }
+}
+
+fn is_half_turn(radians: f64) -> bool { (radians - PI).abs() < f64::EPSILON }
+
+/// Renders these operations as a Base Profile QIR module with a single entry-point, recording
+/// every measure as an output in the order they happen.
+pub fn to_base_profile(
+  operations: &Vec<CircuitOperation>, name: &str
+) -> Result<BaseProfileModule, String> {
+  let mut emitter = BaseProfileEmitter::new();
+  emitter.call("__quantum__rt__initialize", "i8*", &[String::from(
+    "i8* null"
+  )]);
+
+  let mut qubit_count = 0;
+  let mut result_count = 0;
+  for operation in operations {
+    match operation {
+      CircuitOperation::X(qb, radians) => emitter.rotation("rx", qb, *radians),
+      CircuitOperation::Y(qb, radians) => emitter.rotation("ry", qb, *radians),
+      CircuitOperation::Z(qb, radians) => emitter.rotation("rz", qb, *radians),
+      CircuitOperation::CX(controls, target, radians) => {
+        emitter.controlled("x", controls, target, *radians)?;
+      }
+      CircuitOperation::CY(controls, target, radians) => {
+        emitter.controlled("y", controls, target, *radians)?;
+      }
+      CircuitOperation::CZ(controls, target, radians) => {
+        emitter.controlled("z", controls, target, *radians)?;
+      }
+      CircuitOperation::Reset(qb) => emitter.gate("reset", &[qb]),
+      CircuitOperation::Measure(qb) => {
+        emitter.call("__quantum__qis__mz__body", "%Qubit*, %Result*", &[
+          BaseProfileEmitter::qubit(qb),
+          BaseProfileEmitter::result(result_count)
+        ]);
+        result_count += 1;
+      }
+    }
+
+    let qubits = match operation {
+      CircuitOperation::CX(controls, target, _)
+      | CircuitOperation::CY(controls, target, _)
+      | CircuitOperation::CZ(controls, target, _) => controls.iter().chain([target]).collect(),
+      CircuitOperation::X(qb, _)
+      | CircuitOperation::Y(qb, _)
+      | CircuitOperation::Z(qb, _)
+      | CircuitOperation::Reset(qb)
+      | CircuitOperation::Measure(qb) => vec![qb]
+    };
+    for qb in qubits {
+      qubit_count = qubit_count.max(qb.index + 1);
+    }
+  }
+
+  emitter.call("__quantum__rt__array_record_output", "i64, i8*", &[
+    format!("i64 {result_count}"),
+    String::from("i8* null")
+  ]);
+  for index in 0..result_count {
+    emitter.call("__quantum__rt__result_record_output", "%Result*, i8*", &[
+      BaseProfileEmitter::result(index),
+      String::from("i8* null")
+    ]);
+  }
+
+  let mut ir = String::new();
+  ir.push_str(&format!(
+    "; ModuleID = '{name}'\nsource_filename = \"{name}\"\n\n"
+  ));
+  ir.push_str("%Qubit = type opaque\n%Result = type opaque\n\n");
+  ir.push_str(&format!(
+    "define void @{name}() #0 {{\nentry:\n{}  ret void\n}}\n\n",
+    emitter.body
+  ));
+  for (function, parameters) in &emitter.declarations {
+    ir.push_str(&format!("declare void @{function}({parameters})\n\n"));
+  }
+  ir.push_str(&format!(
+    "attributes #0 = {{ \"entry_point\" \"output_labeling_schema\" \
+     \"qir_profiles\"=\"base_profile\" \"required_num_qubits\"=\"{qubit_count}\" \
+     \"required_num_results\"=\"{result_count}\" }}\n\n"
+  ));
+  ir.push_str(
+    "!llvm.module.flags = !{!0, !1, !2, !3}\n\n\
+     !0 = !{i32 1, !\"qir_major_version\", i32 1}\n\
+     !1 = !{i32 7, !\"qir_minor_version\", i32 0}\n\
+     !2 = !{i32 1, !\"dynamic_qubit_management\", i1 false}\n\
+     !3 = !{i32 1, !\"dynamic_result_allocation\", i1 false}\n"
+  );
+
+  Ok(BaseProfileModule {
+    name: name.to_string(),
+    ir,
+    required_qubits: qubit_count,
+    required_results: result_count
+  })
+}
+
+type BaseProfileExecutor =
+  dyn Fn(&BaseProfileModule, &ExecutionOptions) -> AnalysisResult + Send + Sync;
+
+/// Backend for machines which only accept Base Profile QIR. Every circuit is rendered as a
+/// standalone module and handed to the executor, so full QIR programs can be run hybridly with
+/// Rasqal evaluating everything around the circuits.
+///
+/// Executors should return results keyed with the first recorded result rightmost.
+pub struct BaseProfileRuntime {
+  identity: String,
+  features: QuantumFeatures,
+  executor: Box<BaseProfileExecutor>
+}
+
+impl BaseProfileRuntime {
+  pub fn new<F>(identity: &str, executor: F) -> BaseProfileRuntime
+  where
+    F: Fn(&BaseProfileModule, &ExecutionOptions) -> AnalysisResult + Send + Sync + 'static
+  {
+    BaseProfileRuntime {
+      identity: identity.to_string(),
+      features: QuantumFeatures::default(),
+      executor: Box::new(executor)
+    }
+  }
+
+  pub fn with_features(mut self, features: QuantumFeatures) -> BaseProfileRuntime {
+    self.features = features;
+    self
+  }
+}
+
+impl QuantumBackend for BaseProfileRuntime {
+  fn identity(&self) -> String { self.identity.clone() }
+
+  /// Only Toffolis can be written with more than one control, and features can't tell them apart
+  /// from any other rotation, so every multi-controlled circuit is turned away.
+  fn features(&self) -> QuantumFeatures {
+    QuantumFeatures::new(self.features.qubits).with_controls(1)
+  }
+
+  fn create_builder(&self) -> Box<dyn CircuitBuilder> { Box::new(CircuitRecorder::new()) }
+
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+    let circuit = builder
+      .downcast::<CircuitRecorder>()
+      .expect("Runtime/Builder execution type mismatch.");
+    let module =
+      to_base_profile(&circuit.operations(), "main").unwrap_or_else(|err| panic!("{err}"));
+    (self.executor)(&module, options)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::projections::AnalysisResult;
+  use crate::base_profile::{to_base_profile, BaseProfileModule, BaseProfileRuntime};
+  use crate::builders::{CircuitBuilder, CircuitRecorder, QuantumBackend};
+  use crate::config::RasqalConfig;
+  use crate::execution::{parse_bitcode, parse_text, run_graph, RuntimeCollection};
+  use crate::features::QuantumFeatures;
+  use crate::hardware::Qubit;
+  use crate::instructions::Value;
+  use crate::simulators::SimulatorRuntime;
+  use crate::smart_pointers::Ptr;
+  use std::collections::HashMap;
+  use std::f64::consts::PI;
+  use std::sync::{Arc, Mutex};
+
+  fn bell() -> CircuitRecorder {
+    let circuit = CircuitRecorder::new();
+    let (first, second) = (Qubit::new(0), Qubit::new(1));
+    circuit.x(&first, PI);
+    circuit.cx(&vec![first.clone()], &second, PI);
+    circuit.measure(&first);
+    circuit.measure(&second);
+    circuit
+  }
+
+  #[test]
+  fn emit_base_profile() {
+    let module = to_base_profile(&bell().operations(), "bell").unwrap();
+    assert_eq!(module.required_qubits, 2);
+    assert_eq!(module.required_results, 2);
+    assert!(module
+      .ir
+      .contains("call void @__quantum__qis__cnot__body(%Qubit* inttoptr (i64 0 to %Qubit*)"));
+    assert!(module.ir.contains("\"qir_profiles\"=\"base_profile\""));
+
+    // Rasqal should be able to run what it emits, with the same results.
+    let graph = parse_text(&module.ir, None).expect("Emitted module should parse.");
+    assert_eq!(graph.signature.name, "bell");
+    assert_eq!(graph.signature.required_qubits(), Some(2));
+    assert!(parse_bitcode(&module.bitcode().unwrap(), None).is_ok());
+
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+    let results = run_graph(
+      &graph,
+      &Vec::new(),
+      &Ptr::from(runtimes),
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.")
+    .expect("Should have a result.");
+    let Value::AnalysisResult(results) = &*results else {
+      panic!("Base profile should return its results.");
+    };
+    assert_eq!(results.distribution.keys().collect::<Vec<_>>(), vec!["11"]);
+  }
+
+  #[test]
+  fn decomposes_controlled_rotations() {
+    let circuit = CircuitRecorder::new();
+    circuit.cy(&vec![Qubit::new(0)], &Qubit::new(1), PI / 2.);
+    circuit.cx(&vec![Qubit::new(0)], &Qubit::new(2), PI / 2.);
+    let module = to_base_profile(&circuit.operations(), "main").unwrap();
+    assert!(!module.ir.contains("__quantum__qis__cry"));
+    assert!(module
+      .ir
+      .contains("declare void @__quantum__qis__h__body(%Qubit*)"));
+    assert!(module.bitcode().is_ok());
+
+    circuit.cz(&vec![Qubit::new(0), Qubit::new(1)], &Qubit::new(2), PI);
+    assert!(to_base_profile(&circuit.operations(), "main").is_err());
+
+    // The runtime turns multi-controlled circuits away rather than failing on them.
+    let runtime = BaseProfileRuntime::new("tests.base_profile", |_, _| AnalysisResult::empty());
+    assert!(!runtime.has_features(&QuantumFeatures::new(3).with_controls(2)));
+    assert!(runtime.has_features(&QuantumFeatures::new(3).with_controls(1)));
+  }
+
+  #[test]
+  fn base_profile_runtime() {
+    let sent = Arc::new(Mutex::new(Vec::<BaseProfileModule>::new()));
+    let recorder = sent.clone();
+    let runtime = BaseProfileRuntime::new("tests.base_profile", move |module, _| {
+      recorder.lock().unwrap().push(module.clone());
+      AnalysisResult::new(HashMap::from([(String::from("11"), 10)]))
+    });
+
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(runtime);
+    let graph = parse_text(
+      &to_base_profile(&bell().operations(), "bell").unwrap().ir,
+      None
+    )
+    .expect("Emitted module should parse.");
+    run_graph(
+      &graph,
+      &Vec::new(),
+      &Ptr::from(runtimes),
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+
+    let sent = sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].name, "main");
+    assert_eq!(sent[0].required_results, 2);
+  }
+}
@@ -2,258 +2,398 @@
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
 use crate::analysis::projections::AnalysisResult;
+use crate::config::ExecutionOptions;
 use crate::features::QuantumFeatures;
 use crate::hardware::Qubit;
-use crate::python::RequiredFeatures;
+#[cfg(feature = "python")]
+use crate::python::{PyExecutionOptions, RequiredFeatures};
 use crate::smart_pointers::Ptr;
+#[cfg(feature = "python")]
 use pyo3::{IntoPy, PyAny, PyObject, Python};
-use std::borrow::Borrow;
+use std::any::Any;
 use std::f64::consts::PI;
-use std::ops::{Deref, DerefMut};
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
 
-// TODO: Use macros to reduce duplication across both integration objects.
-
-pub enum IntegrationRuntime {
-  Empty,
-  Python(PythonRuntime)
+/// Lets backends get back to their concrete types from a trait object.
+pub trait AsAny {
+  fn as_any(&self) -> &dyn Any;
 }
 
-impl Default for IntegrationRuntime {
-  fn default() -> Self { IntegrationRuntime::Empty }
+impl<T: Any> AsAny for T {
+  fn as_any(&self) -> &dyn Any { self }
 }
 
-impl IntegrationRuntime {
-  pub fn is_valid(&mut self) -> bool {
-    match self {
-      IntegrationRuntime::Python(py) => py.is_valid(),
-      _ => true
-    }
-  }
+/// Something Rasqal can send circuits to, be it a QPU, simulator or anything else. Register them
+/// with [`RuntimeCollection::register`](crate::execution::RuntimeCollection::register).
+pub trait QuantumBackend: AsAny + Send + Sync {
+  /// Name which identifies what sort of backend this is, stable across processes.
+  fn identity(&self) -> String;
 
-  pub fn execute(&self, builder: &Ptr<IntegrationBuilder>) -> AnalysisResult {
-    if let IntegrationRuntime::Python(py) = self {
-      if let IntegrationBuilder::Python(builder) = builder.deref() {
-        return py.execute(builder);
-      }
+  /// Returns whether this backend can be actively used.
+  fn is_valid(&self) -> bool { true }
 
-      panic!("Runtime/Builder execution type mismatch.")
-    }
+  /// Features this backend supports. Defaults to no restrictions.
+  fn features(&self) -> QuantumFeatures { QuantumFeatures::default() }
 
-    AnalysisResult::empty()
+  /// Returns whether this backend can run circuits which need these features.
+  fn has_features(&self, required: &QuantumFeatures) -> bool {
+    let available = self.features();
+    (available.qubits < 0 || required.qubits <= available.qubits)
+      && (available.controls < 0 || required.controls <= available.controls)
   }
 
-  pub fn create_builder(&self) -> Ptr<IntegrationBuilder> {
-    match self {
-      IntegrationRuntime::Python(py) => py.create_builder(),
-      _ => Ptr::from(IntegrationBuilder::Empty)
-    }
+  fn create_builder(&self) -> Box<dyn CircuitBuilder>;
+
+  /// Executes a circuit built by this backends builder, blocking until it has results.
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult;
+
+  /// Starts executing a circuit without waiting for its results. Backends with their own job
+  /// queues should override this, by default it just runs [`QuantumBackend::execute`].
+  fn submit(
+    &self, builder: &dyn CircuitBuilder, options: &ExecutionOptions
+  ) -> Box<dyn PendingExecution> {
+    Box::new(CompletedExecution::new(self.execute(builder, options)))
   }
+}
 
-  pub fn has_features(&self, features: &QuantumFeatures) -> bool {
-    match self {
-      IntegrationRuntime::Python(py) => py.has_features(features),
-      _ => true
-    }
+impl<'a> dyn QuantumBackend + 'a {
+  /// Returns the backend as its concrete type, if it is one.
+  pub fn downcast<T: QuantumBackend + 'static>(&self) -> Option<&T> {
+    self.as_any().downcast_ref::<T>()
   }
 }
 
-pub enum IntegrationBuilder {
-  Empty,
-  Python(PythonBuilder)
+/// Circuit that's been submitted to a backend and may still be running.
+pub trait PendingExecution: Send {
+  /// Returns whether results are available, so waiting won't block.
+  fn is_ready(&self) -> bool;
+
+  /// Blocks until the results are available.
+  fn wait(self: Box<Self>) -> AnalysisResult;
 }
 
-impl Default for IntegrationBuilder {
-  fn default() -> Self { IntegrationBuilder::Empty }
+/// Execution which finished as soon as it was submitted.
+pub struct CompletedExecution {
+  result: AnalysisResult
 }
 
-impl IntegrationBuilder {
-  /// Returns whether this runtime can be actively used.
-  pub fn is_valid(&mut self) -> bool {
-    match self {
-      IntegrationBuilder::Python(py) => py.is_valid(),
-      _ => true
-    }
+impl CompletedExecution {
+  pub fn new(result: AnalysisResult) -> CompletedExecution { CompletedExecution { result } }
+}
+
+impl PendingExecution for CompletedExecution {
+  fn is_ready(&self) -> bool { true }
+
+  fn wait(self: Box<Self>) -> AnalysisResult { self.result }
+}
+
+/// Builds up a circuit for a particular backend. Only rotations, controlled rotations, resets and
+/// measures need implementing, every other gate is decomposed into them by default.
+pub trait CircuitBuilder: AsAny + Send + Sync {
+  fn x(&self, qb: &Qubit, radians: f64);
+
+  fn y(&self, qb: &Qubit, radians: f64);
+
+  fn z(&self, qb: &Qubit, radians: f64);
+
+  fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64);
+
+  fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64);
+
+  fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64);
+
+  fn reset(&self, qb: &Qubit);
+
+  fn measure(&self, qb: &Qubit);
+
+  /// Returns whether this builder can be actively used.
+  fn is_valid(&self) -> bool { true }
+
+  /// How many shots this builder will be executed with, if it exposes it.
+  fn shot_count(&self) -> Option<i64> { None }
+
+  fn had(&self, qb: &Qubit) {
+    self.z(qb, PI);
+    self.y(qb, PI / 2.0);
   }
 
-  pub fn measure(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.measure(qb);
-    }
-    self
+  fn i(&self, qb: &Qubit) {}
+
+  fn u(&self, qb: &Qubit, theta: f64, phi: f64, lambda: f64) {
+    self.z(qb, lambda);
+    self.y(qb, phi);
+    self.z(qb, theta);
   }
 
-  pub fn had(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.had(qb);
-    }
-    self
+  fn swap(&self, first: &Qubit, second: &Qubit) {
+    self.cnot(first, second, PI);
+    self.cnot(second, first, PI);
+    self.cnot(first, second, PI);
   }
 
-  pub fn i(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.i(qb);
-    }
-    self
+  fn sx(&self, qb: &Qubit) { self.x(qb, PI / 2.0) }
+
+  fn sx_dgr(&self, qb: &Qubit) { self.x(qb, -(PI / 2.0)) }
+
+  fn s(&self, qb: &Qubit) { self.z(qb, PI / 2.0) }
+
+  fn s_dgr(&self, qb: &Qubit) { self.z(qb, -(PI / 2.0)) }
+
+  fn t(&self, qb: &Qubit) { self.z(qb, PI / 4.0) }
+
+  fn t_dgr(&self, qb: &Qubit) { self.z(qb, -(PI / 4.0)) }
+
+  fn cnot(&self, control: &Qubit, target: &Qubit, radians: f64) {
+    self.cx(&vec![control.clone()], target, radians)
   }
 
-  pub fn x(&self, qb: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.x(qb, radii);
-    }
-    self
+  fn ccnot(&self, c1: &Qubit, c2: &Qubit, target: &Qubit, radians: f64) {
+    self.cx(&vec![c1.clone(), c2.clone()], target, radians)
   }
+}
 
-  pub fn y(&self, qb: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.y(qb, radii);
-    }
-    self
+impl<'a> dyn CircuitBuilder + 'a {
+  /// Returns the builder as its concrete type, if it is one.
+  pub fn downcast<T: CircuitBuilder + 'static>(&self) -> Option<&T> {
+    self.as_any().downcast_ref::<T>()
   }
+}
 
-  pub fn z(&self, qb: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.z(qb, radii);
-    }
-    self
+/// Backend registered with a runtime collection, caching its validity as checking can be expensive.
+pub struct IntegrationRuntime {
+  backend: Box<dyn QuantumBackend>,
+  is_valid: Option<bool>
+}
+
+impl Default for IntegrationRuntime {
+  fn default() -> Self { IntegrationRuntime::new(EmptyBackend {}) }
+}
+
+impl IntegrationRuntime {
+  pub fn new(backend: impl QuantumBackend + 'static) -> IntegrationRuntime {
+    IntegrationRuntime::from_box(Box::new(backend))
   }
 
-  pub fn u(&self, qb: &Qubit, theta: f64, phi: f64, lambda: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.u(qb, theta, phi, lambda);
+  pub fn from_box(backend: Box<dyn QuantumBackend>) -> IntegrationRuntime {
+    IntegrationRuntime {
+      backend,
+      is_valid: None
     }
-    self
   }
 
-  pub fn swap(&self, first: &Qubit, second: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.swap(first, second);
+  pub fn backend(&self) -> &dyn QuantumBackend { self.backend.as_ref() }
+
+  /// Returns whether the backend is a `T`.
+  pub fn is<T: QuantumBackend + 'static>(&self) -> bool { self.backend().downcast::<T>().is_some() }
+
+  /// Returns whether this runtime can be actively used.
+  pub fn is_valid(&mut self) -> bool {
+    if self.is_valid.is_none() {
+      self.is_valid = Some(self.backend.is_valid());
     }
-    self
+
+    self.is_valid.unwrap()
   }
 
-  pub fn sx(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.sx(qb);
-    }
-    self
+  pub fn execute(
+    &self, builder: &Ptr<IntegrationBuilder>, options: &ExecutionOptions
+  ) -> AnalysisResult {
+    self.backend.execute(builder.circuit(), options)
   }
 
-  pub fn sx_dgr(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.sx_dgr(qb);
-    }
-    self
+  pub fn submit(
+    &self, builder: &Ptr<IntegrationBuilder>, options: &ExecutionOptions
+  ) -> Box<dyn PendingExecution> {
+    self.backend.submit(builder.circuit(), options)
   }
 
-  pub fn s(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.s(qb);
-    }
-    self
+  pub fn create_builder(&self) -> Ptr<IntegrationBuilder> {
+    Ptr::from(IntegrationBuilder::from_box(self.backend.create_builder()))
   }
 
-  pub fn s_dgr(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.s_dgr(qb);
-    }
-    self
+  pub fn has_features(&self, features: &QuantumFeatures) -> bool {
+    self.backend.has_features(features)
   }
 
-  pub fn t(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.t(qb);
-    }
-    self
+  /// Name which identifies what sort of backend this is, stable across processes.
+  pub fn identity(&self) -> String { self.backend.identity() }
+}
+
+/// Builder created by one of our runtimes, dereferencing to the backends [`CircuitBuilder`].
+pub struct IntegrationBuilder {
+  builder: Box<dyn CircuitBuilder>
+}
+
+impl IntegrationBuilder {
+  pub fn new(builder: impl CircuitBuilder + 'static) -> IntegrationBuilder {
+    IntegrationBuilder::from_box(Box::new(builder))
   }
 
-  pub fn t_dgr(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.t_dgr(qb);
-    }
-    self
+  pub fn from_box(builder: Box<dyn CircuitBuilder>) -> IntegrationBuilder {
+    IntegrationBuilder { builder }
   }
 
-  pub fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.cx(controls, target, radii);
-    }
-    self
+  pub fn circuit(&self) -> &dyn CircuitBuilder { self.builder.as_ref() }
+}
+
+impl Deref for IntegrationBuilder {
+  type Target = dyn CircuitBuilder;
+
+  fn deref(&self) -> &Self::Target { self.builder.as_ref() }
+}
+
+/// Backend which accepts anything and returns empty results.
+pub struct EmptyBackend {}
+
+impl QuantumBackend for EmptyBackend {
+  fn identity(&self) -> String { String::from("empty") }
+
+  fn create_builder(&self) -> Box<dyn CircuitBuilder> { Box::new(CircuitRecorder::new()) }
+
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+    AnalysisResult::empty()
   }
+}
 
-  pub fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.cy(controls, target, radii);
+/// Gate-level operations recorded for native runtimes.
+#[derive(Clone, Debug)]
+pub enum CircuitOperation {
+  X(Qubit, f64),
+  Y(Qubit, f64),
+  Z(Qubit, f64),
+  CX(Vec<Qubit>, Qubit, f64),
+  CY(Vec<Qubit>, Qubit, f64),
+  CZ(Vec<Qubit>, Qubit, f64),
+  Reset(Qubit),
+  Measure(Qubit)
+}
+
+impl Display for CircuitOperation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let controls = |qbs: &Vec<Qubit>| {
+      qbs
+        .iter()
+        .map(|qb| qb.index.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+    };
+    match self {
+      CircuitOperation::X(qb, radians) => write!(f, "x {} {radians}", qb.index),
+      CircuitOperation::Y(qb, radians) => write!(f, "y {} {radians}", qb.index),
+      CircuitOperation::Z(qb, radians) => write!(f, "z {} {radians}", qb.index),
+      CircuitOperation::CX(controls_, target, radians) => {
+        write!(f, "cx [{}] {} {radians}", controls(controls_), target.index)
+      }
+      CircuitOperation::CY(controls_, target, radians) => {
+        write!(f, "cy [{}] {} {radians}", controls(controls_), target.index)
+      }
+      CircuitOperation::CZ(controls_, target, radians) => {
+        write!(f, "cz [{}] {} {radians}", controls(controls_), target.index)
+      }
+      CircuitOperation::Reset(qb) => write!(f, "reset {}", qb.index),
+      CircuitOperation::Measure(qb) => write!(f, "measure {}", qb.index)
     }
-    self
   }
+}
 
-  pub fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.cz(controls, target, radii);
+/// Builder for native runtimes which just records the operations applied to it.
+pub struct CircuitRecorder {
+  operations: Mutex<Vec<CircuitOperation>>
+}
+
+impl Default for CircuitRecorder {
+  fn default() -> Self { CircuitRecorder::new() }
+}
+
+impl CircuitRecorder {
+  pub fn new() -> CircuitRecorder {
+    CircuitRecorder {
+      operations: Mutex::new(Vec::new())
     }
-    self
   }
 
-  pub fn cnot(&self, control: &Qubit, target: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.cnot(control, target, radii);
-    }
-    self
+  /// Operations recorded so far, locked against further recording until the guard is dropped.
+  pub fn operations(&self) -> MutexGuard<'_, Vec<CircuitOperation>> {
+    self.operations.lock().unwrap()
   }
 
-  pub fn ccnot(&self, c1: &Qubit, c2: &Qubit, target: &Qubit, radii: f64) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.ccnot(c1, c2, target, radii);
-    }
-    self
+  fn record(&self, operation: CircuitOperation) { self.operations.lock().unwrap().push(operation); }
+}
+
+impl CircuitBuilder for CircuitRecorder {
+  fn x(&self, qb: &Qubit, radians: f64) { self.record(CircuitOperation::X(qb.clone(), radians)) }
+
+  fn y(&self, qb: &Qubit, radians: f64) { self.record(CircuitOperation::Y(qb.clone(), radians)) }
+
+  fn z(&self, qb: &Qubit, radians: f64) { self.record(CircuitOperation::Z(qb.clone(), radians)) }
+
+  fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
+    self.record(CircuitOperation::CX(
+      controls.clone(),
+      target.clone(),
+      radians
+    ))
   }
 
-  pub fn reset(&self, qb: &Qubit) -> &Self {
-    if let IntegrationBuilder::Python(py) = self {
-      py.reset(qb);
-    }
-    self
+  fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
+    self.record(CircuitOperation::CY(
+      controls.clone(),
+      target.clone(),
+      radians
+    ))
+  }
+
+  fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
+    self.record(CircuitOperation::CZ(
+      controls.clone(),
+      target.clone(),
+      radians
+    ))
   }
+
+  fn reset(&self, qb: &Qubit) { self.record(CircuitOperation::Reset(qb.clone())) }
+
+  fn measure(&self, qb: &Qubit) { self.record(CircuitOperation::Measure(qb.clone())) }
 }
 
+#[cfg(feature = "python")]
 macro_rules! python_methods {
   (self.$wrapped_obj:ident.$python_gate:ident()) => {
-      pub fn $python_gate(&self) -> Result<&PyAny, String> {
+      pub fn $python_gate(&self) -> Result<PyObject, String> {
         Python::with_gil(|py| {
-          let target = self.$wrapped_obj.getattr(stringify!($python_gate))
+          let target = self.$wrapped_obj.as_ref(py).getattr(stringify!($python_gate))
             .map_err(|err| err.value(py).to_string())
             .expect(format!("'{}' can't be found on {}", stringify!($python_gate), stringify!($wrapped_obj)).as_str());
-          target.call0().map_err(|err| err.value(py).to_string())
+          target.call0().map(PyObject::from).map_err(|err| err.value(py).to_string())
         })
       }
   };
   (self.$wrapped_obj:ident.$python_gate:ident($($var:ident: $ty:ty),*)) => {
-    pub fn $python_gate(&self, $($var: $ty),*) -> Result<&PyAny, String> {
+    pub fn $python_gate(&self, $($var: $ty),*) -> Result<PyObject, String> {
       Python::with_gil(|py| {
-        let target = self.$wrapped_obj.getattr(stringify!($python_gate))
+        let target = self.$wrapped_obj.as_ref(py).getattr(stringify!($python_gate))
           .map_err(|err| err.value(py).to_string())
           .expect(format!("'{}' can't be found {}", stringify!($python_gate), stringify!($wrapped_obj)).as_str());
-        target.call1(($($var),*,)).map_err(|err| err.value(py).to_string())
+        target.call1(($($var),*,)).map(PyObject::from).map_err(|err| err.value(py).to_string())
       })
     }
   }
 }
 
-/// Rust wrapper for our Python builders.
+/// Rust wrapper for our Python builders. Holds an owned reference so it can be sent to whichever
+/// thread the circuit is dispatched from.
+#[cfg(feature = "python")]
 struct PyBuilderAdaptor {
-  builder: Ptr<PyAny>
+  builder: PyObject
 }
 
+#[cfg(feature = "python")]
 impl PyBuilderAdaptor {
-  fn new(builder: &PyAny) -> PyBuilderAdaptor {
-    PyBuilderAdaptor {
-      builder: Ptr::from(builder)
-    }
-  }
+  fn new(builder: PyObject) -> PyBuilderAdaptor { PyBuilderAdaptor { builder } }
 
-  pub fn is_adaptor_empty(&self) -> bool {
-    return Ptr::is_null(self.builder.borrow()) || self.builder.is_none();
-  }
+  pub fn is_adaptor_empty(&self) -> bool { Python::with_gil(|py| self.builder.is_none(py)) }
 
   python_methods!(self.builder.x(qubit: i64, radians: f64));
   python_methods!(self.builder.y(qubit: i64, radians: f64));
@@ -265,254 +405,218 @@ impl PyBuilderAdaptor {
   python_methods!(self.builder.measure(qubit: i64));
 }
 
-impl Deref for PyBuilderAdaptor {
-  type Target = PyAny;
-  fn deref(&self) -> &Self::Target { self.builder.deref() }
-}
-
-impl DerefMut for PyBuilderAdaptor {
-  fn deref_mut(&mut self) -> &mut Self::Target { self.builder.deref_mut() }
-}
-
+#[cfg(feature = "python")]
 impl Default for PyBuilderAdaptor {
-  fn default() -> Self {
-    PyBuilderAdaptor {
-      builder: Ptr::default()
-    }
-  }
+  fn default() -> Self { PyBuilderAdaptor::new(Python::with_gil(|py| py.None())) }
 }
 
-/// Rust wrapper for our Python runtime.
+/// Rust wrapper for our Python runtime. Holds an owned reference so it can be sent to whichever
+/// thread the circuit is dispatched from.
+#[cfg(feature = "python")]
 struct PyRuntimeAdaptor {
-  runtime: Ptr<PyAny>
+  runtime: PyObject
 }
 
+#[cfg(feature = "python")]
 impl PyRuntimeAdaptor {
-  fn new(runtime: &PyAny) -> PyRuntimeAdaptor {
-    PyRuntimeAdaptor {
-      runtime: Ptr::from(runtime)
-    }
-  }
+  fn new(runtime: PyObject) -> PyRuntimeAdaptor { PyRuntimeAdaptor { runtime } }
 
-  pub fn is_adaptor_empty(&self) -> bool {
-    return Ptr::is_null(self.runtime.borrow()) || self.runtime.is_none();
-  }
+  pub fn is_adaptor_empty(&self) -> bool { Python::with_gil(|py| self.runtime.is_none(py)) }
 
-  python_methods!(self.runtime.execute(builder: &PyAny));
   python_methods!(self.runtime.create_builder());
   python_methods!(self.runtime.has_features(features: PyObject));
-}
-
-impl Deref for PyRuntimeAdaptor {
-  type Target = PyAny;
-
-  fn deref(&self) -> &Self::Target { self.runtime.deref() }
-}
 
-impl DerefMut for PyRuntimeAdaptor {
-  fn deref_mut(&mut self) -> &mut Self::Target { self.runtime.deref_mut() }
+  /// Calls 'execute' with the builder and its options, or just the builder for adaptors written
+  /// before options were passed along.
+  pub fn execute(&self, builder: &PyObject, options: PyObject) -> Result<PyObject, String> {
+    Python::with_gil(|py| {
+      let builder = builder.as_ref(py);
+      let target = self
+        .runtime
+        .as_ref(py)
+        .getattr("execute")
+        .map_err(|err| err.value(py).to_string())?;
+      let takes_options = match py
+        .import("inspect")
+        .and_then(|inspect| inspect.call_method1("signature", (target,)))
+      {
+        Ok(signature) => signature
+          .call_method1("bind", (builder, options.clone_ref(py)))
+          .is_ok(),
+        Err(_) => true
+      };
+
+      if takes_options {
+        target.call1((builder, options))
+      } else {
+        target.call1((builder,))
+      }
+      .map(PyObject::from)
+      .map_err(|err| err.value(py).to_string())
+    })
+  }
 }
 
+#[cfg(feature = "python")]
 impl Default for PyRuntimeAdaptor {
-  fn default() -> Self {
-    PyRuntimeAdaptor {
-      runtime: Ptr::default()
-    }
-  }
+  fn default() -> Self { PyRuntimeAdaptor::new(Python::with_gil(|py| py.None())) }
 }
 
+/// Backend implemented in Python, called through its adaptor.
+#[cfg(feature = "python")]
 pub struct PythonRuntime {
-  wrapped: PyRuntimeAdaptor,
-  is_valid: Option<bool>
+  wrapped: PyRuntimeAdaptor
 }
 
+#[cfg(feature = "python")]
 impl PythonRuntime {
   pub fn new(backend: &PyAny) -> PythonRuntime {
     PythonRuntime {
-      wrapped: PyRuntimeAdaptor::new(backend),
-      is_valid: None
+      wrapped: PyRuntimeAdaptor::new(backend.into())
     }
   }
+}
 
-  /// Returns whether this runtime can be actively used.
-  pub fn is_valid(&mut self) -> bool {
-    if self.is_valid.is_none() {
-      let mut builder = self.create_builder();
-      self.is_valid = Some(builder.is_valid() && !self.wrapped.is_adaptor_empty());
-    }
+#[cfg(feature = "python")]
+impl QuantumBackend for PythonRuntime {
+  /// Whatever the adaptors optional 'identity' method returns, otherwise the fully-qualified name
+  /// of the Python class backing this runtime.
+  fn identity(&self) -> String {
+    Python::with_gil(|py| {
+      let runtime = self.wrapped.runtime.as_ref(py);
+      let supplied = runtime
+        .getattr("identity")
+        .and_then(|method| method.call0())
+        .and_then(|val| val.extract::<String>());
+      if let Ok(identity) = supplied {
+        return identity;
+      }
 
-    self.is_valid.unwrap()
+      let class = runtime.get_type();
+      let module = class
+        .getattr("__module__")
+        .and_then(|val| val.extract::<String>())
+        .unwrap_or_default();
+      format!("{}.{}", module, class.name().unwrap_or("unknown"))
+    })
   }
 
-  pub fn execute(&self, builder: &PythonBuilder) -> AnalysisResult {
-    let result = self
-      .wrapped
-      .execute(builder.wrapped.deref())
-      .expect("QPU didn't return a result");
+  fn is_valid(&self) -> bool {
+    !self.wrapped.is_adaptor_empty() && self.create_builder().is_valid()
+  }
 
-    AnalysisResult::new(
-      result
-        .extract()
-        .expect("Object returned from 'execute' isn't a distribution dictionary.")
-    )
+  fn has_features(&self, features: &QuantumFeatures) -> bool {
+    let pyfeature = Python::with_gil(|py| -> PyObject {
+      let rbp = RequiredFeatures::new(features);
+      rbp.into_py(py)
+    });
+
+    self.wrapped.has_features(pyfeature).map_or(false, |obj| {
+      Python::with_gil(|py| obj.extract(py).expect("Unable to extract type."))
+    })
   }
 
-  pub fn create_builder(&self) -> Ptr<IntegrationBuilder> {
-    let pybuilder = PythonBuilder::new(
+  fn create_builder(&self) -> Box<dyn CircuitBuilder> {
+    Box::new(PythonBuilder::new(
       self
         .wrapped
         .create_builder()
         .expect("Couldn't create a builder from runtime")
-    );
-    Ptr::from(IntegrationBuilder::Python(pybuilder))
+    ))
   }
 
-  pub fn has_features(&self, features: &QuantumFeatures) -> bool {
-    let pyfeature = Python::with_gil(|py| -> PyObject {
-      let rbp = RequiredFeatures::new(features);
-      rbp.into_py(py)
-    });
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+    let builder = builder
+      .downcast::<PythonBuilder>()
+      .expect("Runtime/Builder execution type mismatch.");
+    let pyoptions =
+      Python::with_gil(|py| -> PyObject { PyExecutionOptions::new(options).into_py(py) });
 
-    self
+    let result = self
       .wrapped
-      .has_features(pyfeature)
-      .map_or(false, |obj| obj.extract().expect("Unable to extract type."))
+      .execute(&builder.wrapped.builder, pyoptions)
+      .expect("QPU didn't return a result");
+
+    AnalysisResult::new(Python::with_gil(|py| {
+      result
+        .extract(py)
+        .expect("Object returned from 'execute' isn't a distribution dictionary.")
+    }))
   }
 }
 
+#[cfg(feature = "python")]
 impl Default for PythonRuntime {
   fn default() -> Self {
     PythonRuntime {
-      wrapped: PyRuntimeAdaptor::default(),
-      is_valid: None
+      wrapped: PyRuntimeAdaptor::default()
     }
   }
 }
 
+#[cfg(feature = "python")]
 pub struct PythonBuilder {
-  wrapped: PyBuilderAdaptor,
-  is_valid: Option<bool>
+  wrapped: PyBuilderAdaptor
 }
 
+#[cfg(feature = "python")]
 impl Default for PythonBuilder {
   fn default() -> Self {
     PythonBuilder {
-      wrapped: PyBuilderAdaptor::default(),
-      is_valid: None
+      wrapped: PyBuilderAdaptor::default()
     }
   }
 }
 
+#[cfg(feature = "python")]
 impl PythonBuilder {
-  pub fn new(builder: &PyAny) -> PythonBuilder {
+  pub fn new(builder: PyObject) -> PythonBuilder {
     PythonBuilder {
-      wrapped: PyBuilderAdaptor::new(builder),
-      is_valid: None
+      wrapped: PyBuilderAdaptor::new(builder)
     }
   }
-
-  /// Returns whether this builder can be actively used.
-  pub fn is_valid(&mut self) -> bool {
-    if self.is_valid.is_none() {
-      self.is_valid = Some(!self.wrapped.is_adaptor_empty());
-    }
-
-    self.is_valid.unwrap()
-  }
 }
 
-impl InstructionBuilder for PythonBuilder {
-  fn measure(&self, qb: &Qubit) -> &Self {
-    self.wrapped.measure(qb.index);
-    self
-  }
-
-  fn x(&self, qb: &Qubit, radians: f64) -> &Self {
-    self.wrapped.x(qb.index, radians);
-    self
-  }
+#[cfg(feature = "python")]
+impl CircuitBuilder for PythonBuilder {
+  fn x(&self, qb: &Qubit, radians: f64) { self.wrapped.x(qb.index, radians); }
 
-  fn y(&self, qb: &Qubit, radians: f64) -> &Self {
-    self.wrapped.y(qb.index, radians);
-    self
-  }
+  fn y(&self, qb: &Qubit, radians: f64) { self.wrapped.y(qb.index, radians); }
 
-  fn z(&self, qb: &Qubit, radians: f64) -> &Self {
-    self.wrapped.z(qb.index, radians);
-    self
-  }
+  fn z(&self, qb: &Qubit, radians: f64) { self.wrapped.z(qb.index, radians); }
 
-  fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) -> &Self {
-    let controls: Vec<i64> = controls.iter().map(|val| val.index).collect::<Vec<_>>();
+  fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
+    let controls = controls.iter().map(|val| val.index).collect::<Vec<_>>();
     self.wrapped.cx(controls, target.index, radians);
-    self
   }
 
-  fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) -> &Self {
+  fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
     let controls = controls.iter().map(|val| val.index).collect::<Vec<_>>();
     self.wrapped.cy(controls, target.index, radians);
-    self
   }
 
-  fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) -> &Self {
-    let controls: Vec<i64> = controls.iter().map(|val| val.index).collect::<Vec<_>>();
+  fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radians: f64) {
+    let controls = controls.iter().map(|val| val.index).collect::<Vec<_>>();
     self.wrapped.cz(controls, target.index, radians);
-    self
-  }
-
-  fn reset(&self, qb: &Qubit) -> &Self {
-    self.wrapped.reset(qb.index);
-    self
-  }
-}
-
-pub trait InstructionBuilder {
-  fn measure(&self, qb: &Qubit) -> &Self { self }
-
-  fn had(&self, qb: &Qubit) -> &Self {
-    self.z(qb, PI);
-    self.y(qb, PI / 2.0)
-  }
-
-  fn i(&self, qb: &Qubit) -> &Self { self }
-
-  fn x(&self, qb: &Qubit, radii: f64) -> &Self { self }
-
-  fn y(&self, qb: &Qubit, radii: f64) -> &Self { self }
-
-  fn z(&self, qb: &Qubit, radii: f64) -> &Self { self }
-
-  fn u(&self, qb: &Qubit, theta: f64, phi: f64, lambda: f64) -> &Self {
-    self.z(qb, lambda).y(qb, phi).z(qb, theta)
   }
 
-  fn swap(&self, first: &Qubit, second: &Qubit) -> &Self { self }
+  fn reset(&self, qb: &Qubit) { self.wrapped.reset(qb.index); }
 
-  fn sx(&self, qb: &Qubit) -> &Self { self.x(qb, PI / 2.0) }
+  fn measure(&self, qb: &Qubit) { self.wrapped.measure(qb.index); }
 
-  fn sx_dgr(&self, qb: &Qubit) -> &Self { self.x(qb, -(PI / 2.0)) }
-
-  fn s(&self, qb: &Qubit) -> &Self { self.z(qb, PI / 2.0) }
-
-  fn s_dgr(&self, qb: &Qubit) -> &Self { self.z(qb, -(PI / 2.0)) }
-
-  fn t(&self, qb: &Qubit) -> &Self { self.z(qb, PI / 4.0) }
-
-  fn t_dgr(&self, qb: &Qubit) -> &Self { self.z(qb, -(PI / 4.0)) }
-
-  fn cx(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self { self }
-
-  fn cy(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self { self }
-
-  fn cz(&self, controls: &Vec<Qubit>, target: &Qubit, radii: f64) -> &Self { self }
-
-  fn cnot(&self, control: &Qubit, target: &Qubit, radii: f64) -> &Self {
-    self.cx(&vec![control.clone()], target, radii)
-  }
+  /// Returns whether this builder can be actively used.
+  fn is_valid(&self) -> bool { !self.wrapped.is_adaptor_empty() }
 
-  fn ccnot(&self, c1: &Qubit, c2: &Qubit, target: &Qubit, radii: f64) -> &Self {
-    self.cx(&vec![c1.clone(), c2.clone()], target, radii)
+  /// Reads the optional 'shot_count' attribute off the Python builder.
+  fn shot_count(&self) -> Option<i64> {
+    Python::with_gil(|py| {
+      self
+        .wrapped
+        .builder
+        .as_ref(py)
+        .getattr("shot_count")
+        .ok()
+        .and_then(|val| val.extract().ok())
+    })
   }
-
-  fn reset(&self, qb: &Qubit) -> &Self { self }
 }
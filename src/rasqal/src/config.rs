@@ -1,27 +1,255 @@
+use crate::analysis::cache::ProjectionCache;
+use crate::analysis::projections::ResultReduction;
+use crate::analysis::solver::{SolverStrategy, DEFAULT_BOND_DIMENSION};
+use crate::debugger::Debugger;
+use crate::events::{EventBus, TraceEvent};
+use crate::hardware::QubitAllocation;
 use crate::runtime::ActiveTracers;
+use crate::smart_pointers::Ptr;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Options passed to a runtime alongside every circuit it's asked to execute. Anything not set
+/// is left up to the runtime itself.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutionOptions {
+  pub shots: Option<i64>,
+  pub seed: Option<i64>,
+  pub optimisation_level: Option<i64>,
+  pub timeout: Option<Duration>
+}
+
+impl ExecutionOptions {
+  pub fn new() -> ExecutionOptions { ExecutionOptions::default() }
+
+  pub fn with_shots(mut self, shots: i64) -> ExecutionOptions {
+    self.shots = Some(shots);
+    self
+  }
+
+  pub fn with_seed(mut self, seed: i64) -> ExecutionOptions {
+    self.seed = Some(seed);
+    self
+  }
+
+  pub fn with_optimisation_level(mut self, level: i64) -> ExecutionOptions {
+    self.optimisation_level = Some(level);
+    self
+  }
+
+  pub fn with_timeout(mut self, timeout: Duration) -> ExecutionOptions {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Layers these options on top of the others, with any we've set taking precedence.
+  pub fn over(&self, other: &ExecutionOptions) -> ExecutionOptions {
+    ExecutionOptions {
+      shots: self.shots.or(other.shots),
+      seed: self.seed.or(other.seed),
+      optimisation_level: self.optimisation_level.or(other.optimisation_level),
+      timeout: self.timeout.or(other.timeout)
+    }
+  }
+}
+
+impl Display for ExecutionOptions {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let format = |value: Option<String>| value.unwrap_or_else(|| String::from("default"));
+    write!(
+      f,
+      "shots: {}, seed: {}, optimisation: {}, timeout: {}",
+      format(self.shots.map(|val| val.to_string())),
+      format(self.seed.map(|val| val.to_string())),
+      format(self.optimisation_level.map(|val| val.to_string())),
+      format(self.timeout.map(|val| format!("{}ms", val.as_millis())))
+    )
+  }
+}
+
+const ACTIVE: u8 = 0;
+const CANCELLED: u8 = 1;
+const INTERRUPTED: u8 = 2;
+
+/// Shared flag which stops executions from outside of them, such as from another thread. Every
+/// execution using it stops at its next step once cancelled, until it's been reset.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+  state: Arc<AtomicU8>
+}
+
+impl CancellationToken {
+  pub fn new() -> CancellationToken { CancellationToken::default() }
+
+  pub fn cancel(&self) { self.state.store(CANCELLED, Ordering::SeqCst); }
+
+  /// Cancels because the process itself was interrupted, such as by a Ctrl-C.
+  pub fn interrupt(&self) { self.state.store(INTERRUPTED, Ordering::SeqCst); }
+
+  pub fn reset(&self) { self.state.store(ACTIVE, Ordering::SeqCst); }
+
+  pub fn is_cancelled(&self) -> bool { self.state.load(Ordering::SeqCst) != ACTIVE }
+
+  pub fn was_interrupted(&self) -> bool { self.state.load(Ordering::SeqCst) == INTERRUPTED }
+}
+
+/// Called every so often during execution, returning true if it should be interrupted.
+pub type InterruptCheck = Arc<dyn Fn() -> bool + Send + Sync>;
 
 pub struct RasqalConfig {
   /// How many steps the symbolic executor is allowed to make before failing.
   pub step_count_limit: Option<i64>,
 
+  /// How long a single execution is allowed to take before failing. Only checked between steps,
+  /// so a circuit already running on a QPU will be waited on.
+  pub time_limit: Option<Duration>,
+
+  /// Stops any execution using this config when cancelled.
+  pub cancellation: CancellationToken,
+
+  /// Polled periodically during execution to see if something outside of it wants it stopped,
+  /// such as a signal in the host process.
+  pub interrupt_check: Option<InterruptCheck>,
+
   /// Currently active debug tracers.
   pub debug_tracers: ActiveTracers,
 
+  /// Subscribers to the typed trace events emitted during execution.
+  pub events: EventBus,
+
+  /// Stops execution at its breakpoints so the runtime can be inspected.
+  pub debugger: Option<Debugger>,
+
   /// Whether projection circuit solving should be activated. If this is true every circuit will
   /// be included into the solver to help run it. Can drastically change what sort of circuits are
   /// run.
-  pub solver_active: bool
+  pub solver_active: bool,
+
+  /// Which solver implementation is used when the solver is active.
+  pub solver_strategy: SolverStrategy,
+
+  /// Cap on the bond dimension of the matrix-product-state solver. Larger values are more
+  /// accurate for highly-entangled circuits but cost more memory and time.
+  pub max_bond_dimension: usize,
+
+  /// Whether projections get split into their independent circuits, with each one (and any
+  /// other projection needed at the same time) dispatched concurrently across every capable QPU.
+  pub parallel_dispatch: bool,
+
+  /// Whether qubits only share a projection once gates entangle them, with qubits split back off
+  /// into their own when reset. Independent sub-circuits then execute as separate, smaller jobs
+  /// rather than as one circuit covering every qubit.
+  pub projection_splitting: bool,
+
+  /// Results of circuits already run on a QPU, shared by every execution using this config. When
+  /// set, identical circuits sent to the same sort of backend are only executed once.
+  pub result_cache: Option<Ptr<ProjectionCache>>,
+
+  /// How long results stay in the result cache before their circuits need executing again.
+  pub result_cache_time_to_live: Option<Duration>,
+
+  /// Most results the result cache holds, with the oldest evicted first once it's full.
+  pub result_cache_max_entries: Option<usize>,
+
+  /// Options passed to runtimes for every circuit executed.
+  pub execution_options: ExecutionOptions,
+
+  /// Overrides of the global execution options for particular projections, keyed by the order
+  /// the projection was created in during a run (starting at 0).
+  pub projection_options: HashMap<usize, ExecutionOptions>,
+
+  /// How measurement results get turned into a single classical value when a program branches on
+  /// or reads them. Per-shot reduction picks its shots using the execution options seed.
+  pub result_reduction: ResultReduction,
+
+  /// How qubits the program releases get reused when it allocates more.
+  pub qubit_allocation: QubitAllocation
 }
 
 impl RasqalConfig {
   pub fn step_count_limit(&mut self, count: i64) { self.step_count_limit = Some(count); }
 
+  pub fn time_limit(&mut self, limit: Duration) { self.time_limit = Some(limit); }
+
+  pub fn interrupt_check(&mut self, check: impl Fn() -> bool + Send + Sync + 'static) {
+    self.interrupt_check = Some(Arc::new(check));
+  }
+
   pub fn trace_runtime(&mut self) { self.debug_tracers.insert(ActiveTracers::Runtime); }
 
   pub fn trace_projections(&mut self) { self.debug_tracers.insert(ActiveTracers::Projections); }
 
   pub fn trace_graphs(&mut self) { self.debug_tracers.insert(ActiveTracers::Graphs); }
 
+  pub fn trace_solver(&mut self) { self.debug_tracers.insert(ActiveTracers::Solver); }
+
+  /// Calls this with every trace event emitted during execution.
+  pub fn subscribe(&mut self, subscriber: impl Fn(&TraceEvent) + Send + Sync + 'static) {
+    self.events.subscribe(subscriber);
+  }
+
+  /// Receiver for every trace event emitted during execution.
+  pub fn trace_events(&mut self) -> Receiver<TraceEvent> { self.events.channel() }
+
+  /// Writes every trace event emitted during execution to this file as JSON lines.
+  pub fn trace_to_file(&mut self, path: &Path) -> Result<(), String> { self.events.write_to(path) }
+
+  pub fn debug(&mut self, debugger: Debugger) { self.debugger = Some(debugger); }
+
+  pub fn activate_solver(&mut self) { self.solver_active = true; }
+
+  pub fn solver_strategy(&mut self, strategy: SolverStrategy) { self.solver_strategy = strategy; }
+
+  pub fn max_bond_dimension(&mut self, max_bond_dimension: usize) {
+    self.max_bond_dimension = max_bond_dimension;
+  }
+
+  pub fn parallel_dispatch(&mut self) { self.parallel_dispatch = true; }
+
+  pub fn projection_splitting(&mut self) { self.projection_splitting = true; }
+
+  pub fn cache_results(&mut self, cache: ProjectionCache) {
+    self.result_cache = Some(Ptr::from(cache));
+  }
+
+  pub fn result_cache_limits(
+    &mut self, time_to_live: Option<Duration>, max_entries: Option<usize>
+  ) {
+    self.result_cache_time_to_live = time_to_live;
+    self.result_cache_max_entries = max_entries;
+  }
+
+  pub fn execution_options(&mut self, options: ExecutionOptions) {
+    self.execution_options = options;
+  }
+
+  pub fn projection_options(&mut self, index: usize, options: ExecutionOptions) {
+    self.projection_options.insert(index, options);
+  }
+
+  pub fn result_reduction(&mut self, reduction: ResultReduction) {
+    self.result_reduction = reduction;
+  }
+
+  pub fn qubit_allocation(&mut self, allocation: QubitAllocation) {
+    self.qubit_allocation = allocation;
+  }
+
+  /// Options for the projection created at this index, including any overrides.
+  pub fn options_for(&self, index: usize) -> ExecutionOptions {
+    self
+      .projection_options
+      .get(&index)
+      .map_or(self.execution_options.clone(), |val| {
+        val.over(&self.execution_options)
+      })
+  }
+
   pub fn with_trace_runtime(mut self) -> RasqalConfig {
     self.debug_tracers.insert(ActiveTracers::Runtime);
     self
@@ -37,28 +265,124 @@ impl RasqalConfig {
     self
   }
 
+  pub fn with_debugger(mut self, debugger: Debugger) -> RasqalConfig {
+    self.debugger = Some(debugger);
+    self
+  }
+
   pub fn with_step_count_limit(mut self, count: i64) -> RasqalConfig {
     self.step_count_limit = Some(count);
     self
   }
 
+  pub fn with_time_limit(mut self, limit: Duration) -> RasqalConfig {
+    self.time_limit = Some(limit);
+    self
+  }
+
+  pub fn with_cancellation(mut self, token: &CancellationToken) -> RasqalConfig {
+    self.cancellation = token.clone();
+    self
+  }
+
   pub fn with_trace_solver(mut self) -> RasqalConfig {
     self.debug_tracers.insert(ActiveTracers::Solver);
     self
   }
 
+  pub fn with_subscriber(
+    mut self, subscriber: impl Fn(&TraceEvent) + Send + Sync + 'static
+  ) -> RasqalConfig {
+    self.events.subscribe(subscriber);
+    self
+  }
+
   pub fn with_activate_solver(mut self) -> RasqalConfig {
     self.solver_active = true;
     self
   }
+
+  /// Activates the solver using the matrix-product-state implementation, with its bond dimension
+  /// capped at the passed-in value.
+  pub fn with_mps_solver(mut self, max_bond_dimension: usize) -> RasqalConfig {
+    self.solver_active = true;
+    self.solver_strategy = SolverStrategy::MatrixProductState;
+    self.max_bond_dimension = max_bond_dimension;
+    self
+  }
+
+  pub fn with_parallel_dispatch(mut self) -> RasqalConfig {
+    self.parallel_dispatch = true;
+    self
+  }
+
+  pub fn with_projection_splitting(mut self) -> RasqalConfig {
+    self.projection_splitting = true;
+    self
+  }
+
+  pub fn with_result_cache(mut self, cache: ProjectionCache) -> RasqalConfig {
+    self.result_cache = Some(Ptr::from(cache));
+    self
+  }
+
+  pub fn with_result_cache_limits(
+    mut self, time_to_live: Option<Duration>, max_entries: Option<usize>
+  ) -> RasqalConfig {
+    self.result_cache_limits(time_to_live, max_entries);
+    self
+  }
+
+  pub fn with_execution_options(mut self, options: ExecutionOptions) -> RasqalConfig {
+    self.execution_options = options;
+    self
+  }
+
+  pub fn with_projection_options(
+    mut self, index: usize, options: ExecutionOptions
+  ) -> RasqalConfig {
+    self.projection_options.insert(index, options);
+    self
+  }
+
+  pub fn with_max_bond_dimension(mut self, max_bond_dimension: usize) -> RasqalConfig {
+    self.max_bond_dimension = max_bond_dimension;
+    self
+  }
+
+  pub fn with_result_reduction(mut self, reduction: ResultReduction) -> RasqalConfig {
+    self.result_reduction = reduction;
+    self
+  }
+
+  pub fn with_qubit_allocation(mut self, allocation: QubitAllocation) -> RasqalConfig {
+    self.qubit_allocation = allocation;
+    self
+  }
 }
 
 impl Default for RasqalConfig {
   fn default() -> Self {
     RasqalConfig {
       step_count_limit: None,
+      time_limit: None,
+      cancellation: CancellationToken::default(),
+      interrupt_check: None,
       debug_tracers: ActiveTracers::empty(),
-      solver_active: false
+      events: EventBus::default(),
+      debugger: None,
+      solver_active: false,
+      solver_strategy: SolverStrategy::Entanglement,
+      max_bond_dimension: DEFAULT_BOND_DIMENSION,
+      parallel_dispatch: false,
+      projection_splitting: false,
+      result_cache: None,
+      result_cache_time_to_live: None,
+      result_cache_max_entries: None,
+      execution_options: ExecutionOptions::default(),
+      projection_options: HashMap::new(),
+      result_reduction: ResultReduction::default(),
+      qubit_allocation: QubitAllocation::default()
     }
   }
 }
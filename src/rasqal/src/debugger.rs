@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::instructions::Instruction;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// Somewhere a [`Debugger`] stops execution, just before the node is executed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Breakpoint {
+  /// Node in the named function, by its order. Orders are what's shown for each node when
+  /// tracing graphs or the runtime.
+  Node(String, i64),
+
+  /// Label with this name in any function. Labels are the basic blocks of the original QIR.
+  Label(String),
+
+  /// Start of every call to the function with this name.
+  Function(String)
+}
+
+impl Display for Breakpoint {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Breakpoint::Node(graph, order) => write!(f, "node {order} in {graph}"),
+      Breakpoint::Label(label) => write!(f, "label {label}"),
+      Breakpoint::Function(name) => write!(f, "function {name}")
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopReason {
+  Breakpoint(Breakpoint),
+
+  /// Previous stop asked to step, so stopped at the very next node.
+  Step
+}
+
+/// Projection which is active in the runtime at the point execution stopped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingProjection {
+  /// Order the projection was created in during the run, starting at 0.
+  pub index: usize,
+
+  /// Qubits currently associated with the projection.
+  pub qubits: Vec<i64>,
+
+  /// Operations added to the projection so far.
+  pub circuit: Vec<String>,
+
+  /// Whether it's already been executed, which happens when its results are first needed.
+  pub concretized: bool
+}
+
+/// State of the runtime when it's stopped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugStop {
+  pub reason: StopReason,
+
+  /// Function being executed and the order of the node about to be executed in it.
+  pub graph: String,
+  pub node: i64,
+  pub instruction: String,
+  pub step: i64,
+
+  /// Where the node came from in the QIR or its source, if known.
+  pub origin: Option<String>,
+
+  /// Every function currently being executed, outermost first.
+  pub call_stack: Vec<String>,
+
+  /// Variables in the current function with any references followed. Quantum results which
+  /// haven't been executed yet show as deferred, inspecting them won't execute anything.
+  pub variables: BTreeMap<String, String>,
+  pub active_qubits: Vec<i64>,
+  pub projections: Vec<PendingProjection>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugAction {
+  /// Execute the current node then stop at the next one, wherever that is.
+  Step,
+
+  /// Carry on until the next breakpoint.
+  Continue,
+
+  /// Stop execution, which then fails.
+  Abort
+}
+
+impl DebugAction {
+  pub fn from_name(name: &str) -> Option<DebugAction> {
+    match name {
+      "step" => Some(DebugAction::Step),
+      "continue" => Some(DebugAction::Continue),
+      "abort" => Some(DebugAction::Abort),
+      _ => None
+    }
+  }
+}
+
+/// Called whenever execution stops, returning what should happen next.
+pub type DebugHandler = Arc<dyn Fn(&DebugStop) -> DebugAction + Send + Sync>;
+
+/// Stops execution at breakpoints, or at every node while stepping, and hands the state of the
+/// runtime to its handler to inspect. The handler is called on the executing thread, and
+/// execution waits until it returns.
+#[derive(Clone)]
+pub struct Debugger {
+  breakpoints: Vec<Breakpoint>,
+  handler: DebugHandler
+}
+
+impl Debugger {
+  pub fn new(handler: impl Fn(&DebugStop) -> DebugAction + Send + Sync + 'static) -> Debugger {
+    Debugger {
+      breakpoints: Vec::new(),
+      handler: Arc::new(handler)
+    }
+  }
+
+  pub fn with_breakpoint(mut self, breakpoint: Breakpoint) -> Debugger {
+    self.breakpoints.push(breakpoint);
+    self
+  }
+
+  pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) { self.breakpoints.push(breakpoint); }
+
+  pub fn clear_breakpoints(&mut self) { self.breakpoints.clear(); }
+
+  pub fn breakpoints(&self) -> &Vec<Breakpoint> { &self.breakpoints }
+
+  /// First breakpoint which stops at this node. Entry is whether it's the first node of a call.
+  pub(crate) fn breakpoint_at(
+    &self, graph: &str, order: i64, instruction: &Instruction, entry: bool
+  ) -> Option<&Breakpoint> {
+    self.breakpoints.iter().find(|breakpoint| match breakpoint {
+      Breakpoint::Node(name, node) => name == graph && *node == order,
+      Breakpoint::Label(label) => {
+        matches!(instruction, Instruction::Label(name) if name == label)
+      }
+      Breakpoint::Function(name) => entry && name == graph
+    })
+  }
+
+  pub(crate) fn stopped(&self, stop: &DebugStop) -> DebugAction { (self.handler)(stop) }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::debugger::{Breakpoint, DebugAction, Debugger};
+  use crate::instructions::Instruction;
+
+  #[test]
+  fn match_breakpoints() {
+    let debugger = Debugger::new(|_| DebugAction::Continue)
+      .with_breakpoint(Breakpoint::Node(String::from("main"), 4))
+      .with_breakpoint(Breakpoint::Label(String::from("body")))
+      .with_breakpoint(Breakpoint::Function(String::from("helper")));
+
+    let label = Instruction::Label(String::from("body"));
+    assert_eq!(
+      debugger.breakpoint_at("other", 2, &label, false),
+      Some(&Breakpoint::Label(String::from("body")))
+    );
+    assert!(debugger
+      .breakpoint_at("main", 4, &Instruction::NoOp, false)
+      .is_some());
+    assert!(debugger
+      .breakpoint_at("other", 4, &Instruction::NoOp, false)
+      .is_none());
+
+    // Functions are only stopped at when they're called, not at every node in them.
+    assert!(debugger
+      .breakpoint_at("helper", 0, &Instruction::NoOp, true)
+      .is_some());
+    assert!(debugger
+      .breakpoint_at("helper", 1, &Instruction::NoOp, false)
+      .is_none());
+  }
+}
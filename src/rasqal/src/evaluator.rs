@@ -2,7 +2,8 @@
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
 use crate::graphs::{
-  AnalysisGraph, AnalysisGraphBuilder, CallableAnalysisGraph, ExecutableAnalysisGraph, Node
+  AnalysisGraph, AnalysisGraphBuilder, ArgumentType, CallableAnalysisGraph, EntryPointSignature,
+  ExecutableAnalysisGraph, Node, NodeOrigin, SourceLocation
 };
 use crate::hardware::Qubit;
 use crate::instructions::{
@@ -11,6 +12,7 @@ use crate::instructions::{
 use crate::runtime::RuntimeContext;
 use crate::smart_pointers::Ptr;
 use crate::with_mutable;
+use inkwell::attributes::AttributeLoc;
 use inkwell::basic_block::BasicBlock;
 use inkwell::module::Module;
 use inkwell::types::AnyTypeEnum;
@@ -23,6 +25,10 @@ use llvm_sys::core::{
   LLVMConstIntGetSExtValue, LLVMGetElementType, LLVMGetNumOperands, LLVMGetOperand,
   LLVMGetTypeKind, LLVMPrintTypeToString, LLVMPrintValueToString, LLVMTypeOf
 };
+use llvm_sys::debuginfo::{
+  LLVMDIFileGetFilename, LLVMDILocationGetColumn, LLVMDILocationGetLine, LLVMDILocationGetScope,
+  LLVMDIScopeGetFile, LLVMInstructionGetDebugLoc
+};
 use llvm_sys::prelude::LLVMValueRef;
 use llvm_sys::LLVMTypeKind;
 use log::{log, warn, Level};
@@ -145,6 +151,83 @@ pub fn get_ref_id_from_value(ptr_string: &str) -> String {
   parse_ref_id_from_value(ptr_string).expect("Can't parse ref-id from value.")
 }
 
+/// Builds the signature of this function, treating it as an entry-point.
+pub fn entry_point_signature(function: &FunctionValue) -> EntryPointSignature {
+  let arguments = function
+    .get_params()
+    .iter()
+    .map(|param| {
+      (
+        get_ref_id_from_value(&param.to_string()),
+        ArgumentType::from_llvm(&param.get_type().print_to_string().to_string())
+      )
+    })
+    .collect();
+
+  let attributes = function
+    .attributes(AttributeLoc::Function)
+    .iter()
+    .filter(|attr| attr.is_string())
+    .map(|attr| {
+      (
+        attr.get_string_kind_id().to_string_lossy().to_string(),
+        attr.get_string_value().to_string_lossy().to_string()
+      )
+    })
+    .collect();
+
+  EntryPointSignature {
+    name: function.get_name().to_string_lossy().to_string(),
+    arguments,
+    return_type: function
+      .get_type()
+      .get_return_type()
+      .map_or(ArgumentType::Unit, |val| {
+        ArgumentType::from_llvm(&val.print_to_string().to_string())
+      }),
+    attributes
+  }
+}
+
+/// Source location from the `!dbg` metadata attached to this instruction, if there is any.
+pub fn debug_location(inst: &InstructionValue) -> Option<SourceLocation> {
+  unsafe {
+    let location = LLVMInstructionGetDebugLoc(inst.as_value_ref());
+    if location.is_null() {
+      return None;
+    }
+
+    // Locations always have a scope, but the scope doesn't always have a file.
+    let mut file = String::new();
+    let file_metadata = LLVMDIScopeGetFile(LLVMDILocationGetScope(location));
+    if !file_metadata.is_null() {
+      let mut length: c_uint = 0;
+      let name = LLVMDIFileGetFilename(file_metadata, &mut length);
+      if !name.is_null() {
+        let bytes = std::slice::from_raw_parts(name as *const u8, length as usize);
+        file = String::from_utf8_lossy(bytes).to_string();
+      }
+    }
+
+    Some(SourceLocation {
+      file,
+      line: LLVMDILocationGetLine(location),
+      column: LLVMDILocationGetColumn(location)
+    })
+  }
+}
+
+/// Function and basic block this instruction is in, along with its source location.
+pub fn instruction_origin(inst: &InstructionValue) -> Option<NodeOrigin> {
+  let block = inst.get_parent()?;
+  let function = block.get_parent()?;
+  Some(NodeOrigin::new(
+    &function.get_name().to_string_lossy(),
+    &block.get_name().to_string_lossy(),
+    debug_location(inst)
+  ))
+}
+
 /// Parsing context, molds all state required by the evalautor to run.
 pub struct EvaluationContext<'ctx> {
   pub module: Ptr<Module<'ctx>>,
@@ -243,16 +326,14 @@ impl QIREvaluator {
     // Create a callable graph with its arguments, but the values set as empty (validly).
     let mut callable = Ptr::from(CallableAnalysisGraph::new(&builder.graph));
     for param in entry_point.get_params().iter() {
-      let param_ref_id = get_ref_id_from_value(&param.to_string());
-      callable
-        .argument_mappings
-        .insert(param_ref_id, Ptr::from(Value::Empty));
+      callable.add_parameter(get_ref_id_from_value(&param.to_string()));
     }
 
-    let exe_graph = ExecutableAnalysisGraph::with_context(
+    let mut exe_graph = ExecutableAnalysisGraph::with_context(
       &callable,
       &Ptr::from(RuntimeContext::from_evaluation(&context))
     );
+    exe_graph.signature = entry_point_signature(entry_point);
 
     Ok(Ptr::from(exe_graph))
   }
@@ -306,11 +387,14 @@ impl QIREvaluator {
     let mut subcontext = Ptr::from(EvaluationContext::create_subcontext(context));
 
     let graph = Ptr::from(AnalysisGraph::new(method_name.clone()));
-    with_mutable!(context.method_graphs.insert(method_name, graph.clone()));
+    with_mutable!(context
+      .method_graphs
+      .insert(method_name.clone(), graph.clone()));
 
     // Build up anchor labels/nodes so we can associate them at the start and end.
     for bb in func.get_basic_blocks() {
       let bb_name = bb.get_name().to_str().unwrap().to_string();
+      with_mutable!(graph.current_origin = Some(NodeOrigin::new(&method_name, &bb_name, None)));
       let anchor_node = with_mutable!(graph.add_loose(Instruction::Label(bb_name.clone())));
       subcontext.anchors.insert(bb_name, anchor_node.clone());
     }
@@ -320,6 +404,8 @@ impl QIREvaluator {
       self.walk_basic_block(bb, &builder, subcontext.borrow());
     }
 
+    // Anything added after evaluation is synthesized, so has no origin.
+    with_mutable!(graph.current_origin = None);
     builder
   }
 
@@ -654,6 +740,9 @@ impl QIREvaluator {
     &self, inst: &Ptr<InstructionValue>, graph: &Ptr<AnalysisGraphBuilder>,
     context: &Ptr<EvaluationContext>
   ) {
+    // Every node added while evaluating this instruction comes from it.
+    with_mutable!(graph.graph.current_origin = instruction_origin(inst));
+
     let op_code = inst.get_opcode();
     match op_code {
       InstructionOpcode::Call => {
@@ -1163,6 +1252,11 @@ impl QIREvaluator {
         graph.Measure(Value::Pauli(Pauli::Z), qb, target_value);
       }
 
+      "__quantum__qis__reset__body" => {
+        let qb = parse_qubit(inst, 0);
+        graph.Reset(qb);
+      }
+
       // Slightly non-official QIR, measure then reset.
       "__quantum__qis__mresetz__body" => {
         let target_value = if let Some(val) = parse_ref_id_from_instruction(inst.borrow()) {
@@ -1213,13 +1307,40 @@ impl QIREvaluator {
           with_mutable!(context.is_base_profile.expand_into(&Ptr::from(true)));
         }
       }
-      "__quantum__rt__string_equal" | "__quantum__rt__result_equal" => {
+      "__quantum__rt__string_equal" => {
         let ref_id = get_ref_id_from_instruction(inst.borrow());
         let left = parse_as_value(inst, 0).expect("Left comparison result unresolvable.");
         let right = parse_as_value(inst, 1).expect("Right comparison result unresolvable.");
 
         graph.Condition(ref_id, left, Equalities::Equals, right);
       }
+      "__quantum__rt__result_equal" => {
+        let ref_id = get_ref_id_from_instruction(inst.borrow());
+        let mut operands = Vec::new();
+        for index in 0..2 {
+          let value = parse_as_value(inst, index).expect("Comparison result unresolvable.");
+
+          // Static results are addresses in the classical register, so have to be read first.
+          operands.push(if let Value::Int(_) = value {
+            let register_var = format!("{ref_id}_{index}");
+            graph.Expression(Expression::ReadResult(value), Some(register_var.clone()));
+            Value::Ref(register_var, None)
+          } else {
+            value
+          });
+        }
+
+        let right = operands.pop().unwrap();
+        let left = operands.pop().unwrap();
+        graph.Condition(ref_id, left, Equalities::Equals, right);
+      }
+      "__quantum__qis__read_result__body"
+      | "__quantum__qir__read_result"
+      | "__quantum__rt__read_result" => {
+        let ref_id = get_ref_id_from_instruction(inst.borrow());
+        let result = parse_as_value(inst, 0).expect("Can't resolve result.");
+        graph.Expression(Expression::ReadResult(result), Some(ref_id));
+      }
       "__quantum__rt__result_get_one" => {
         let ref_id = get_ref_id_from_instruction(inst.borrow());
         graph.Assign(ref_id, Value::Int(1));
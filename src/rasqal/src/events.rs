@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use log::{log, Level};
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+
+/// Something that happened during execution. Unlike the tracers' log lines these are meant to be
+/// consumed by tooling, so every value is kept separate rather than formatted into a message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+  /// Runtime is about to execute this node. Node IDs are stable for the lifetime of a graph, so
+  /// can be used to spot revisits.
+  NodeEntered {
+    graph: String,
+    node: usize,
+    instruction: String,
+
+    /// Where the node came from in the QIR or its source, if known.
+    origin: Option<String>,
+    step: i64
+  },
+
+  /// Variable has a different value after the node executed last.
+  VariableChanged {
+    graph: String,
+    variable: String,
+    value: String
+  },
+
+  /// Index is the order the projection was created in during the run, starting at 0.
+  ProjectionCreated {
+    projection: usize
+  },
+
+  /// Projection has been merged into an older one, as a gate or measure spanned qubits in both.
+  /// Operations meant for it get added to the projection it merged into from then on.
+  ProjectionsMerged {
+    projection: usize,
+    into: usize
+  },
+
+  /// Operation added to a projections circuit. For controlled operations the controls come
+  /// first and the target last.
+  GateAdded {
+    projection: usize,
+    gate: String,
+    qubits: Vec<i64>,
+    radians: Option<f64>
+  },
+
+  /// Solver has looked at a projection, and whether it could calculate the results outright.
+  SolverDecision {
+    projection: usize,
+    solved: bool
+  },
+
+  /// Projection has its results, either from the solver or from the backends listed.
+  ProjectionConcretized {
+    projection: usize,
+    solved: bool,
+    backends: Vec<String>,
+    results: Vec<(String, i64)>
+  },
+
+  SubgraphCalled {
+    caller: String,
+    callee: String
+  },
+  SubgraphReturned {
+    caller: String,
+    callee: String
+  }
+}
+
+/// Value of a single field of a [`TraceEvent`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceField {
+  Empty,
+  Int(i64),
+  Float(f64),
+  Bool(bool),
+  String(String),
+  Ints(Vec<i64>),
+  Strings(Vec<String>),
+
+  /// Result distribution, bitstring to count.
+  Counts(Vec<(String, i64)>)
+}
+
+impl TraceEvent {
+  /// Snake-case name of this event, used as its type when serialized.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      TraceEvent::NodeEntered { .. } => "node_entered",
+      TraceEvent::VariableChanged { .. } => "variable_changed",
+      TraceEvent::ProjectionCreated { .. } => "projection_created",
+      TraceEvent::ProjectionsMerged { .. } => "projections_merged",
+      TraceEvent::GateAdded { .. } => "gate_added",
+      TraceEvent::SolverDecision { .. } => "solver_decision",
+      TraceEvent::ProjectionConcretized { .. } => "projection_concretized",
+      TraceEvent::SubgraphCalled { .. } => "subgraph_called",
+      TraceEvent::SubgraphReturned { .. } => "subgraph_returned"
+    }
+  }
+
+  /// Every field of this event, in declaration order.
+  pub fn fields(&self) -> Vec<(&'static str, TraceField)> {
+    let string = |val: &String| TraceField::String(val.clone());
+    let index = |val: &usize| TraceField::Int(*val as i64);
+    match self {
+      TraceEvent::NodeEntered {
+        graph,
+        node,
+        instruction,
+        origin,
+        step
+      } => vec![
+        ("graph", string(graph)),
+        ("node", index(node)),
+        ("instruction", string(instruction)),
+        ("origin", origin.as_ref().map_or(TraceField::Empty, string)),
+        ("step", TraceField::Int(*step))
+      ],
+      TraceEvent::VariableChanged {
+        graph,
+        variable,
+        value
+      } => vec![
+        ("graph", string(graph)),
+        ("variable", string(variable)),
+        ("value", string(value))
+      ],
+      TraceEvent::ProjectionCreated { projection } => vec![("projection", index(projection))],
+      TraceEvent::ProjectionsMerged { projection, into } => {
+        vec![("projection", index(projection)), ("into", index(into))]
+      }
+      TraceEvent::GateAdded {
+        projection,
+        gate,
+        qubits,
+        radians
+      } => vec![
+        ("projection", index(projection)),
+        ("gate", string(gate)),
+        ("qubits", TraceField::Ints(qubits.clone())),
+        ("radians", radians.map_or(TraceField::Empty, TraceField::Float))
+      ],
+      TraceEvent::SolverDecision { projection, solved } => vec![
+        ("projection", index(projection)),
+        ("solved", TraceField::Bool(*solved))
+      ],
+      TraceEvent::ProjectionConcretized {
+        projection,
+        solved,
+        backends,
+        results
+      } => vec![
+        ("projection", index(projection)),
+        ("solved", TraceField::Bool(*solved)),
+        ("backends", TraceField::Strings(backends.clone())),
+        ("results", TraceField::Counts(results.clone()))
+      ],
+      TraceEvent::SubgraphCalled { caller, callee }
+      | TraceEvent::SubgraphReturned { caller, callee } => {
+        vec![("caller", string(caller)), ("callee", string(callee))]
+      }
+    }
+  }
+
+  /// Serializes this event as a single-line JSON object, with its kind under "event".
+  pub fn to_json(&self) -> String {
+    let mut json = format!("{{\"event\": {}", json_string(self.kind()));
+    for (name, field) in self.fields() {
+      let value = match field {
+        TraceField::Empty => String::from("null"),
+        TraceField::Int(val) => val.to_string(),
+        TraceField::Float(val) => json_float(val),
+        TraceField::Bool(val) => val.to_string(),
+        TraceField::String(val) => json_string(&val),
+        TraceField::Ints(vals) => format!(
+          "[{}]",
+          vals
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+        ),
+        TraceField::Strings(vals) => format!(
+          "[{}]",
+          vals
+            .iter()
+            .map(|val| json_string(val))
+            .collect::<Vec<_>>()
+            .join(", ")
+        ),
+        TraceField::Counts(vals) => format!(
+          "{{{}}}",
+          vals
+            .iter()
+            .map(|(key, count)| format!("{}: {count}", json_string(key)))
+            .collect::<Vec<_>>()
+            .join(", ")
+        )
+      };
+      json.push_str(&format!(", {}: {value}", json_string(name)));
+    }
+    json.push('}');
+    json
+  }
+}
+
+fn json_string(value: &str) -> String {
+  let mut escaped = String::from("\"");
+  for ch in value.chars() {
+    match ch {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      ch if (ch as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+      ch => escaped.push(ch)
+    }
+  }
+  escaped.push('"');
+  escaped
+}
+
+/// JSON has no representation for infinities or NaN, so they're written as null.
+fn json_float(value: f64) -> String {
+  if value.is_finite() {
+    value.to_string()
+  } else {
+    String::from("null")
+  }
+}
+
+/// Called with every event emitted during execution. Subscribers are called on the executing
+/// thread, in the order events happen, so should be quick.
+pub type TraceSubscriber = Arc<dyn Fn(&TraceEvent) + Send + Sync>;
+
+/// Hands trace events to everything subscribed. Events are only built if something is listening,
+/// so emitting to an empty bus costs next to nothing.
+#[derive(Clone, Default)]
+pub struct EventBus {
+  subscribers: Vec<TraceSubscriber>
+}
+
+impl EventBus {
+  pub fn new() -> EventBus { EventBus::default() }
+
+  pub fn subscribe(&mut self, subscriber: impl Fn(&TraceEvent) + Send + Sync + 'static) {
+    self.subscribers.push(Arc::new(subscriber));
+  }
+
+  /// Sends every event to the returned receiver, which can be iterated over from another thread
+  /// as execution happens or drained afterwards.
+  pub fn channel(&mut self) -> Receiver<TraceEvent> {
+    let (sender, receiver) = channel();
+    let sender = Mutex::new(sender);
+    self.subscribe(move |event| {
+      // If the receiver has been dropped nobody is listening anymore, so nothing to do.
+      let _ = sender.lock().unwrap().send(event.clone());
+    });
+    receiver
+  }
+
+  /// Writes every event to this file as a line of JSON, replacing anything already there.
+  pub fn write_to(&mut self, path: &Path) -> Result<(), String> {
+    let file = File::create(path)
+      .map_err(|err| format!("Unable to create trace file at {}: {err}", path.display()))?;
+    let writer = Mutex::new(LineWriter::new(file));
+    let path = path.display().to_string();
+    self.subscribe(move |event| {
+      // Subscribers can't stop execution, so failing to write is only reported.
+      if let Err(err) = writeln!(writer.lock().unwrap(), "{}", event.to_json()) {
+        log!(Level::Warn, "Unable to write trace event to {path}: {err}");
+      }
+    });
+    Ok(())
+  }
+
+  pub fn is_active(&self) -> bool { !self.subscribers.is_empty() }
+
+  /// Builds the event and sends it to every subscriber, if there are any.
+  pub fn emit(&self, event: impl FnOnce() -> TraceEvent) {
+    if self.subscribers.is_empty() {
+      return;
+    }
+
+    let event = event();
+    for subscriber in &self.subscribers {
+      subscriber(&event);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::events::{EventBus, TraceEvent};
+  use std::env::temp_dir;
+  use std::fs;
+  use std::process;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  #[test]
+  fn serialize_events() {
+    let gate = TraceEvent::GateAdded {
+      projection: 0,
+      gate: String::from("cx"),
+      qubits: vec![0, 1],
+      radians: Some(0.5)
+    };
+    assert_eq!(
+      gate.to_json(),
+      "{\"event\": \"gate_added\", \"projection\": 0, \"gate\": \"cx\", \"qubits\": [0, 1], \
+       \"radians\": 0.5}"
+    );
+
+    let variable = TraceEvent::VariableChanged {
+      graph: String::from("main"),
+      variable: String::from("%0"),
+      value: String::from("\"quoted\"\n")
+    };
+    assert_eq!(
+      variable.to_json(),
+      "{\"event\": \"variable_changed\", \"graph\": \"main\", \"variable\": \"%0\", \
+       \"value\": \"\\\"quoted\\\"\\n\"}"
+    );
+
+    let concretized = TraceEvent::ProjectionConcretized {
+      projection: 1,
+      solved: false,
+      backends: vec![String::from("sim")],
+      results: vec![(String::from("00"), 3), (String::from("11"), 5)]
+    };
+    assert_eq!(
+      concretized.to_json(),
+      "{\"event\": \"projection_concretized\", \"projection\": 1, \"solved\": false, \
+       \"backends\": [\"sim\"], \"results\": {\"00\": 3, \"11\": 5}}"
+    );
+  }
+
+  #[test]
+  fn subscribers() {
+    let mut bus = EventBus::new();
+    let built = Arc::new(AtomicUsize::new(0));
+    let event = || {
+      built.fetch_add(1, Ordering::SeqCst);
+      TraceEvent::ProjectionCreated { projection: 2 }
+    };
+
+    // Nobody is listening, so the event shouldn't even be built.
+    bus.emit(event);
+    assert_eq!(built.load(Ordering::SeqCst), 0);
+
+    let receiver = bus.channel();
+    let path = temp_dir().join(format!("rasqal_trace_events_{}.jsonl", process::id()));
+    bus.write_to(&path).unwrap();
+    bus.emit(event);
+    bus.emit(|| TraceEvent::SolverDecision {
+      projection: 2,
+      solved: true
+    });
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+
+    let received = receiver.try_iter().collect::<Vec<_>>();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0], TraceEvent::ProjectionCreated { projection: 2 });
+
+    let written = fs::read_to_string(&path).unwrap();
+    let lines = written.lines().collect::<Vec<_>>();
+    assert_eq!(lines, vec![
+      "{\"event\": \"projection_created\", \"projection\": 2}",
+      "{\"event\": \"solver_decision\", \"projection\": 2, \"solved\": true}"
+    ]);
+    fs::remove_file(path).expect("Trace file should be removable.");
+  }
+}
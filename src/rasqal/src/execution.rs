@@ -3,13 +3,19 @@
 
 #![deny(clippy::all, clippy::pedantic)]
 
-use crate::builders::IntegrationRuntime;
-use crate::evaluator::QIREvaluator;
+use crate::analysis::projections::{AnalysisResult, DispatchJob};
+use crate::analysis::sampling::ShotSampler;
+#[cfg(feature = "python")]
+use crate::builders::PythonRuntime;
+use crate::builders::{IntegrationRuntime, QuantumBackend};
+use crate::evaluator::{entry_point_signature, QIREvaluator};
 use crate::features::QuantumFeatures;
 use crate::graphs::ExecutableAnalysisGraph;
 use crate::instructions::Value;
 use crate::runtime::QuantumRuntime;
+use crate::simulators::SimulatorRuntime;
 use crate::smart_pointers::Ptr;
+use crate::statistics::RunStatistics;
 use crate::with_mutable;
 use inkwell::attributes::AttributeLoc;
 use inkwell::values::FunctionValue;
@@ -25,7 +31,11 @@ use inkwell::{
 use crate::config::RasqalConfig;
 use crate::exceptions::catch_panics;
 use log::{log, Level};
-use std::{ffi::OsStr, path::Path};
+#[cfg(feature = "python")]
+use pyo3::Python;
+use std::collections::HashMap;
+use std::time::Instant;
+use std::{ffi::OsStr, panic, path::Path, thread};
 
 /// Executes the file.
 pub fn run_file(
@@ -49,6 +59,79 @@ pub fn parse_file(
   catch_panics(|| build_graph_from_module(&module, entry_point))
 }
 
+/// Parses and links several .ll/.bc files into one module, then builds an
+/// [`ExecutableAnalysisGraph`] for it. Calls into functions defined in any of the files are
+/// resolved as normal, so shared libraries of operations can live in their own modules.
+pub fn parse_files<P: AsRef<Path>>(
+  paths: &[P], entry_point: Option<&str>
+) -> Result<Ptr<ExecutableAnalysisGraph>, String> {
+  log!(
+    Level::Info,
+    "Parsing from {}.",
+    paths
+      .iter()
+      .map(|path| path.as_ref().display().to_string())
+      .collect::<Vec<_>>()
+      .join(", ")
+  );
+  let context = Context::create();
+  let module = files_to_module(paths, &context)?;
+  catch_panics(|| build_graph_from_module(&module, entry_point))
+}
+
+/// Parses in-memory LLVM IR text and builds an [`ExecutableAnalysisGraph`] for it.
+pub fn parse_text(
+  text: &str, entry_point: Option<&str>
+) -> Result<Ptr<ExecutableAnalysisGraph>, String> {
+  log!(Level::Info, "Parsing from in-memory LLVM IR.");
+  let context = Context::create();
+  let module = text_to_module(text, &context)?;
+  catch_panics(|| build_graph_from_module(&module, entry_point))
+}
+
+/// Parses in-memory LLVM bitcode and builds an [`ExecutableAnalysisGraph`] for it.
+pub fn parse_bitcode(
+  bitcode: &[u8], entry_point: Option<&str>
+) -> Result<Ptr<ExecutableAnalysisGraph>, String> {
+  log!(Level::Info, "Parsing from in-memory LLVM bitcode.");
+  let context = Context::create();
+  let module = bitcode_to_module(bitcode, &context)?;
+  catch_panics(|| build_graph_from_module(&module, entry_point))
+}
+
+/// Transforms several LLVM files into a single LLVM module, linking each into the first.
+pub fn files_to_module<'ctx, P: AsRef<Path>>(
+  paths: &[P], context: &'ctx Context
+) -> Result<Module<'ctx>, String> {
+  let (first, rest) = paths
+    .split_first()
+    .ok_or_else(|| String::from("No files to link."))?;
+
+  let module = file_to_module(first, context)?;
+  for path in rest {
+    module
+      .link_in_module(file_to_module(path, context)?)
+      .map_err(|e| format!("Failed to link {}: {e}", path.as_ref().display()))?;
+  }
+  Ok(module)
+}
+
+/// Transforms LLVM IR text into an LLVM module.
+pub fn text_to_module<'ctx>(text: &str, context: &'ctx Context) -> Result<Module<'ctx>, String> {
+  let buffer = MemoryBuffer::create_from_memory_range_copy(text.as_bytes(), "module");
+  context
+    .create_module_from_ir(buffer)
+    .map_err(|e| e.to_string())
+}
+
+/// Transforms LLVM bitcode into an LLVM module.
+pub fn bitcode_to_module<'ctx>(
+  bitcode: &[u8], context: &'ctx Context
+) -> Result<Module<'ctx>, String> {
+  let buffer = MemoryBuffer::create_from_memory_range_copy(bitcode, "module");
+  Module::parse_bitcode_from_buffer(&buffer, context).map_err(|e| e.to_string())
+}
+
 /// Transforms an LLVM file into an LLVM module.
 pub fn file_to_module(path: impl AsRef<Path>, context: &Context) -> Result<Module, String> {
   let path = path.as_ref();
@@ -91,8 +174,17 @@ pub fn build_graph_from_module(
       "{} is the entry-point.",
       entry_point.get_name().to_str().unwrap()
     );
+    let start = Instant::now();
     let evaluator = QIREvaluator::new();
-    evaluator.evaluate(&entry_point, &Ptr::from(module))
+    let graph = evaluator.evaluate(&entry_point, &Ptr::from(module))?;
+    with_mutable!(graph.evaluation_time = start.elapsed());
+    with_mutable!(
+      graph.entry_points = module_functions(module)
+        .filter(|func| is_entry_point(*func))
+        .map(|func| entry_point_signature(&func))
+        .collect()
+    );
+    Ok(graph)
   })
 }
 
@@ -101,8 +193,128 @@ pub fn run_graph(
   graph: &Ptr<ExecutableAnalysisGraph>, arguments: &Vec<Value>, runtimes: &Ptr<RuntimeCollection>,
   config: &Ptr<RasqalConfig>
 ) -> Result<Option<Ptr<Value>>, String> {
+  run_graph_with_statistics(graph, arguments, runtimes, config).map(|(value, _)| value)
+}
+
+/// Executes a graph, returning statistics about the run alongside its results.
+pub fn run_graph_with_statistics(
+  graph: &Ptr<ExecutableAnalysisGraph>, arguments: &Vec<Value>, runtimes: &Ptr<RuntimeCollection>,
+  config: &Ptr<RasqalConfig>
+) -> Result<(Option<Ptr<Value>>, RunStatistics), String> {
   let mut runtime = QuantumRuntime::new(runtimes, config);
-  catch_panics(|| runtime.execute(graph, arguments))
+  let results = catch_panics(|| runtime.execute(graph, arguments))?;
+  Ok((results, runtime.statistics()))
+}
+
+/// Executes a graph with arguments passed by name, ordering them as the entry-point declares them.
+/// Missing, unknown or mistyped arguments are returned as errors before anything is run.
+pub fn run_graph_named(
+  graph: &Ptr<ExecutableAnalysisGraph>, arguments: &HashMap<String, Value>,
+  runtimes: &Ptr<RuntimeCollection>, config: &Ptr<RasqalConfig>
+) -> Result<Option<Ptr<Value>>, String> {
+  let ordered = graph.signature.order_arguments(arguments)?;
+  run_graph(graph, &ordered, runtimes, config)
+}
+
+/// Executes a graph once for every set of arguments, returning results in the same order. Graph
+/// analysis is shared across runs. Runs are otherwise independent unless the config has a result
+/// cache, in which case any circuit identical to one already executed is served from it.
+pub fn run_graph_batch(
+  graph: &Ptr<ExecutableAnalysisGraph>, argument_sets: &Vec<Vec<Value>>,
+  runtimes: &Ptr<RuntimeCollection>, config: &Ptr<RasqalConfig>
+) -> Result<Vec<Option<Ptr<Value>>>, String> {
+  run_graph_batch_with_statistics(graph, argument_sets, runtimes, config)
+    .map(|results| results.into_iter().map(|(value, _)| value).collect())
+}
+
+/// See [`run_graph_batch`], with statistics about each run returned alongside its results.
+pub fn run_graph_batch_with_statistics(
+  graph: &Ptr<ExecutableAnalysisGraph>, argument_sets: &Vec<Vec<Value>>,
+  runtimes: &Ptr<RuntimeCollection>, config: &Ptr<RasqalConfig>
+) -> Result<Vec<(Option<Ptr<Value>>, RunStatistics)>, String> {
+  let previous_hits = config.result_cache.as_ref().map_or(0, |cache| cache.hits());
+  let mut results = Vec::new();
+  for arguments in argument_sets {
+    let mut runtime = QuantumRuntime::new(runtimes, config);
+    let result = catch_panics(|| runtime.execute(graph, arguments))?;
+    results.push((result, runtime.statistics()));
+  }
+
+  if let Some(cache) = config.result_cache.as_ref() {
+    log!(
+      Level::Info,
+      "Batch of {} runs reused {} projection results.",
+      argument_sets.len(),
+      cache.hits() - previous_hits
+    );
+  }
+  Ok(results)
+}
+
+/// Executes a graph once per shot on the built-in simulator, following a single sampled result
+/// every time a measurement is branched on or read, then counts up what each shot returned.
+///
+/// This is how programs with real feed-forward behave on hardware, so it's a reference to check
+/// projection-based execution against.
+pub fn run_graph_trajectories(
+  graph: &Ptr<ExecutableAnalysisGraph>, arguments: &Vec<Value>, shots: usize,
+  config: &Ptr<RasqalConfig>
+) -> Result<AnalysisResult, String> {
+  run_graph_trajectories_with_statistics(graph, arguments, shots, config)
+    .map(|(histogram, _)| histogram)
+}
+
+/// See [`run_graph_trajectories`], with statistics for every shot added together.
+pub fn run_graph_trajectories_with_statistics(
+  graph: &Ptr<ExecutableAnalysisGraph>, arguments: &Vec<Value>, shots: usize,
+  config: &Ptr<RasqalConfig>
+) -> Result<(AnalysisResult, RunStatistics), String> {
+  let simulator = SimulatorRuntime::new(config.solver_strategy)
+    .with_max_bond_dimension(config.max_bond_dimension);
+  let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+    IntegrationRuntime::new(simulator)
+  )));
+
+  let mut seeds = ShotSampler::with_seed(config.execution_options.seed);
+  let mut histogram = HashMap::new();
+  let mut statistics = RunStatistics::new();
+  for _ in 0..shots {
+    let mut runtime = QuantumRuntime::new(&runtimes, config);
+    runtime.follow_shot(seeds.next_seed());
+    let result = catch_panics(|| runtime.execute(graph, arguments))?;
+    *histogram
+      .entry(shot_outcome(result.as_deref()))
+      .or_insert(0) += 1;
+    statistics.merge(&runtime.statistics());
+  }
+
+  log!(
+    Level::Info,
+    "Ran {} trajectories with {} distinct outcomes.",
+    shots,
+    histogram.len()
+  );
+  Ok((AnalysisResult::new(histogram), statistics))
+}
+
+/// What a single shot returned, as a key for the trajectory histogram. Results only hold the
+/// one shot that was followed, so show up as its bitstring.
+fn shot_outcome(value: Option<&Value>) -> String {
+  match value {
+    None => String::new(),
+    Some(Value::AnalysisResult(result)) if result.distribution.len() == 1 => {
+      result.distribution.keys().next().unwrap().clone()
+    }
+    Some(Value::Array(values)) => format!(
+      "[{}]",
+      values
+        .iter()
+        .map(|val| shot_outcome(Some(&**val)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    Some(val) => val.to_string()
+  }
 }
 
 /// Top-level collection item that holds information about target runtimes and engines for graphs.
@@ -121,6 +333,11 @@ impl RuntimeCollection {
     self.QPU_runtimes.push(python_engine.clone());
   }
 
+  /// Registers a backend, which will be used for any circuits it has the features for.
+  pub fn register(&mut self, backend: impl QuantumBackend + 'static) {
+    self.add(&Ptr::from(IntegrationRuntime::new(backend)));
+  }
+
   pub fn from(python_engine: &Ptr<IntegrationRuntime>) -> RuntimeCollection {
     RuntimeCollection::new(vec![python_engine.clone()])
   }
@@ -135,6 +352,89 @@ impl RuntimeCollection {
 
     None
   }
+
+  /// Fetches every available QPU which has these features.
+  pub fn find_capable_QPUs(&self, features: &QuantumFeatures) -> Vec<Ptr<IntegrationRuntime>> {
+    self
+      .QPU_runtimes
+      .iter()
+      .filter(|engine| with_mutable!(engine.is_valid()) && engine.has_features(features))
+      .cloned()
+      .collect()
+  }
+
+  /// Executes each job against its runtime, returning results in the same order. When concurrent
+  /// every runtime gets its own thread which submits that runtime's jobs one after another, so
+  /// different blocking backends run side-by-side and asynchronous ones can queue everything up
+  /// front then wait on them all, but no runtime is ever called into from two threads at once.
+  /// Otherwise they're run one after another.
+  pub fn dispatch(jobs: &Vec<DispatchJob>, concurrent: bool) -> Vec<AnalysisResult> {
+    if !concurrent || jobs.len() < 2 {
+      return jobs
+        .iter()
+        .map(|(runtime, builder, options)| runtime.execute(builder, options))
+        .collect();
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (index, (runtime, ..)) in jobs.iter().enumerate() {
+      match groups
+        .iter_mut()
+        .find(|group| Ptr::eq(&jobs[group[0]].0, runtime))
+      {
+        Some(group) => group.push(index),
+        None => groups.push(vec![index])
+      }
+    }
+
+    let run_all = || {
+      thread::scope(|scope| {
+        let handles = groups
+          .iter()
+          .map(|group| {
+            scope.spawn(move || {
+              let pending = group
+                .iter()
+                .map(|index| {
+                  let (runtime, builder, options) = &jobs[*index];
+                  (*index, runtime.submit(builder, options))
+                })
+                .collect::<Vec<_>>();
+              pending
+                .into_iter()
+                .map(|(index, execution)| (index, execution.wait()))
+                .collect::<Vec<_>>()
+            })
+          })
+          .collect::<Vec<_>>();
+
+        let mut results = handles
+          .into_iter()
+          .flat_map(|handle| {
+            handle
+              .join()
+              .unwrap_or_else(|err| panic::resume_unwind(err))
+          })
+          .collect::<Vec<_>>();
+        results.sort_by_key(|(index, _)| *index);
+        results
+          .into_iter()
+          .map(|(_, result)| result)
+          .collect::<Vec<_>>()
+      })
+    };
+
+    // Python runtimes need the GIL to execute, so we need to release it while we wait on them.
+    #[cfg(feature = "python")]
+    if jobs
+      .iter()
+      .any(|(runtime, ..)| runtime.is::<PythonRuntime>())
+    {
+      return Python::with_gil(|py| py.allow_threads(run_all));
+    }
+
+    run_all()
+  }
 }
 
 impl Default for RuntimeCollection {
@@ -199,13 +499,35 @@ pub fn choose_entry_point<'ctx>(
 
 #[cfg(test)]
 mod tests {
-  use crate::builders::IntegrationRuntime;
-  use crate::config::RasqalConfig;
-  use crate::execution::{run_file, RuntimeCollection};
-  use crate::instructions::Value;
+  use crate::analysis::cache::ProjectionCache;
+  use crate::analysis::projections::{AnalysisResult, ResultReduction};
+  use crate::builders::{
+    CircuitBuilder, CircuitOperation, CircuitRecorder, IntegrationRuntime, PendingExecution,
+    QuantumBackend
+  };
+  use crate::config::ExecutionOptions;
+  use crate::config::{CancellationToken, RasqalConfig};
+  use crate::debugger::{Breakpoint, DebugAction, DebugStop, Debugger, StopReason};
+  use crate::events::TraceEvent;
+  use crate::execution::{
+    bitcode_to_module, file_to_module, parse_bitcode, parse_file, parse_files, parse_text,
+    run_file, run_graph, run_graph_batch, run_graph_named, run_graph_trajectories,
+    run_graph_trajectories_with_statistics, run_graph_with_statistics, RuntimeCollection
+  };
+  use crate::features::QuantumFeatures;
+  use crate::graphs::{ArgumentType, ExecutableAnalysisGraph};
+  use crate::hardware::QubitAllocation;
+  use crate::instructions::{Pauli, Value};
+  use crate::simulators::SimulatorRuntime;
   use crate::smart_pointers::Ptr;
+  use inkwell::context::Context;
   use std::borrow::Borrow;
-  use std::fs::canonicalize;
+  use std::collections::HashMap;
+  use std::fs::{canonicalize, read, read_to_string};
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::{Arc, Mutex};
+  use std::thread;
+  use std::time::Duration;
 
   /// Just run a QIR file to make sure it parses and returns the value.
   fn run(path: &str) -> Option<Ptr<Value>> { run_with_config(path, RasqalConfig::default()) }
@@ -241,6 +563,620 @@ mod tests {
     run_file(path, args, runtimes.borrow(), None, &Ptr::from(config))
   }
 
+  /// Runs one entry point out of a QIR file against these runtimes, returning its result as a bool.
+  fn run_entry_point(
+    path: &str, entry_point: &str, runtimes: &Ptr<RuntimeCollection>, config: RasqalConfig
+  ) -> Result<bool, String> {
+    run_file(
+      canonicalize(path).unwrap(),
+      &Vec::new(),
+      runtimes,
+      Some(entry_point),
+      &Ptr::from(config)
+    )
+    .map(|val| val.expect("Should have a result.").as_bool())
+  }
+
+  #[test]
+  fn execute_batch() {
+    let relative_path = canonicalize("../tests/files/qir/flip_argument.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::new(SimulatorRuntime::default())
+    )));
+
+    let results = run_graph_batch(
+      &graph,
+      &vec![
+        vec![Value::from(true)],
+        vec![Value::from(false)],
+        vec![Value::from(true)]
+      ],
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Batch execution failed.");
+    let results = results
+      .iter()
+      .map(|val| val.as_ref().expect("Should have a result.").as_bool())
+      .collect::<Vec<_>>();
+    assert_eq!(results, vec![true, false, true]);
+  }
+
+  #[test]
+  fn execute_with_result_cache() {
+    let relative_path = canonicalize("../tests/files/qir/bell_psi_plus.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::default()
+    )));
+    let config = Ptr::from(RasqalConfig::default().with_result_cache(ProjectionCache::new()));
+
+    run_graph(&graph, &Vec::new(), &runtimes, &config).expect("Execution failed.");
+    run_graph(&graph, &Vec::new(), &runtimes, &config).expect("Execution failed.");
+    assert_ne!(config.result_cache.as_ref().unwrap().hits(), 0);
+  }
+
+  #[test]
+  fn execute_array_argument() {
+    let results = run_with_args(
+      "../tests/files/qir/array_argument.ll",
+      &vec![Value::Array(vec![
+        Ptr::from(Value::Pauli(Pauli::X)),
+        Ptr::from(Value::Pauli(Pauli::Z))
+      ])]
+    );
+    assert_eq!(results.expect("Should have a result.").as_int(), 2);
+  }
+
+  #[test]
+  fn parse_in_memory() {
+    let text = read_to_string("../tests/files/qir/bell_psi_plus.ll").unwrap();
+    let graph = parse_text(&text, None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::default()
+    )));
+    run_graph(
+      &graph,
+      &Vec::new(),
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+    assert!(parse_text("not llvm", None).is_err());
+
+    let path = canonicalize("../tests/files/qir/bell_qir_measure.bc").unwrap();
+    let context = Context::create();
+    let from_file = file_to_module(&path, &context).expect("Parsing failed.");
+    let from_buffer = bitcode_to_module(&read(&path).unwrap(), &context).expect("Parsing failed.");
+    let names = |module: &inkwell::module::Module| {
+      module
+        .get_functions()
+        .map(|func| func.get_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&from_file), names(&from_buffer));
+    assert!(parse_bitcode(text.as_bytes(), None).is_err());
+  }
+
+  #[test]
+  fn execute_native_runtime() {
+    let relative_path = canonicalize("../tests/files/qir/bell_int_return.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::new(SimulatorRuntime::default())
+    )));
+
+    let results = run_graph(
+      &graph,
+      &Vec::new(),
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.")
+    .expect("Should have a result.");
+    assert!(matches!(results.as_int(), 0 | 3));
+  }
+
+  #[test]
+  fn run_statistics() {
+    let relative_path = canonicalize("../tests/files/qir/bell_int_return.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+    let runtimes = Ptr::from(runtimes);
+
+    let (_, statistics) = run_graph_with_statistics(
+      &graph,
+      &Vec::new(),
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+    assert!(statistics.steps > 0);
+    assert_eq!(
+      statistics.steps_per_graph.values().sum::<i64>(),
+      statistics.steps
+    );
+    assert_eq!(statistics.evaluation_time, graph.evaluation_time);
+    assert!(statistics.projections_created >= 1);
+    assert_eq!(statistics.projections_concretized, 1);
+    assert_eq!(statistics.circuits_executed, 1);
+    assert_eq!(statistics.solver_attempts, 0);
+
+    let projection = &statistics.projections[0];
+    assert_eq!(projection.width, 2);
+    assert_eq!(projection.gate_counts.get("measure"), Some(&2));
+    assert_eq!(projection.backends, vec![String::from(
+      "rasqal.simulators.mps"
+    )]);
+
+    // Solved circuits never make it to a backend.
+    let config = Ptr::from(RasqalConfig::default().with_activate_solver());
+    let (_, statistics) = run_graph_with_statistics(&graph, &Vec::new(), &runtimes, &config)
+      .expect("Execution failed.");
+    assert_eq!(statistics.solver_attempts, 1);
+    assert_eq!(statistics.solver_hits + statistics.circuits_executed, 1);
+  }
+
+  #[test]
+  fn trace_events() {
+    let relative_path = canonicalize("../tests/files/qir/bell_int_return.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+
+    let mut config = RasqalConfig::default();
+    let receiver = config.trace_events();
+    run_graph(
+      &graph,
+      &Vec::new(),
+      &Ptr::from(runtimes),
+      &Ptr::from(config)
+    )
+    .expect("Execution failed.");
+
+    let events = receiver.try_iter().collect::<Vec<_>>();
+    assert!(matches!(
+      events.first(),
+      Some(TraceEvent::NodeEntered { step: 1, .. })
+    ));
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, TraceEvent::VariableChanged { .. })));
+
+    // Projections are created before anything is added to them, then concretized once.
+    let created = events
+      .iter()
+      .position(|event| matches!(event, TraceEvent::ProjectionCreated { .. }))
+      .expect("Projection should have been created.");
+    let gates = events
+      .iter()
+      .enumerate()
+      .filter_map(|(index, event)| match event {
+        TraceEvent::GateAdded { gate, qubits, .. } => Some((index, gate.as_str(), qubits.clone())),
+        _ => None
+      })
+      .collect::<Vec<_>>();
+    assert!(gates.iter().all(|(index, ..)| *index > created));
+    assert!(gates
+      .iter()
+      .any(|(_, gate, qubits)| *gate == "cx" && *qubits == vec![0, 1]));
+
+    let concretized = events
+      .iter()
+      .filter_map(|event| match event {
+        TraceEvent::ProjectionConcretized {
+          solved, backends, ..
+        } => Some((*solved, backends.clone())),
+        _ => None
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(concretized, vec![(false, vec![String::from(
+      "rasqal.simulators.mps"
+    )])]);
+  }
+
+  #[test]
+  fn execute_pauli_measures() {
+    let path = canonicalize("../tests/files/qir/pauli_measures.ll").unwrap();
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+    let runtimes = Ptr::from(runtimes);
+    let config = Ptr::from(RasqalConfig::default());
+
+    for (entry_point, expected) in [
+      ("Paulis__MeasureX__body", 0),
+      ("Paulis__MeasureY__body", 1),
+      ("Paulis__MeasureXX__body", 1),
+      ("Paulis__MeasureYY__body", 0),
+      ("Paulis__MeasureZZ__body", 1)
+    ] {
+      let results = run_file(&path, &Vec::new(), &runtimes, Some(entry_point), &config)
+        .expect("Execution failed.")
+        .expect("Should have a result.");
+      assert_eq!(
+        results.as_int(),
+        expected,
+        "{entry_point} gave the wrong result."
+      );
+    }
+  }
+
+  /// Third-party style backend which queues circuits and only 'runs' them when waited on, with
+  /// every measure returning one.
+  struct QueuedBackend {
+    submitted: Arc<AtomicUsize>
+  }
+
+  struct QueuedExecution {
+    measures: usize
+  }
+
+  impl PendingExecution for QueuedExecution {
+    fn is_ready(&self) -> bool { false }
+
+    fn wait(self: Box<Self>) -> AnalysisResult {
+      AnalysisResult::new(HashMap::from([("1".repeat(self.measures), 100)]))
+    }
+  }
+
+  impl QuantumBackend for QueuedBackend {
+    fn identity(&self) -> String { String::from("tests.queued") }
+
+    fn create_builder(&self) -> Box<dyn CircuitBuilder> { Box::new(CircuitRecorder::new()) }
+
+    fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+      self.submit(builder, options).wait()
+    }
+
+    fn submit(
+      &self, builder: &dyn CircuitBuilder, _options: &ExecutionOptions
+    ) -> Box<dyn PendingExecution> {
+      self.submitted.fetch_add(1, Ordering::SeqCst);
+      let circuit = builder
+        .downcast::<CircuitRecorder>()
+        .expect("Builder should be ours.");
+      let measures = circuit
+        .operations()
+        .iter()
+        .filter(|op| matches!(op, CircuitOperation::Measure(_)))
+        .count();
+      Box::new(QueuedExecution { measures })
+    }
+  }
+
+  /// Simulator which only has a few qubits, like a small device would.
+  struct SizedBackend {
+    qubits: i32,
+    simulator: SimulatorRuntime
+  }
+
+  impl QuantumBackend for SizedBackend {
+    fn identity(&self) -> String { String::from("tests.sized") }
+
+    fn features(&self) -> QuantumFeatures { QuantumFeatures::new(self.qubits) }
+
+    fn create_builder(&self) -> Box<dyn CircuitBuilder> { self.simulator.create_builder() }
+
+    fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+      self.simulator.execute(builder, options)
+    }
+  }
+
+  /// Simulator which notes whether it was ever called into while another circuit was running.
+  struct SerialBackend {
+    running: Arc<AtomicUsize>,
+    overlapped: Arc<AtomicUsize>,
+    simulator: SimulatorRuntime
+  }
+
+  impl QuantumBackend for SerialBackend {
+    fn identity(&self) -> String { String::from("tests.serial") }
+
+    fn create_builder(&self) -> Box<dyn CircuitBuilder> { self.simulator.create_builder() }
+
+    fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+      if self.running.fetch_add(1, Ordering::SeqCst) > 0 {
+        self.overlapped.fetch_add(1, Ordering::SeqCst);
+      }
+      thread::sleep(Duration::from_millis(20));
+      let result = self.simulator.execute(builder, options);
+      self.running.fetch_sub(1, Ordering::SeqCst);
+      result
+    }
+  }
+
+  #[test]
+  fn qubit_allocation() {
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SizedBackend {
+      qubits: 2,
+      simulator: SimulatorRuntime::default()
+    });
+    let runtimes = Ptr::from(runtimes);
+    let run = |entry_point: &str, allocation: QubitAllocation| {
+      let config = RasqalConfig::default().with_qubit_allocation(allocation);
+      run_entry_point(
+        "../tests/files/qir/qubit_reuse.ll",
+        entry_point,
+        &runtimes,
+        config
+      )
+    };
+
+    // Released qubits get reused, so a two qubit device is enough however many rounds there are.
+    assert_eq!(
+      run("Reuse__Released__body", QubitAllocation::LowestFree),
+      Ok(false)
+    );
+    assert_eq!(
+      run("Reuse__Reset__body", QubitAllocation::LowestFree),
+      Ok(false)
+    );
+
+    // Only qubits the program reset itself get reused, anything else is retired. Circuits are
+    // built on compacted qubits though, so retired indices don't make them any wider.
+    assert_eq!(
+      run("Reuse__Reset__body", QubitAllocation::AfterReset),
+      Ok(false)
+    );
+    assert_eq!(
+      run("Reuse__Released__body", QubitAllocation::AfterReset),
+      Ok(false)
+    );
+
+    // Two program qubits compacted onto the same backend qubit still keep their own results.
+    assert_eq!(
+      run("Reuse__Shared__body", QubitAllocation::AfterReset),
+      Ok(false)
+    );
+
+    assert_eq!(
+      run("Reuse__Released__body", QubitAllocation::FixedCapacity(2)),
+      Ok(false)
+    );
+    let error = run("Reuse__Released__body", QubitAllocation::FixedCapacity(1))
+      .expect_err("Shouldn't be able to allocate a second qubit.");
+    assert!(error.starts_with("Ran out of qubits, all 1 are already in use."));
+  }
+
+  #[test]
+  fn projection_splitting() {
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+    let runtimes = Ptr::from(runtimes);
+    let run = |file: &str, entry_point: Option<&str>, config: RasqalConfig| {
+      let path = canonicalize(format!("../tests/files/qir/{file}")).unwrap();
+      let graph = parse_file(path.to_str().unwrap(), entry_point).expect("Parsing failed.");
+      let (value, statistics) =
+        run_graph_with_statistics(&graph, &Vec::new(), &runtimes, &Ptr::from(config))
+          .expect("Execution failed.");
+      (value.expect("Should have a result.").as_bool(), statistics)
+    };
+
+    // By default every qubit shares the one circuit.
+    let (value, statistics) = run("independent_pairs.ll", None, RasqalConfig::default());
+    assert!(value);
+    assert_eq!(statistics.circuits_executed, 1);
+    assert_eq!(statistics.projections[0].width, 4);
+
+    // Split, each pair only joins up when entangled and runs as its own smaller circuit.
+    let config = RasqalConfig::default().with_projection_splitting();
+    let (value, statistics) = run("independent_pairs.ll", None, config);
+    assert!(value);
+    assert_eq!(statistics.projections_merged, 2);
+    assert_eq!(statistics.projections_concretized, 2);
+    assert_eq!(statistics.circuits_executed, 2);
+    for projection in &statistics.projections {
+      assert_eq!(projection.width, 2);
+      assert_eq!(projection.gate_counts.get("cx"), Some(&1));
+      assert_eq!(projection.gate_counts.get("measure"), Some(&1));
+    }
+
+    // Reset qubits are split back off, so the final measure doesn't drag along every round before
+    // it that used the same qubit.
+    let config = RasqalConfig::default().with_projection_splitting();
+    let (value, statistics) = run("qubit_reuse.ll", Some("Reuse__Reset__body"), config);
+    assert!(!value);
+    assert_eq!(statistics.projections_merged, 4);
+    assert_eq!(statistics.projections_concretized, 1);
+    assert_eq!(statistics.projections[0].width, 1);
+    assert_eq!(statistics.projections[0].gate_counts.get("cx"), None);
+  }
+
+  #[test]
+  fn execute_custom_backend() {
+    let relative_path = canonicalize("../tests/files/qir/bell_int_return.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let submitted = Arc::new(AtomicUsize::new(0));
+    let mut collection = RuntimeCollection::default();
+    collection.register(QueuedBackend {
+      submitted: submitted.clone()
+    });
+
+    let results = run_graph(
+      &graph,
+      &Vec::new(),
+      &Ptr::from(collection),
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.")
+    .expect("Should have a result.");
+    assert_eq!(results.as_int(), 3);
+    assert!(submitted.load(Ordering::SeqCst) > 0);
+  }
+
+  #[test]
+  fn parallel_dispatch_serializes_runtimes() {
+    let path = canonicalize("../tests/files/qir/independent_pairs.ll").unwrap();
+    let graph = parse_file(path.to_str().unwrap(), None).expect("Parsing failed.");
+    let overlapped = Arc::new(AtomicUsize::new(0));
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SerialBackend {
+      running: Arc::new(AtomicUsize::new(0)),
+      overlapped: overlapped.clone(),
+      simulator: SimulatorRuntime::default()
+    });
+
+    // Both pairs are split into their own circuit, but there's only the one runtime to run them.
+    let config = RasqalConfig::default().with_parallel_dispatch();
+    let (value, statistics) = run_graph_with_statistics(
+      &graph,
+      &Vec::new(),
+      &Ptr::from(runtimes),
+      &Ptr::from(config)
+    )
+    .expect("Execution failed.");
+    assert!(value.expect("Should have a result.").as_bool());
+    assert_eq!(statistics.circuits_executed, 2);
+    assert_eq!(overlapped.load(Ordering::SeqCst), 0);
+  }
+
+  #[test]
+  fn execute_linked_modules() {
+    let graph = parse_files(
+      &[
+        canonicalize("../tests/files/qir/linked_entry.ll").unwrap(),
+        canonicalize("../tests/files/qir/linked_library.ll").unwrap()
+      ],
+      None
+    )
+    .expect("Parsing failed.");
+    assert_eq!(graph.signature.name, "Linked__CountPaulis__body");
+
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::default()
+    )));
+    let results = run_graph(
+      &graph,
+      &vec![Value::Array(vec![
+        Ptr::from(Value::Pauli(Pauli::X)),
+        Ptr::from(Value::Pauli(Pauli::Z))
+      ])],
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+    assert_eq!(results.expect("Should have a result.").as_int(), 2);
+
+    let no_files: [&str; 0] = [];
+    assert!(parse_files(&no_files, None).is_err());
+  }
+
+  #[test]
+  fn execute_named_arguments() {
+    let relative_path = canonicalize("../tests/files/qir/array_argument.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::default()
+    )));
+    let config = Ptr::from(RasqalConfig::default());
+    let paulis = Value::Array(vec![Ptr::from(Value::Pauli(Pauli::X))]);
+
+    let results = run_graph_named(
+      &graph,
+      &HashMap::from([(String::from("paulis"), paulis.clone())]),
+      &runtimes,
+      &config
+    )
+    .expect("Execution failed.");
+    assert_eq!(results.expect("Should have a result.").as_int(), 1);
+
+    let missing = run_graph_named(&graph, &HashMap::new(), &runtimes, &config)
+      .expect_err("Missing argument should fail.");
+    assert!(missing.contains("missing paulis: Array"));
+
+    let extra = run_graph_named(
+      &graph,
+      &HashMap::from([
+        (String::from("%paulis"), paulis),
+        (String::from("other"), Value::from(true))
+      ]),
+      &runtimes,
+      &config
+    )
+    .expect_err("Unknown argument should fail.");
+    assert!(extra.contains("unknown other"));
+
+    let mistyped = fail_with_args("../tests/files/qir/array_argument.ll", &vec![Value::from(
+      true
+    )])
+    .expect("Mistyped argument should fail.");
+    assert!(mistyped.contains("expects Array"));
+    assert!(fail_with_args("../tests/files/qir/array_argument.ll", &Vec::new()).is_some());
+  }
+
+  #[test]
+  fn entry_point_signature() {
+    let relative_path = canonicalize("../tests/files/qir/array_argument.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    assert_eq!(graph.signature.name, "Arguments__CountPaulis__body");
+    assert_eq!(graph.signature.arguments, vec![(
+      String::from("%paulis"),
+      ArgumentType::Array
+    )]);
+    assert_eq!(graph.signature.return_type, ArgumentType::Int);
+    assert_eq!(graph.entry_points.len(), 1);
+
+    let relative_path = canonicalize("../tests/files/qir/base_profile_ops.ll").unwrap();
+    let graph = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    assert_eq!(graph.signature.return_type, ArgumentType::Unit);
+    assert_eq!(graph.signature.required_qubits(), Some(2));
+  }
+
+  #[test]
+  fn execute_without_signature() {
+    let relative_path = canonicalize("../tests/files/qir/array_argument.ll").unwrap();
+    let parsed = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(
+      IntegrationRuntime::default()
+    )));
+
+    // Graphs put together by hand have no entry-point, so arguments just go in as they are.
+    let graph = Ptr::from(ExecutableAnalysisGraph::with_context(
+      &parsed.callable_graph,
+      &parsed.context
+    ));
+    assert!(graph.signature.is_empty());
+    let results = run_graph(
+      &graph,
+      &vec![Value::Array(vec![
+        Ptr::from(Value::Pauli(Pauli::X)),
+        Ptr::from(Value::Pauli(Pauli::Z))
+      ])],
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+    assert_eq!(results.expect("Should have a result.").as_int(), 2);
+
+    let error = run_graph(
+      &graph,
+      &Vec::new(),
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect_err("Missing arguments should fail.");
+    assert!(error.starts_with("Root graph requires %paulis arguments to execute."));
+
+    // Without a signature, arguments are bound in the order the parameters were declared.
+    let relative_path = canonicalize("../tests/files/qir/argument_order.ll").unwrap();
+    let parsed = parse_file(relative_path.to_str().unwrap(), None).expect("Parsing failed.");
+    let graph = Ptr::from(ExecutableAnalysisGraph::with_context(
+      &parsed.callable_graph,
+      &parsed.context
+    ));
+    let results = run_graph(
+      &graph,
+      &vec![Value::Int(7), Value::Int(2)],
+      &runtimes,
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.");
+    assert_eq!(results.expect("Should have a result.").as_int(), 5);
+  }
+
   #[test]
   fn execute_qaoa_solver() {
     let config = RasqalConfig::default()
@@ -248,6 +1184,12 @@ mod tests {
     run_with_config("../tests/qsharp/qaoa/qir/qaoa.ll", config);
   }
 
+  #[test]
+  fn execute_qaoa_mps_solver() {
+    let config = RasqalConfig::default().with_mps_solver(16);
+    run_with_config("../tests/qsharp/qaoa/qir/qaoa.ll", config);
+  }
+
   #[test]
   fn execute_qaoa() { run("../tests/qsharp/qaoa/qir/qaoa.ll"); }
 
@@ -278,6 +1220,299 @@ mod tests {
     assert!(results.is_some())
   }
 
+  fn run_loop(entry_point: &str, config: RasqalConfig) -> Result<Option<Ptr<Value>>, String> {
+    let path = canonicalize("../tests/files/qir/loops.ll").unwrap();
+    let runtimes = Ptr::from(RuntimeCollection::default());
+    run_file(
+      &path,
+      &Vec::new(),
+      &runtimes,
+      Some(entry_point),
+      &Ptr::from(config)
+    )
+  }
+
+  #[test]
+  fn detect_infinite_loop() {
+    let error = run_loop("Loops__Spin__body", RasqalConfig::default())
+      .expect_err("Loop should have been detected.");
+    assert!(error.starts_with("Infinite loop detected"));
+
+    let results = run_loop("Loops__Count__body", RasqalConfig::default())
+      .expect("Execution failed.")
+      .expect("Should have a result.");
+    assert_eq!(results.as_int(), 5);
+  }
+
+  #[test]
+  fn execution_time_limit() {
+    let config = RasqalConfig::default().with_time_limit(Duration::from_millis(50));
+    let error = run_loop("Loops__Forever__body", config).expect_err("Should have timed out.");
+    assert_eq!(error, "Execution time limit of 50ms exceeded.");
+  }
+
+  #[test]
+  fn cancel_execution() {
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    let handle = thread::spawn(move || {
+      thread::sleep(Duration::from_millis(50));
+      canceller.cancel();
+    });
+
+    // Time limit is just a fallback so a broken cancellation doesn't hang the tests.
+    let config = RasqalConfig::default()
+      .with_cancellation(&token)
+      .with_time_limit(Duration::from_secs(60));
+    let error = run_loop("Loops__Forever__body", config).expect_err("Should have been cancelled.");
+    handle.join().unwrap();
+    assert_eq!(error, "Execution was cancelled.");
+    assert!(!token.was_interrupted());
+  }
+
+  /// Debugger which records every stop, then does what the action returns for it.
+  fn recording_debugger(
+    action: impl Fn(&DebugStop) -> DebugAction + Send + Sync + 'static
+  ) -> (Debugger, Arc<Mutex<Vec<DebugStop>>>) {
+    let stops = Arc::new(Mutex::new(Vec::new()));
+    let recorded = stops.clone();
+    let debugger = Debugger::new(move |stop| {
+      recorded.lock().unwrap().push(stop.clone());
+      action(stop)
+    });
+    (debugger, stops)
+  }
+
+  #[test]
+  fn debugger_breakpoints() {
+    let (debugger, stops) = recording_debugger(|_| DebugAction::Continue);
+    let debugger = debugger.with_breakpoint(Breakpoint::Label(String::from("body")));
+    let results = run_loop(
+      "Loops__Count__body",
+      RasqalConfig::default().with_debugger(debugger)
+    )
+    .expect("Execution failed.")
+    .expect("Should have a result.");
+    assert_eq!(results.as_int(), 5);
+
+    // Once for every iteration of the loop, with the counter visible at each.
+    let stops = stops.lock().unwrap();
+    assert_eq!(stops.len(), 5);
+    for stop in stops.iter() {
+      assert_eq!(
+        stop.reason,
+        StopReason::Breakpoint(Breakpoint::Label(String::from("body")))
+      );
+      assert_eq!(stop.call_stack, vec![String::from("Loops__Count__body")]);
+      assert_eq!(stop.instruction, "label body");
+    }
+    assert!(stops[4].variables.values().any(|val| val == "4"));
+  }
+
+  #[test]
+  fn debugger_step_and_abort() {
+    let (debugger, stops) = recording_debugger(|stop| {
+      if stop.step < 3 {
+        DebugAction::Step
+      } else {
+        DebugAction::Abort
+      }
+    });
+    let debugger =
+      debugger.with_breakpoint(Breakpoint::Function(String::from("Loops__Forever__body")));
+    let error = run_loop(
+      "Loops__Forever__body",
+      RasqalConfig::default().with_debugger(debugger)
+    )
+    .expect_err("Debugger should have aborted.");
+    assert_eq!(error, "Execution aborted by debugger.");
+
+    let stops = stops.lock().unwrap();
+    assert_eq!(
+      stops.iter().map(|stop| stop.step).collect::<Vec<_>>(),
+      vec![1, 2, 3]
+    );
+    assert!(matches!(stops[0].reason, StopReason::Breakpoint(_)));
+    assert_eq!(stops[1].reason, StopReason::Step);
+  }
+
+  #[test]
+  fn debug_info_origins() {
+    let relative_path = canonicalize("../tests/files/qir/debug_info.ll").unwrap();
+    let path = relative_path.to_str().unwrap();
+    let graph = parse_file(path, Some("Debug__Count__body")).expect("Parsing failed.");
+    let runtimes = Ptr::from(RuntimeCollection::default());
+
+    // Graph dumps show where in the source each node came from.
+    assert!(graph.to_string().contains(" @ Debug.qs:6:13 "));
+
+    let mut config = RasqalConfig::default();
+    let receiver = config.trace_events();
+    run_graph(&graph, &Vec::new(), &runtimes, &Ptr::from(config)).expect("Execution failed.");
+    let origins = receiver
+      .try_iter()
+      .filter_map(|event| match event {
+        TraceEvent::NodeEntered { origin, .. } => origin,
+        _ => None
+      })
+      .collect::<Vec<_>>();
+    assert!(origins.contains(&String::from("Debug.qs:6:13 (Debug__Count__body, body)")));
+
+    // Labels have no location of their own, but still know which function and block they're in.
+    assert!(origins.contains(&String::from("Debug__Count__body, header")));
+
+    let config = Ptr::from(RasqalConfig::default());
+    let error = run_file(
+      path,
+      &Vec::new(),
+      &runtimes,
+      Some("Debug__Fail__body"),
+      &config
+    )
+    .expect_err("Execution should have failed.");
+    assert!(error.starts_with("Out of range."));
+    assert!(error.ends_with(" At Debug.qs:12:9 (Debug__Fail__body, entry)."));
+  }
+
+  #[test]
+  fn result_reductions() {
+    let path = canonicalize("../tests/files/qir/result_reduction.ll").unwrap();
+    let mut runtimes = RuntimeCollection::default();
+    runtimes.register(SimulatorRuntime::default());
+    let runtimes = Ptr::from(runtimes);
+    let run = |entry_point: &str, config: RasqalConfig| {
+      run_entry_point(
+        "../tests/files/qir/result_reduction.ll",
+        entry_point,
+        &runtimes,
+        config
+      )
+    };
+
+    // A fair coin is a tie, which majority treats as zero.
+    assert_eq!(
+      run("Reduction__Coin__body", RasqalConfig::default()),
+      Ok(false)
+    );
+
+    // Reads come back as classical results rather than plain bools.
+    let read = run_file(
+      &path,
+      &Vec::new(),
+      &runtimes,
+      Some("Reduction__Coin__body"),
+      &Ptr::from(RasqalConfig::default())
+    )
+    .expect("Execution failed.")
+    .expect("Should have a result.");
+    assert!(matches!(*read, Value::Result(false)));
+
+    let error = run(
+      "Reduction__Coin__body",
+      RasqalConfig::default().with_result_reduction(ResultReduction::Unanimous)
+    )
+    .expect_err("Coin shouldn't be unanimous.");
+    assert!(error.starts_with("Result isn't unanimous"));
+
+    let error = run(
+      "Reduction__Correct__body",
+      RasqalConfig::default().with_result_reduction(ResultReduction::ErrorOnAmbiguity(0.6))
+    )
+    .expect_err("Coin should be ambiguous.");
+    assert!(error.starts_with("Result is ambiguous"));
+    assert!(error.ends_with(" At Reduction__Correct__body, entry."));
+
+    let error = run("Reduction__Unwritten__body", RasqalConfig::default())
+      .expect_err("Nothing was measured into the result.");
+    assert!(error.starts_with("Result 3 was read before anything was measured into it."));
+
+    // Following single shots flips both ways, but corrections always match what was measured.
+    let per_shot = |seed| {
+      RasqalConfig::default()
+        .with_result_reduction(ResultReduction::PerShot)
+        .with_execution_options(ExecutionOptions::new().with_seed(seed))
+    };
+    let mut flips = Vec::new();
+    for seed in 0..20 {
+      flips.push(run("Reduction__Coin__body", per_shot(seed)).expect("Execution failed."));
+      assert_eq!(run("Reduction__Correct__body", per_shot(seed)), Ok(true));
+    }
+    assert!(flips.contains(&true) && flips.contains(&false));
+    assert_eq!(
+      run("Reduction__Coin__body", per_shot(3)),
+      run("Reduction__Coin__body", per_shot(3))
+    );
+  }
+
+  #[test]
+  fn trajectories() {
+    let path = canonicalize("../tests/files/qir/result_reduction.ll").unwrap();
+    let config = Ptr::from(
+      RasqalConfig::default().with_execution_options(ExecutionOptions::new().with_seed(11))
+    );
+    let histogram = |entry_point: &str| {
+      let graph = parse_file(&path, Some(entry_point)).expect("Unable to parse.");
+      run_graph_trajectories(&graph, &Vec::new(), 40, &config).expect("Execution failed.")
+    };
+
+    // Every shot follows its own coin flip, so both sides come up.
+    let coin = histogram("Reduction__Coin__body");
+    assert_eq!(coin.distribution.len(), 2);
+    assert_eq!(coin.distribution.values().sum::<i64>(), 40);
+    assert!(coin
+      .distribution
+      .get("true")
+      .is_some_and(|count| *count > 0));
+    assert!(coin
+      .distribution
+      .get("false")
+      .is_some_and(|count| *count > 0));
+    assert_eq!(
+      coin.distribution,
+      histogram("Reduction__Coin__body").distribution
+    );
+
+    // Corrections always match the flip they were applied for, unlike when branching on aggregates.
+    let correct = histogram("Reduction__Correct__body");
+    assert_eq!(
+      correct.distribution,
+      HashMap::from([(String::from("true"), 40)])
+    );
+
+    // Each measure of a re-measured qubit is followed on its own, rather than the first pinning
+    // every later one to the same value.
+    let remeasure = histogram("Reduction__Remeasure__body");
+    assert_eq!(
+      remeasure.distribution,
+      HashMap::from([(String::from("false"), 40)])
+    );
+    let repeat = histogram("Reduction__Repeat__body");
+    assert_eq!(
+      repeat.distribution,
+      HashMap::from([(String::from("true"), 40)])
+    );
+
+    // Shots only share circuit results when a cache has been configured.
+    let graph = parse_file(&path, Some("Reduction__Correct__body")).expect("Unable to parse.");
+    let (_, statistics) = run_graph_trajectories_with_statistics(&graph, &Vec::new(), 40, &config)
+      .expect("Execution failed.");
+    assert_eq!(statistics.cache_hits, 0);
+    let cached = Ptr::from(
+      RasqalConfig::default()
+        .with_execution_options(ExecutionOptions::new().with_seed(11))
+        .with_result_cache(ProjectionCache::new())
+    );
+    let (_, statistics) = run_graph_trajectories_with_statistics(&graph, &Vec::new(), 40, &cached)
+      .expect("Execution failed.");
+    assert!(statistics.cache_hits > 0);
+  }
+
+  #[test]
+  fn execute_bell_parallel_dispatch() {
+    let config = RasqalConfig::default().with_parallel_dispatch();
+    run_with_config("../tests/files/qir/unrestricted_bell.ll", config);
+  }
+
   #[test]
   fn execute_bell_int_return() { run(&"../tests/files/qir/bell_int_return.ll"); }
 
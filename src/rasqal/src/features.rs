@@ -6,20 +6,33 @@ use std::fmt::{Display, Formatter};
 /// A feature collection which a QPU needs to have in order to run a particular projection.
 pub struct QuantumFeatures {
   /// Amount of qubits required for this feature.
-  pub qubits: i32
+  pub qubits: i32,
+
+  /// Most controls on any one rotation. -1 for QPUs which have no limit.
+  pub controls: i32
 }
 
 impl QuantumFeatures {
-  pub fn new(qubits: i32) -> QuantumFeatures { QuantumFeatures { qubits } }
+  pub fn new(qubits: i32) -> QuantumFeatures {
+    QuantumFeatures {
+      qubits,
+      controls: -1
+    }
+  }
+
+  pub fn with_controls(mut self, controls: i32) -> QuantumFeatures {
+    self.controls = controls;
+    self
+  }
 }
 
 impl Default for QuantumFeatures {
-  fn default() -> Self { QuantumFeatures { qubits: -1 } }
+  fn default() -> Self { QuantumFeatures::new(-1) }
 }
 
 impl Display for QuantumFeatures {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    f.write_str(format!("Qubits: {}", self.qubits).as_str())
+    f.write_str(format!("Qubits: {}, controls: {}", self.qubits, self.controls).as_str())
   }
 }
 
@@ -13,6 +13,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
 use std::iter::zip;
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 
 /// Walks the graph from its entry-point to its logical conclusion. Will take all pathways exactly
 /// once. Walks a pathway until it finds an intersection/phi node then reverses and takes the path
@@ -176,7 +177,11 @@ pub struct AnalysisGraph {
 
   /// Denotes which node will be automatically attached too
   /// if you add one without explicit edges.
-  pub auto_attach_target: Ptr<Node>
+  pub auto_attach_target: Ptr<Node>,
+
+  /// Origin given to every node added to this graph that doesn't already have one. Set by the
+  /// evaluator as it walks each instruction.
+  pub current_origin: Option<NodeOrigin>
 }
 
 impl AnalysisGraph {
@@ -185,7 +190,8 @@ impl AnalysisGraph {
       identity: id,
       edges: Ptr::from(HashMap::default()),
       nodes: Ptr::from(HashMap::default()),
-      auto_attach_target: Ptr::default()
+      auto_attach_target: Ptr::default(),
+      current_origin: None
     }
   }
 
@@ -320,6 +326,10 @@ impl AnalysisGraph {
         });
       }
 
+      if node.origin.is_none() {
+        with_mutable!(node.origin = self.current_origin.clone());
+      }
+
       with_mutable!(node.linked_graph = Ptr::from(self.borrow_mut()));
       self.nodes.insert(instruction_address, node.clone());
     }
@@ -493,15 +503,22 @@ impl Display for AnalysisGraph {
 pub struct CallableAnalysisGraph {
   pub analysis_graph: Ptr<AnalysisGraph>,
 
-  /// The declared input variables, in order, which demand to be in place by this graph.
+  /// The declared input variables which demand to be in place by this graph.
   /// So if you have a declaration of method(arg1, arg2), and a call of it is method(1, %seven)
   /// it allows you to link arg1 = 1, arg2 = %seven.
-  pub argument_mappings: HashMap<String, Ptr<Value>>
+  pub argument_mappings: HashMap<String, Ptr<Value>>,
+
+  /// Names of the declared parameters in the order they were declared, which is how arguments
+  /// passed in from outside are bound to them.
+  pub parameters: Vec<String>
 }
 
 impl Clone for CallableAnalysisGraph {
   fn clone(&self) -> Self {
-    CallableAnalysisGraph::new_with_args(&self.analysis_graph, self.argument_mappings.clone())
+    let mut callable =
+      CallableAnalysisGraph::new_with_args(&self.analysis_graph, self.argument_mappings.clone());
+    callable.parameters = self.parameters.clone();
+    callable
   }
 }
 
@@ -509,7 +526,8 @@ impl CallableAnalysisGraph {
   pub fn new(graph: &Ptr<AnalysisGraph>) -> CallableAnalysisGraph {
     CallableAnalysisGraph {
       analysis_graph: graph.clone(),
-      argument_mappings: HashMap::new()
+      argument_mappings: HashMap::new(),
+      parameters: Vec::new()
     }
   }
 
@@ -518,9 +536,18 @@ impl CallableAnalysisGraph {
   ) -> CallableAnalysisGraph {
     CallableAnalysisGraph {
       analysis_graph: graph.clone(),
-      argument_mappings
+      argument_mappings,
+      parameters: Vec::new()
     }
   }
+
+  /// Declares the next parameter of this graph, with no value until it's called.
+  pub fn add_parameter(&mut self, name: String) {
+    self
+      .argument_mappings
+      .insert(name.clone(), Ptr::from(Value::Empty));
+    self.parameters.push(name);
+  }
 }
 
 impl Display for CallableAnalysisGraph {
@@ -566,14 +593,30 @@ impl Eq for CallableAnalysisGraph {}
 /// appropriate metadata.
 pub struct ExecutableAnalysisGraph {
   pub callable_graph: Ptr<CallableAnalysisGraph>,
-  pub context: Ptr<RuntimeContext>
+  pub context: Ptr<RuntimeContext>,
+
+  /// Whether pre-execution analysis (node ordering, variable scoping) has been run.
+  pub is_analysed: bool,
+
+  /// Signature of the entry-point this graph was built from.
+  pub signature: EntryPointSignature,
+
+  /// Signatures of every entry-point in the module this graph was built from.
+  pub entry_points: Vec<EntryPointSignature>,
+
+  /// How long it took to evaluate the QIR into this graph.
+  pub evaluation_time: Duration
 }
 
 impl ExecutableAnalysisGraph {
   pub fn new(graph: &Ptr<CallableAnalysisGraph>) -> ExecutableAnalysisGraph {
     ExecutableAnalysisGraph {
       callable_graph: graph.clone(),
-      context: Ptr::from(RuntimeContext::new())
+      context: Ptr::from(RuntimeContext::new()),
+      is_analysed: false,
+      signature: EntryPointSignature::default(),
+      entry_points: Vec::new(),
+      evaluation_time: Duration::ZERO
     }
   }
 
@@ -582,7 +625,11 @@ impl ExecutableAnalysisGraph {
   ) -> ExecutableAnalysisGraph {
     ExecutableAnalysisGraph {
       callable_graph: graph.clone(),
-      context: context.clone()
+      context: context.clone(),
+      is_analysed: false,
+      signature: EntryPointSignature::default(),
+      entry_points: Vec::new(),
+      evaluation_time: Duration::ZERO
     }
   }
 
@@ -625,6 +672,248 @@ impl Display for ExecutableAnalysisGraph {
   }
 }
 
+/// Type of an entry-point argument or return value, inferred from its QIR type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArgumentType {
+  #[default]
+  Unit,
+  Bool,
+  Int,
+  Double,
+  Pauli,
+  Qubit,
+  Result,
+  String,
+  Array,
+  Tuple,
+  Range,
+  Callable,
+
+  /// Anything we don't recognize, holding the LLVM type.
+  Unknown(String)
+}
+
+impl ArgumentType {
+  pub fn from_llvm(llvm_type: &str) -> ArgumentType {
+    match llvm_type.trim() {
+      "void" => ArgumentType::Unit,
+      "i1" => ArgumentType::Bool,
+      "i2" => ArgumentType::Pauli,
+      "i8" | "i16" | "i32" | "i64" => ArgumentType::Int,
+      "float" | "double" => ArgumentType::Double,
+      "%Qubit*" => ArgumentType::Qubit,
+      "%Result*" => ArgumentType::Result,
+      "%String*" => ArgumentType::String,
+      "%Array*" => ArgumentType::Array,
+      "%Tuple*" => ArgumentType::Tuple,
+      "%Range" => ArgumentType::Range,
+      "%Callable*" => ArgumentType::Callable,
+      val => ArgumentType::Unknown(val.to_string())
+    }
+  }
+
+  /// Whether this value can be passed in for an argument of this type. Doubles also accept
+  /// integers, since the runtime treats numbers the same whichever way they came in and callers
+  /// like Python don't distinguish '1' from '1.0' in most places.
+  pub fn accepts(&self, value: &Value) -> bool {
+    match self {
+      ArgumentType::Unit => matches!(value, Value::Empty),
+      ArgumentType::Bool => matches!(value, Value::Bool(_) | Value::Result(_)),
+      ArgumentType::Int => matches!(
+        value,
+        Value::Byte(_) | Value::Short(_) | Value::Int(_) | Value::Long(_)
+      ),
+      ArgumentType::Double => matches!(
+        value,
+        Value::Float(_) | Value::Byte(_) | Value::Short(_) | Value::Int(_) | Value::Long(_)
+      ),
+      ArgumentType::Pauli => matches!(value, Value::Pauli(_)),
+      ArgumentType::Qubit => matches!(value, Value::Qubit(_)),
+      ArgumentType::Result => matches!(
+        value,
+        Value::Bool(_)
+          | Value::Result(_)
+          | Value::Int(_)
+          | Value::Long(_)
+          | Value::AnalysisResult(_)
+      ),
+      ArgumentType::String => matches!(value, Value::String(_)),
+      ArgumentType::Array | ArgumentType::Tuple | ArgumentType::Range => {
+        matches!(value, Value::Array(_))
+      }
+      ArgumentType::Callable => matches!(value, Value::Callable(_)),
+      ArgumentType::Unknown(_) => true
+    }
+  }
+}
+
+impl Display for ArgumentType {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      ArgumentType::Unit => "Unit",
+      ArgumentType::Bool => "Bool",
+      ArgumentType::Int => "Int",
+      ArgumentType::Double => "Double",
+      ArgumentType::Pauli => "Pauli",
+      ArgumentType::Qubit => "Qubit",
+      ArgumentType::Result => "Result",
+      ArgumentType::String => "String",
+      ArgumentType::Array => "Array",
+      ArgumentType::Tuple => "Tuple",
+      ArgumentType::Range => "Range",
+      ArgumentType::Callable => "Callable",
+      ArgumentType::Unknown(val) => val.as_str()
+    })
+  }
+}
+
+/// Name, arguments, return type and attributes of a QIR entry-point.
+#[derive(Clone, Debug, Default)]
+pub struct EntryPointSignature {
+  pub name: String,
+
+  /// Argument names and types in the order they're declared.
+  pub arguments: Vec<(String, ArgumentType)>,
+  pub return_type: ArgumentType,
+
+  /// QIR function attributes, such as 'required_num_qubits'.
+  pub attributes: HashMap<String, String>
+}
+
+/// Older QIR uses different names for the same attributes, so we check both.
+impl EntryPointSignature {
+  /// Whether this is a placeholder for a graph which wasn't built from a QIR entry-point, so we
+  /// have no idea what it expects.
+  pub fn is_empty(&self) -> bool { self.name.is_empty() }
+
+  pub fn required_qubits(&self) -> Option<i64> {
+    self
+      .numeric_attribute("required_num_qubits")
+      .or_else(|| self.numeric_attribute("requiredQubits"))
+  }
+
+  pub fn required_results(&self) -> Option<i64> {
+    self
+      .numeric_attribute("required_num_results")
+      .or_else(|| self.numeric_attribute("requiredResults"))
+  }
+
+  /// Checks that these positional arguments match this signature.
+  pub fn validate(&self, arguments: &Vec<Value>) -> Result<(), String> {
+    if self.arguments.len() != arguments.len() {
+      let mut required = self
+        .arguments
+        .iter()
+        .map(|(name, arg_type)| format!("{name}: {arg_type}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+      if required.is_empty() {
+        required = String::from("no arguments");
+      }
+
+      let mut supplied = arguments
+        .iter()
+        .map(|val| val.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+      if supplied.is_empty() {
+        supplied = String::from("none");
+      }
+
+      return Err(format!(
+        "{} requires {required}. Got given: {supplied}.",
+        self.name
+      ));
+    }
+
+    for ((name, arg_type), value) in zip(&self.arguments, arguments) {
+      if !arg_type.accepts(value) {
+        return Err(format!(
+          "Argument {name} of {} expects {arg_type}, got {value}.",
+          self.name
+        ));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Orders named arguments as the signature declares them, validating them as we go. Names can
+  /// be passed with or without their leading '%'.
+  pub fn order_arguments(&self, named: &HashMap<String, Value>) -> Result<Vec<Value>, String> {
+    let normalize = |name: &str| name.trim_start_matches('%').to_string();
+    let supplied = named
+      .iter()
+      .map(|(key, value)| (normalize(key), value))
+      .collect::<HashMap<_, _>>();
+    let expected = self
+      .arguments
+      .iter()
+      .map(|(name, _)| normalize(name))
+      .collect::<HashSet<_>>();
+
+    let mut missing = self
+      .arguments
+      .iter()
+      .filter(|(name, _)| !supplied.contains_key(&normalize(name)))
+      .map(|(name, arg_type)| format!("{}: {arg_type}", normalize(name)))
+      .collect::<Vec<_>>();
+    let mut extra = supplied
+      .keys()
+      .filter(|key| !expected.contains(*key))
+      .cloned()
+      .collect::<Vec<_>>();
+    extra.sort();
+
+    let mut errors = Vec::new();
+    if !missing.is_empty() {
+      errors.push(format!("missing {}", missing.join(", ")));
+    }
+    if !extra.is_empty() {
+      errors.push(format!("unknown {}", extra.join(", ")));
+    }
+    if !errors.is_empty() {
+      return Err(format!(
+        "Invalid arguments for {}: {}.",
+        self.name,
+        errors.join("; ")
+      ));
+    }
+
+    let ordered = self
+      .arguments
+      .iter()
+      .map(|(name, _)| supplied[&normalize(name)].clone())
+      .collect::<Vec<_>>();
+    self.validate(&ordered)?;
+    Ok(ordered)
+  }
+
+  fn numeric_attribute(&self, name: &str) -> Option<i64> {
+    self
+      .attributes
+      .get(name)
+      .and_then(|val| val.trim().parse().ok())
+  }
+}
+
+impl Display for EntryPointSignature {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}({}) -> {}",
+      self.name,
+      self
+        .arguments
+        .iter()
+        .map(|(name, arg_type)| format!("{name}: {arg_type}"))
+        .collect::<Vec<_>>()
+        .join(", "),
+      self.return_type
+    )
+  }
+}
+
 /// Wrapper for various graphs that allow you to use builder syntax on them.
 /// Uses auto-deref to appropriately wrap the class.
 pub struct AnalysisGraphBuilder {
@@ -916,6 +1205,50 @@ impl Display for Edge {
   }
 }
 
+/// Position in the original source, taken from an instructions `!dbg` metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+  pub file: String,
+  pub line: u32,
+  pub column: u32
+}
+
+impl Display for SourceLocation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}:{}", self.file, self.line, self.column)
+  }
+}
+
+/// Where a node came from in the QIR, and in the source it was compiled from if the compiler
+/// emitted debug information. Nodes the evaluator or analysis synthesizes on their own have none.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeOrigin {
+  /// LLVM function and basic block the instruction is in.
+  pub function: String,
+  pub block: String,
+  pub location: Option<SourceLocation>
+}
+
+impl NodeOrigin {
+  pub fn new(function: &str, block: &str, location: Option<SourceLocation>) -> NodeOrigin {
+    NodeOrigin {
+      function: function.to_string(),
+      block: block.to_string(),
+      location
+    }
+  }
+}
+
+impl Display for NodeOrigin {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if let Some(location) = &self.location {
+      write!(f, "{location} ({}, {})", self.function, self.block)
+    } else {
+      write!(f, "{}, {}", self.function, self.block)
+    }
+  }
+}
+
 pub struct Node {
   /// Pointer to the graph it is currently a part of.
   /// Gets updated when it moves graphs.
@@ -926,7 +1259,10 @@ pub struct Node {
 
   /// Assigned just before execution, states precisely what position the node
   /// in the graph is in relation to its breathren
-  pub order: Option<i64>
+  pub order: Option<i64>,
+
+  /// Where the instruction came from, if known.
+  pub origin: Option<NodeOrigin>
 }
 
 impl Node {
@@ -934,7 +1270,8 @@ impl Node {
     Node {
       linked_graph: Ptr::None,
       instruction: inst.clone(),
-      order: None
+      order: None,
+      origin: None
     }
   }
 
@@ -1086,7 +1423,8 @@ impl Clone for Node {
     Node {
       linked_graph: self.linked_graph.clone(),
       instruction: self.instruction.clone(),
-      order: self.order
+      order: self.order,
+      origin: self.origin.clone()
     }
   }
 }
@@ -1127,8 +1465,16 @@ impl Display for Node {
       inst => inst.to_string()
     };
 
+    // Function and block are already obvious from where the node is, so only show where it came
+    // from in the source.
+    let location = self
+      .origin
+      .as_ref()
+      .and_then(|val| val.location.as_ref())
+      .map_or(String::new(), |val| format!(" @ {val}"));
     f.write_str(
-      format!("({incoming}) -> ({node_id}) {stringified_instruction} -> ({out})").as_str()
+      format!("({incoming}) -> ({node_id}) {stringified_instruction}{location} -> ({out})")
+        .as_str()
     )
   }
 }
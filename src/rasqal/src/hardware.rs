@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: BSD-3-Clause
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 
@@ -31,3 +32,131 @@ impl Eq for Qubit {}
 impl Hash for Qubit {
   fn hash<H: Hasher>(&self, state: &mut H) { state.write_i64(self.index) }
 }
+
+/// How qubits a program releases get handed back out when it allocates more.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum QubitAllocation {
+  /// The lowest index that isn't live, whether or not the qubit behind it was reset.
+  #[default]
+  LowestFree,
+
+  /// Like lowest-free, but released qubits are only reused if their circuit shows them reset
+  /// since they were last used. Anything else is retired for the rest of the run.
+  AfterReset,
+
+  /// Lowest-free with at most this many qubits live at once, failing execution when a program
+  /// tries to allocate more.
+  FixedCapacity(usize)
+}
+
+impl QubitAllocation {
+  pub fn from_name(name: &str, capacity: Option<usize>) -> Option<QubitAllocation> {
+    match name.to_lowercase().as_str() {
+      "lowest-free" | "lowest_free" => Some(QubitAllocation::LowestFree),
+      "after-reset" | "after_reset" => Some(QubitAllocation::AfterReset),
+      "fixed" | "fixed-capacity" | "fixed_capacity" => capacity.map(QubitAllocation::FixedCapacity),
+      _ => None
+    }
+  }
+}
+
+impl Display for QubitAllocation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      QubitAllocation::LowestFree => f.write_str("lowest-free"),
+      QubitAllocation::AfterReset => f.write_str("after-reset"),
+      QubitAllocation::FixedCapacity(capacity) => write!(f, "fixed-capacity ({capacity})")
+    }
+  }
+}
+
+/// Picks indices for the qubits a program allocates, following a [`QubitAllocation`].
+#[derive(Default)]
+pub struct QubitAllocator {
+  strategy: QubitAllocation,
+
+  /// Released qubits which can't be handed out again.
+  retired: HashSet<i64>
+}
+
+impl QubitAllocator {
+  pub fn new(strategy: QubitAllocation) -> QubitAllocator {
+    QubitAllocator {
+      strategy,
+      retired: HashSet::new()
+    }
+  }
+
+  /// Index for a newly-allocated qubit when these ones are live. Panics if there's no capacity
+  /// left.
+  pub fn allocate<T>(&mut self, live: &HashMap<i64, T>) -> i64 {
+    if let QubitAllocation::FixedCapacity(capacity) = self.strategy {
+      if live.len() >= capacity {
+        panic!("Ran out of qubits, all {capacity} are already in use.");
+      }
+    }
+
+    let mut index: i64 = 0;
+    while live.contains_key(&index) || self.retired.contains(&index) {
+      index += 1;
+    }
+    index
+  }
+
+  /// Records a qubit being released, along with whether it's known to be reset.
+  pub fn release(&mut self, index: i64, is_reset: bool) {
+    if self.strategy == QubitAllocation::AfterReset && !is_reset {
+      self.retired.insert(index);
+    }
+  }
+
+  /// Whether qubits need to be checked for resets before they can be released.
+  pub fn verifies_resets(&self) -> bool { self.strategy == QubitAllocation::AfterReset }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::exceptions::catch_panics;
+  use crate::hardware::{QubitAllocation, QubitAllocator};
+  use std::collections::HashMap;
+
+  #[test]
+  fn allocation_strategies() {
+    let mut live = HashMap::new();
+    let mut allocator = QubitAllocator::new(QubitAllocation::LowestFree);
+    for _ in 0..3 {
+      let index = allocator.allocate(&live);
+      live.insert(index, ());
+    }
+    live.remove(&1);
+    allocator.release(1, false);
+    assert_eq!(allocator.allocate(&live), 1);
+
+    let mut allocator = QubitAllocator::new(QubitAllocation::AfterReset);
+    live = HashMap::from([(0, ()), (1, ())]);
+    live.remove(&0);
+    allocator.release(0, false);
+    live.remove(&1);
+    allocator.release(1, true);
+    assert_eq!(allocator.allocate(&live), 1);
+    live.insert(1, ());
+    assert_eq!(allocator.allocate(&live), 2);
+
+    let mut allocator = QubitAllocator::new(QubitAllocation::FixedCapacity(2));
+    live = HashMap::from([(0, ()), (1, ())]);
+    live.remove(&0);
+    assert_eq!(allocator.allocate(&live), 0);
+    live.insert(0, ());
+    assert_eq!(
+      catch_panics(|| Ok(allocator.allocate(&live))),
+      Err(String::from("Ran out of qubits, all 2 are already in use."))
+    );
+
+    assert_eq!(
+      QubitAllocation::from_name("fixed", Some(8)),
+      Some(QubitAllocation::FixedCapacity(8))
+    );
+    assert_eq!(QubitAllocation::from_name("fixed", None), None);
+    assert_eq!(QubitAllocation::from_name("random", None), None);
+  }
+}
@@ -281,7 +281,11 @@ pub enum Expression {
   /// Allows dynamically injecting arguments into a callable.
   ArgInjection(Value, Option<Value>),
 
-  MakeCtrlAdj(Value, LambdaModifier)
+  MakeCtrlAdj(Value, LambdaModifier),
+
+  /// Reads a measurement result as a classical [`Value::Result`]. Takes either the result itself
+  /// or the address of a result in the classical register.
+  ReadResult(Value)
 }
 
 impl Display for Expression {
@@ -297,6 +301,7 @@ impl Display for Expression {
           val.as_ref().map_or(String::new(), |val| val.to_string()),
           graph
         ),
+        Expression::ReadResult(value) => format!("read result {value}"),
         Expression::MakeCtrlAdj(val, modifier) => {
           format!("Swapping {} to {}", val, match modifier {
             LambdaModifier::Ctl => "ctrl",
@@ -328,6 +333,18 @@ impl Pauli {
       _ => panic!("Not a valid int for pauli: {index}.")
     }
   }
+
+  /// Maps QIR's encoding of paulis, where I=0, X=1, Z=2 and Y=3. These are usually stored as an i2
+  /// so also show up signed, as Z=-2 and Y=-1.
+  pub fn from_qir(value: i64) -> Pauli {
+    match value {
+      0 => Pauli::I,
+      1 => Pauli::X,
+      2 | -2 => Pauli::Z,
+      3 | -1 => Pauli::Y,
+      _ => panic!("Not a valid QIR pauli: {value}.")
+    }
+  }
 }
 
 impl Display for Pauli {
@@ -364,6 +381,12 @@ pub enum Value {
   /// List of qubits this promise needs, the axis it wants to measure on and the projection
   /// the result should be got from.
   QuantumPromise(Vec<Qubit>, Ptr<QuantumProjection>),
+
+  /// Classical outcome of a measurement, true if it was one. Promises get reduced into these when
+  /// read, following the configured
+  /// [`ResultReduction`](crate::analysis::projections::ResultReduction).
+  Result(bool),
+
   AnalysisResult(Ptr<AnalysisResult>),
 
   /// First value is the in-line variable the value is referencing, the second is additional
@@ -384,6 +407,7 @@ impl Clone for Value {
       Value::Int(val) => Value::Int(*val),
       Value::Long(val) => Value::Long(*val),
       Value::Bool(val) => Value::Bool(*val),
+      Value::Result(val) => Value::Result(*val),
       Value::Float(val) => Value::Float(*val),
       Value::String(val) => Value::String(val.clone()),
       Value::Pauli(val) => Value::Pauli(val.clone()),
@@ -405,14 +429,14 @@ impl Value {
   /// Attempts to coerce this value into an int. Returns None if it can't.
   pub fn try_as_int(&self) -> Option<i64> {
     match self {
-      Value::Bool(b) => Some(i64::from(*b)),
+      Value::Bool(b) | Value::Result(b) => Some(i64::from(*b)),
       Value::Byte(b) => Some(*b as i64),
       Value::Short(s) => Some(*s as i64),
       Value::Int(i) => Some(*i),
       Value::Long(l) => Some(*l as i64),
       Value::Float(f) => Some(*f as i64),
       Value::QuantumPromise(qbs, projection) => {
-        Some(if with_mutable!(projection.results_for(qbs).is_one()) {
+        Some(if with_mutable!(projection.is_one_for(qbs)) {
           1
         } else {
           0
@@ -432,14 +456,14 @@ impl Value {
   /// Attempts to coerce this value into a byte. Returns None if it can't.
   pub fn try_as_byte(&self) -> Option<i8> {
     match self {
-      Value::Bool(b) => Some(i8::from(b.clone())),
+      Value::Bool(b) | Value::Result(b) => Some(i8::from(b.clone())),
       Value::Byte(b) => Some(*b),
       Value::Short(s) => Some(*s as i8),
       Value::Int(i) => Some(*i as i8),
       Value::Long(l) => Some(*l as i8),
       Value::Float(f) => Some(*f as i8),
       Value::QuantumPromise(qbs, projection) => {
-        Some(with_mutable!(projection.results_for(qbs).is_one()) as i8)
+        Some(with_mutable!(projection.is_one_for(qbs)) as i8)
       }
       _ => None
     }
@@ -455,14 +479,14 @@ impl Value {
   /// Attempts to coerce this value into a short. Returns None if it can't.
   pub fn try_as_short(&self) -> Option<i16> {
     match self {
-      Value::Bool(b) => Some(if *b { 1 } else { 0 }),
+      Value::Bool(b) | Value::Result(b) => Some(if *b { 1 } else { 0 }),
       Value::Byte(b) => Some(*b as i16),
       Value::Short(s) => Some(*s),
       Value::Int(i) => Some(*i as i16),
       Value::Long(l) => Some(*l as i16),
       Value::Float(f) => Some(*f as i16),
       Value::QuantumPromise(qbs, projection) => {
-        Some(if with_mutable!(projection.results_for(qbs).is_one()) {
+        Some(if with_mutable!(projection.is_one_for(qbs)) {
           1
         } else {
           0
@@ -482,14 +506,14 @@ impl Value {
   /// Attempts to coerce this value into a long. Returns None if it can't.
   pub fn try_as_long(&self) -> Option<i128> {
     match self {
-      Value::Bool(b) => Some(i128::from(*b)),
+      Value::Bool(b) | Value::Result(b) => Some(i128::from(*b)),
       Value::Byte(b) => Some(*b as i128),
       Value::Short(s) => Some(*s as i128),
       Value::Int(i) => Some(*i as i128),
       Value::Long(l) => Some(*l),
       Value::Float(f) => Some(*f as i128),
       Value::QuantumPromise(qbs, projection) => {
-        Some(if with_mutable!(projection.results_for(qbs).is_one()) {
+        Some(if with_mutable!(projection.is_one_for(qbs)) {
           1
         } else {
           0
@@ -509,17 +533,17 @@ impl Value {
   /// Attempts to coerce this value into a float. Returns None if it can't.
   pub fn try_as_float(&self) -> Option<f64> {
     match self {
-      Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+      Value::Bool(b) | Value::Result(b) => Some(if *b { 1.0 } else { 0.0 }),
       Value::Byte(b) => Some(*b as f64),
       Value::Short(s) => Some(*s as f64),
       Value::Int(i) => Some(*i as f64),
       Value::Long(l) => Some(*l as f64),
       Value::Float(f) => Some(*f),
       Value::QuantumPromise(qbs, projection) => {
-        Some(if with_mutable!(projection.results_for(qbs).is_one()) {
+        Some(if with_mutable!(projection.is_one_for(qbs)) {
           1.0
         } else {
-          1.0
+          0.0
         })
       }
       _ => None
@@ -583,7 +607,7 @@ impl Value {
 
   /// Attempts to coerce this value into a bool. Returns None if it can't.
   pub fn try_as_bool(&self) -> Option<bool> {
-    if let Value::Bool(val) = self {
+    if let Value::Bool(val) | Value::Result(val) = self {
       return Some(*val);
     }
 
@@ -691,7 +715,9 @@ impl PartialEq<Self> for Value {
       Value::Short(s) => other.try_as_short().map_or(false, |other_s| *s == other_s),
       Value::Int(i) => other.try_as_int().map_or(false, |other_i| *i == other_i),
       Value::Long(l) => other.try_as_long().map_or(false, |other_l| *l == other_l),
-      Value::Bool(b) => other.try_as_bool().map_or(false, |other_b| *b == other_b),
+      Value::Bool(b) | Value::Result(b) => {
+        other.try_as_bool().map_or(false, |other_b| *b == other_b)
+      }
       Value::Float(f) => other.try_as_float().map_or(false, |other_f| *f == other_f),
       Value::String(s) => other.try_as_string().map_or(false, |other_s| *s == other_s),
       Value::Pauli(p) => other.try_as_pauli().map_or(false, |other_p| *p == other_p),
@@ -762,7 +788,7 @@ impl PartialOrd for Value {
       Value::Int(i) => i.partial_cmp(&other.as_int()),
       Value::Long(l) => l.partial_cmp(&other.as_long()),
       Value::Float(f) => f.partial_cmp(&other.as_float()),
-      Value::Bool(b) => b.partial_cmp(&other.as_bool()),
+      Value::Bool(b) | Value::Result(b) => b.partial_cmp(&other.as_bool()),
       Value::String(str_) => str_.partial_cmp(&other.as_string()),
       _ => None
     }
@@ -777,7 +803,7 @@ fn value_bitand(lhs: &Value, rhs: &Value) -> Value {
     Value::Short(s) => Value::from(s & rhs.as_short()),
     Value::Int(i) => Value::from(i & rhs.as_int()),
     Value::Long(l) => Value::from(l & rhs.as_long()),
-    Value::Bool(b) => Value::from(b & rhs.as_bool()),
+    Value::Bool(b) | Value::Result(b) => Value::from(b & rhs.as_bool()),
     _ => panic!("Attempted | on {lhs} and {rhs} which is illegal.")
   }
 }
@@ -803,7 +829,7 @@ fn value_bitor(lhs: &Value, rhs: &Value) -> Value {
     Value::Short(s) => Value::from(s | rhs.as_short()),
     Value::Int(i) => Value::from(i | rhs.as_int()),
     Value::Long(l) => Value::from(l | rhs.as_long()),
-    Value::Bool(b) => Value::from(b | rhs.as_bool()),
+    Value::Bool(b) | Value::Result(b) => Value::from(b | rhs.as_bool()),
     _ => panic!("Attempted | on {lhs} and {rhs} which is illegal.")
   }
 }
@@ -829,7 +855,7 @@ fn value_bitxor(lhs: &Value, rhs: &Value) -> Value {
     Value::Short(s) => Value::from(s ^ rhs.as_short()),
     Value::Int(i) => Value::from(i ^ rhs.as_int()),
     Value::Long(l) => Value::from(l ^ rhs.as_long()),
-    Value::Bool(b) => Value::from(b ^ rhs.as_bool()),
+    Value::Bool(b) | Value::Result(b) => Value::from(b ^ rhs.as_bool()),
     _ => panic!("Attempted ^ on {lhs} and {rhs} which is illegal.")
   }
 }
@@ -878,7 +904,7 @@ impl ops::Sub for &mut Value {
 fn value_add(lhs: &Value, rhs: &Value) -> Value {
   fn larger_type(val: &Value) -> Option<i64> {
     match val {
-      Value::Bool(_) => Some(1),
+      Value::Bool(_) | Value::Result(_) => Some(1),
       Value::Byte(_) => Some(2),
       Value::Short(_) => Some(3),
       Value::Int(_) => Some(4),
@@ -1020,7 +1046,7 @@ impl Display for Value {
         Value::Short(s) => s.to_string(),
         Value::Int(i) => i.to_string(),
         Value::Long(l) => l.to_string(),
-        Value::Bool(b) => b.to_string(),
+        Value::Bool(b) | Value::Result(b) => b.to_string(),
         Value::Float(f) => f.to_string(),
         Value::String(s) => format!("\"{}\"", s.clone()),
         Value::Qubit(qb) => qb.to_string(),
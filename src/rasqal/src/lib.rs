@@ -25,18 +25,54 @@ use std::path::Path;
 use std::env;
 
 mod analysis;
+mod base_profile;
 mod builders;
 mod config;
+mod debugger;
 mod evaluator;
+mod events;
 mod exceptions;
 mod execution;
 mod features;
 mod graphs;
 mod hardware;
 mod instructions;
+#[cfg(feature = "python")]
 mod python;
+mod qasm;
 mod runtime;
+mod simulators;
 mod smart_pointers;
+mod statistics;
+
+// Public API for embedding Rasqal directly from Rust, without going through Python.
+pub use analysis::projections::{AnalysisResult, ResultReduction};
+pub use analysis::solver::SolverStrategy;
+pub use base_profile::{to_base_profile, BaseProfileModule, BaseProfileRuntime};
+pub use builders::{
+  CircuitBuilder, CircuitOperation, CircuitRecorder, CompletedExecution, EmptyBackend,
+  IntegrationRuntime, PendingExecution, QuantumBackend
+};
+pub use config::{CancellationToken, ExecutionOptions, RasqalConfig};
+pub use debugger::{
+  Breakpoint, DebugAction, DebugHandler, DebugStop, Debugger, PendingProjection, StopReason
+};
+pub use events::{EventBus, TraceEvent, TraceField, TraceSubscriber};
+pub use execution::{
+  parse_bitcode, parse_file, parse_files, parse_text, run_file, run_graph, run_graph_batch,
+  run_graph_batch_with_statistics, run_graph_named, run_graph_trajectories,
+  run_graph_trajectories_with_statistics, run_graph_with_statistics, RuntimeCollection
+};
+pub use features::QuantumFeatures;
+pub use graphs::{
+  ArgumentType, EntryPointSignature, ExecutableAnalysisGraph, NodeOrigin, SourceLocation
+};
+pub use hardware::{Qubit, QubitAllocation};
+pub use instructions::{Pauli, Value};
+pub use qasm::{to_qasm, ExportRuntime, ExportedCircuit, QasmVersion};
+pub use simulators::SimulatorRuntime;
+pub use smart_pointers::Ptr;
+pub use statistics::{ProjectionStatistics, RunStatistics};
 
 const DEFAULT_LOG_FILE: &str = "rasqal_logs.txt";
 
@@ -0,0 +1,339 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+//! Standalone runner for QIR files. Parses, runs and prints the results as JSON, with no need for
+//! a Python interpreter.
+
+use rasqal::{
+  parse_files, run_graph, run_graph_named, run_graph_trajectories, with_mutable, ExecutionOptions,
+  IntegrationRuntime, Pauli, Ptr, QubitAllocation, RasqalConfig, ResultReduction,
+  RuntimeCollection, SimulatorRuntime, SolverStrategy, Value
+};
+use std::collections::HashMap;
+use std::env;
+use std::process::exit;
+
+const USAGE: &str = "Usage: rasqal <file.ll|file.bc>... [options] [arguments...]
+
+Runs the QIR files (linked together if there's more than one) and prints the result as JSON.
+Arguments are passed positionally to the entry-point, or by name with --arg.
+
+Arguments:
+  true/false, integers, floats, PauliI/PauliX/PauliY/PauliZ, [comma,separated,arrays] and strings.
+
+Options:
+  --entry-point <name>        Function to run if there's more than one entry-point.
+  --backend <name>            mps (default), entanglement or empty.
+  --arg <name>=<value>        Pass an argument by name.
+  --shots <count>             Shots each circuit is run with.
+  --seed <seed>               Seed passed to the backend and used to pick per-shot results.
+  --trace <area>              Trace runtime, projections, graphs or solver. Can be repeated.
+  --solver                    Activate the solver.
+  --solver-strategy <name>    Solver to use when active, entanglement or mps.
+  --max-bond-dimension <n>    Bond-dimension cap for the MPS solver and backend.
+  --step-limit <count>        Limit on how many steps the symbolic executor can take.
+  --parallel                  Dispatch independent circuits concurrently.
+  --split-projections         Only group qubits into one circuit once gates entangle them.
+  --reduction <name>          How branched-on results are reduced, majority (default), unanimous,
+                              per-shot or error-on-ambiguity.
+  --qubit-allocation <name>   How released qubits are reused, lowest-free (default) or
+                              after-reset.
+  --qubit-capacity <count>    Reuse the lowest free qubit, failing if more are live at once.
+  --trajectories <shots>      Run once per shot on the built-in simulator, following a single
+                              result through every branch, and print how often each came up.
+  -h, --help                  Print this message.";
+
+struct CommandLine {
+  files: Vec<String>,
+  entry_point: Option<String>,
+  backend: String,
+  arguments: Vec<Value>,
+  named_arguments: HashMap<String, Value>,
+  options: ExecutionOptions,
+  trajectories: Option<usize>,
+  config: RasqalConfig
+}
+
+impl CommandLine {
+  fn parse(args: Vec<String>) -> Result<CommandLine, String> {
+    let mut command = CommandLine {
+      files: Vec::new(),
+      entry_point: None,
+      backend: String::from("mps"),
+      arguments: Vec::new(),
+      named_arguments: HashMap::new(),
+      options: ExecutionOptions::new(),
+      trajectories: None,
+      config: RasqalConfig::default()
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+      let mut next = |flag: &str| {
+        args
+          .next()
+          .ok_or_else(|| format!("{flag} expects a value."))
+      };
+      match arg.as_str() {
+        "-h" | "--help" => {
+          println!("{USAGE}");
+          exit(0);
+        }
+        "--entry-point" => command.entry_point = Some(next(&arg)?),
+        "--backend" => command.backend = next(&arg)?,
+        "--arg" => {
+          let value = next(&arg)?;
+          let (name, value) = value
+            .split_once('=')
+            .ok_or_else(|| format!("--arg expects <name>=<value>, got {value}."))?;
+          command
+            .named_arguments
+            .insert(name.to_string(), parse_value(value));
+        }
+        "--shots" => command.options.shots = Some(parse_number(&arg, &next(&arg)?)?),
+        "--seed" => command.options.seed = Some(parse_number(&arg, &next(&arg)?)?),
+        "--trace" => match next(&arg)?.as_str() {
+          "runtime" => command.config.trace_runtime(),
+          "projections" => command.config.trace_projections(),
+          "graphs" => command.config.trace_graphs(),
+          "solver" => command.config.trace_solver(),
+          other => return Err(format!("Unknown trace area {other}."))
+        },
+        "--solver" => command.config.activate_solver(),
+        "--solver-strategy" => {
+          let name = next(&arg)?;
+          let strategy = SolverStrategy::from_name(&name)
+            .ok_or_else(|| format!("Unknown solver strategy {name}."))?;
+          command.config.solver_strategy(strategy);
+        }
+        "--max-bond-dimension" => {
+          let max_bond_dimension = parse_number(&arg, &next(&arg)?)?;
+          command
+            .config
+            .max_bond_dimension(max_bond_dimension as usize);
+        }
+        "--step-limit" => command
+          .config
+          .step_count_limit(parse_number(&arg, &next(&arg)?)?),
+        "--parallel" => command.config.parallel_dispatch(),
+        "--split-projections" => command.config.projection_splitting(),
+        "--reduction" => {
+          let name = next(&arg)?;
+          let reduction = ResultReduction::from_name(&name, None)
+            .ok_or_else(|| format!("Unknown result reduction {name}."))?;
+          command.config.result_reduction(reduction);
+        }
+        "--qubit-allocation" => {
+          let name = next(&arg)?;
+          let allocation = QubitAllocation::from_name(&name, None)
+            .ok_or_else(|| format!("Unknown qubit allocation {name}."))?;
+          command.config.qubit_allocation(allocation);
+        }
+        "--qubit-capacity" => {
+          let capacity = parse_number(&arg, &next(&arg)?)?;
+          command
+            .config
+            .qubit_allocation(QubitAllocation::FixedCapacity(capacity as usize));
+        }
+        "--trajectories" => {
+          command.trajectories = Some(parse_number(&arg, &next(&arg)?)? as usize);
+        }
+        flag if flag.starts_with("--") => return Err(format!("Unknown option {flag}.")),
+        file
+          if command.arguments.is_empty() && (file.ends_with(".ll") || file.ends_with(".bc")) =>
+        {
+          command.files.push(file.to_string());
+        }
+        value => command.arguments.push(parse_value(value))
+      }
+    }
+
+    if command.files.is_empty() {
+      return Err(String::from("No .ll or .bc files to run."));
+    }
+
+    if !command.arguments.is_empty() && !command.named_arguments.is_empty() {
+      return Err(String::from(
+        "Arguments can't be passed both positionally and by name."
+      ));
+    }
+
+    command.config.execution_options(command.options.clone());
+    Ok(command)
+  }
+
+  fn runtime(&self) -> Result<IntegrationRuntime, String> {
+    let max_bond_dimension = self.config.max_bond_dimension;
+    let simulator = |strategy: SolverStrategy| {
+      IntegrationRuntime::new(
+        SimulatorRuntime::new(strategy).with_max_bond_dimension(max_bond_dimension)
+      )
+    };
+
+    match self.backend.as_str() {
+      "empty" => Ok(IntegrationRuntime::default()),
+      name => SolverStrategy::from_name(name)
+        .map(simulator)
+        .ok_or_else(|| format!("Unknown backend {name}."))
+    }
+  }
+
+  fn run(self) -> Result<Option<Ptr<Value>>, String> {
+    let runtimes = Ptr::from(RuntimeCollection::from(&Ptr::from(self.runtime()?)));
+    let graph = parse_files(&self.files, self.entry_point.as_deref())?;
+    let config = Ptr::from(self.config);
+    if let Some(shots) = self.trajectories {
+      let arguments = if self.named_arguments.is_empty() {
+        self.arguments
+      } else {
+        graph.signature.order_arguments(&self.named_arguments)?
+      };
+      let histogram = run_graph_trajectories(&graph, &arguments, shots, &config)?;
+      return Ok(Some(Ptr::from(Value::AnalysisResult(Ptr::from(histogram)))));
+    }
+
+    if self.named_arguments.is_empty() {
+      run_graph(&graph, &self.arguments, &runtimes, &config)
+    } else {
+      run_graph_named(&graph, &self.named_arguments, &runtimes, &config)
+    }
+  }
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<i64, String> {
+  value
+    .parse()
+    .map_err(|_| format!("{flag} expects a number, got {value}."))
+}
+
+/// Parses a command-line argument into a value, falling back to a string.
+fn parse_value(text: &str) -> Value {
+  let text = text.trim();
+  if let Some(inner) = text.strip_prefix('[').and_then(|val| val.strip_suffix(']')) {
+    return Value::Array(
+      split_elements(inner)
+        .iter()
+        .map(|val| Ptr::from(parse_value(val)))
+        .collect()
+    );
+  }
+
+  match text {
+    "true" => Value::Bool(true),
+    "false" => Value::Bool(false),
+    "PauliI" => Value::Pauli(Pauli::I),
+    "PauliX" => Value::Pauli(Pauli::X),
+    "PauliY" => Value::Pauli(Pauli::Y),
+    "PauliZ" => Value::Pauli(Pauli::Z),
+    _ => {
+      if let Ok(val) = text.parse::<i128>() {
+        Value::Long(val)
+      } else if let Ok(val) = text.parse::<f64>() {
+        Value::Float(val)
+      } else {
+        Value::String(text.trim_matches('"').to_string())
+      }
+    }
+  }
+}
+
+/// Splits the contents of an array on its top-level commas.
+fn split_elements(text: &str) -> Vec<String> {
+  let mut elements = Vec::new();
+  let mut depth = 0;
+  let mut current = String::new();
+  for char in text.chars() {
+    match char {
+      '[' => depth += 1,
+      ']' => depth -= 1,
+      ',' if depth == 0 => {
+        elements.push(current.clone());
+        current.clear();
+        continue;
+      }
+      _ => {}
+    }
+    current.push(char);
+  }
+
+  if !current.trim().is_empty() {
+    elements.push(current);
+  }
+  elements
+}
+
+fn to_json(value: &Value) -> Result<String, String> {
+  let distribution = |distribution: &HashMap<String, i64>| {
+    let mut entries = distribution.iter().collect::<Vec<_>>();
+    entries.sort();
+    format!(
+      "{{{}}}",
+      entries
+        .iter()
+        .map(|(key, count)| format!("{}: {count}", json_string(key)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    )
+  };
+
+  Ok(match value {
+    Value::Empty => String::from("null"),
+    Value::Byte(val) => val.to_string(),
+    Value::Short(val) => val.to_string(),
+    Value::Int(val) => val.to_string(),
+    Value::Long(val) => val.to_string(),
+    Value::Bool(val) | Value::Result(val) => val.to_string(),
+    Value::Float(val) if val.is_finite() => val.to_string(),
+    Value::Float(_) => String::from("null"),
+    Value::String(val) => json_string(val),
+    Value::Pauli(val) => json_string(&val.to_string()),
+    Value::Qubit(val) => val.index.to_string(),
+    Value::AnalysisResult(val) => distribution(&val.distribution),
+    Value::QuantumPromise(qbs, projection) => {
+      distribution(&with_mutable!(projection.results_for(qbs)).distribution)
+    }
+    Value::Array(values) => format!(
+      "[{}]",
+      values
+        .iter()
+        .map(|val| to_json(val))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", ")
+    ),
+    Value::Ref(name, _) => return Err(format!("Unresolved reference {name} can't be returned.")),
+    Value::Callable(_) => return Err(String::from("Callables can't be returned."))
+  })
+}
+
+fn json_string(text: &str) -> String {
+  let mut escaped = String::from("\"");
+  for char in text.chars() {
+    match char {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      char if char.is_control() => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+      char => escaped.push(char)
+    }
+  }
+  escaped.push('"');
+  escaped
+}
+
+fn main() {
+  let results = CommandLine::parse(env::args().skip(1).collect()).and_then(|command| {
+    command
+      .run()?
+      .map_or(Ok(String::from("null")), |value| to_json(&value))
+  });
+
+  match results {
+    Ok(json) => println!("{{\"result\": {json}}}"),
+    Err(message) => {
+      eprintln!("{message}");
+      exit(1);
+    }
+  }
+}
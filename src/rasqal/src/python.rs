@@ -1,24 +1,45 @@
 // SPDX-License-Identifier: BSD-3-Clause
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
+use crate::analysis::cache::ProjectionCache;
+use crate::analysis::projections::{AnalysisResult, ResultReduction};
+use crate::analysis::solver::SolverStrategy;
 use crate::builders::{IntegrationRuntime, PythonRuntime};
-use crate::config::RasqalConfig;
-use crate::execution::{parse_file, run_file, run_graph, RuntimeCollection};
+use crate::config::{ExecutionOptions, RasqalConfig};
+use crate::debugger::{Breakpoint, DebugAction, DebugStop, Debugger, StopReason};
+use crate::events::{TraceEvent, TraceField};
+use crate::execution::{
+  parse_bitcode, parse_file, parse_files, parse_text, run_graph_batch_with_statistics,
+  run_graph_trajectories_with_statistics, run_graph_with_statistics, RuntimeCollection
+};
 use crate::features::QuantumFeatures;
-use crate::graphs::ExecutableAnalysisGraph;
-use crate::instructions::Value;
+use crate::graphs::{EntryPointSignature, ExecutableAnalysisGraph};
+use crate::hardware::{Qubit, QubitAllocation};
+use crate::instructions::{Pauli, Value};
 use crate::smart_pointers::Ptr;
-use crate::{initialize_loggers, DEFAULT_LOG_FILE, DEFAULT_LOG_FOLDER};
+use crate::statistics::{ProjectionStatistics, RunStatistics};
+use crate::{
+  initialize_loggers, with_mutable, with_mutable_self, DEFAULT_LOG_FILE, DEFAULT_LOG_FOLDER
+};
 use log::{log, log_enabled, Level};
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyKeyboardInterrupt, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyFloat, PyInt, PyList, PyString};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
 use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::Path;
+use std::time::Duration;
 
 #[pymodule]
 fn _native(_py: Python, m: &PyModule) -> PyResult<()> {
   m.add_class::<Executor>()?;
   m.add_class::<RequiredFeatures>()?;
+  m.add_class::<PyExecutionOptions>()?;
+  m.add_class::<PyPauli>()?;
+  m.add_class::<PyQubit>()?;
+  m.add_class::<EntryPoint>()?;
+  m.add_class::<Graph>()?;
   m.add_function(wrap_pyfunction!(initialize_file_logger, m)?);
   m.add_function(wrap_pyfunction!(initialize_commandline_logger, m)?);
   m.add("DEFAULT_LOG_FILE", DEFAULT_LOG_FILE);
@@ -26,20 +47,67 @@ fn _native(_py: Python, m: &PyModule) -> PyResult<()> {
   Ok(())
 }
 
+/// Converts seconds passed in from Python into a duration, raising on negative, infinite or NaN
+/// values rather than panicking on them.
+fn to_duration(seconds: f64) -> PyResult<Duration> {
+  Duration::try_from_secs_f64(seconds)
+    .map_err(|_| PyValueError::new_err(format!("{seconds} isn't a valid number of seconds.")))
+}
+
 #[pyclass]
 pub(crate) struct RequiredFeatures {
   #[pyo3(get)]
-  pub qubit_count: i32
+  pub qubit_count: i32,
+  #[pyo3(get)]
+  pub control_count: i32
 }
 
 impl RequiredFeatures {
   pub fn new(bp: &QuantumFeatures) -> RequiredFeatures {
     RequiredFeatures {
-      qubit_count: bp.qubits
+      qubit_count: bp.qubits,
+      control_count: bp.controls
     }
   }
 }
 
+#[pyclass(name = "ExecutionOptions")]
+pub(crate) struct PyExecutionOptions {
+  #[pyo3(get)]
+  pub shots: Option<i64>,
+  #[pyo3(get)]
+  pub seed: Option<i64>,
+  #[pyo3(get)]
+  pub optimisation_level: Option<i64>,
+
+  /// Timeout in seconds.
+  #[pyo3(get)]
+  pub timeout: Option<f64>
+}
+
+impl PyExecutionOptions {
+  pub fn new(options: &ExecutionOptions) -> PyExecutionOptions {
+    PyExecutionOptions {
+      shots: options.shots,
+      seed: options.seed,
+      optimisation_level: options.optimisation_level,
+      timeout: options.timeout.map(|val| val.as_secs_f64())
+    }
+  }
+}
+
+/// Builds execution options from what's been passed in from Python.
+fn to_execution_options(
+  shots: Option<i64>, seed: Option<i64>, optimisation_level: Option<i64>, timeout: Option<f64>
+) -> PyResult<ExecutionOptions> {
+  Ok(ExecutionOptions {
+    shots,
+    seed,
+    optimisation_level,
+    timeout: timeout.map(to_duration).transpose()?
+  })
+}
+
 /// Proxy for initializing Rasqal loggers. Pass in path for file logger initialization.
 #[pyfunction]
 fn initialize_file_logger(file_path: &str) { initialize_loggers(Some(file_path.to_string())); }
@@ -47,6 +115,52 @@ fn initialize_file_logger(file_path: &str) { initialize_loggers(Some(file_path.t
 #[pyfunction]
 fn initialize_commandline_logger() { initialize_loggers(None); }
 
+/// Python mirror of Q#'s Pauli bases.
+#[pyclass(name = "Pauli")]
+#[derive(Clone)]
+pub(crate) enum PyPauli {
+  I,
+  X,
+  Y,
+  Z
+}
+
+impl PyPauli {
+  pub fn new(pauli: &Pauli) -> PyPauli {
+    match pauli {
+      Pauli::I => PyPauli::I,
+      Pauli::X => PyPauli::X,
+      Pauli::Y => PyPauli::Y,
+      Pauli::Z => PyPauli::Z
+    }
+  }
+
+  pub fn as_pauli(&self) -> Pauli {
+    match self {
+      PyPauli::I => Pauli::I,
+      PyPauli::X => Pauli::X,
+      PyPauli::Y => Pauli::Y,
+      PyPauli::Z => Pauli::Z
+    }
+  }
+}
+
+/// Handle to a qubit inside a Rasqal execution.
+#[pyclass(name = "Qubit")]
+#[derive(Clone)]
+pub(crate) struct PyQubit {
+  #[pyo3(get)]
+  pub index: i64
+}
+
+#[pymethods]
+impl PyQubit {
+  #[new]
+  fn new(index: i64) -> Self { PyQubit { index } }
+
+  fn __repr__(&self) -> String { format!("Qubit({})", self.index) }
+}
+
 impl ToPyObject for Value {
   fn to_object(&self, py: Python<'_>) -> PyObject {
     match self {
@@ -55,46 +169,170 @@ impl ToPyObject for Value {
       Value::Short(nested) => nested.to_object(py),
       Value::Int(nested) => nested.to_object(py),
       Value::Long(nested) => nested.to_object(py),
-      Value::Bool(nested) => nested.to_object(py),
+      Value::Bool(nested) | Value::Result(nested) => nested.to_object(py),
       Value::Float(nested) => nested.to_object(py),
       Value::String(nested) => nested.to_object(py),
+      Value::Pauli(nested) => PyPauli::new(nested).into_py(py),
+      Value::Qubit(nested) => PyQubit::new(nested.index).into_py(py),
       Value::AnalysisResult(nested) => nested.distribution.to_object(py),
+      Value::QuantumPromise(qbs, projection) => with_mutable!(projection.results_for(qbs))
+        .distribution
+        .to_object(py),
       Value::Array(nested) => nested
         .iter()
         .map(|val| val.to_object(py))
         .collect::<Vec<_>>()
         .to_object(py),
-      _ => panic!("Can't return this type.")
+      // Neither has a Python equivalent, so they're returned as their description instead.
+      Value::Ref(..) | Value::Callable(_) => self.to_string().to_object(py)
     }
   }
 }
 
+impl ToPyObject for RunStatistics {
+  /// Durations are in seconds.
+  fn to_object(&self, py: Python<'_>) -> PyObject {
+    new_dict(py, |statistics| {
+      statistics.set_item("total_time", self.total_time.as_secs_f64())?;
+      statistics.set_item("evaluation_time", self.evaluation_time.as_secs_f64())?;
+      statistics.set_item("runtime_time", self.runtime_time().as_secs_f64())?;
+      statistics.set_item("solver_time", self.solver_time.as_secs_f64())?;
+      statistics.set_item("qpu_time", self.qpu_time.as_secs_f64())?;
+      statistics.set_item("steps", self.steps)?;
+      statistics.set_item("steps_per_graph", self.steps_per_graph.to_object(py))?;
+      statistics.set_item("projections_created", self.projections_created)?;
+      statistics.set_item("projections_merged", self.projections_merged)?;
+      statistics.set_item("projections_concretized", self.projections_concretized)?;
+      statistics.set_item("solver_attempts", self.solver_attempts)?;
+      statistics.set_item("solver_hits", self.solver_hits)?;
+      statistics.set_item("solver_hit_rate", self.solver_hit_rate())?;
+      statistics.set_item("circuits_executed", self.circuits_executed)?;
+      statistics.set_item("cache_hits", self.cache_hits)?;
+      statistics.set_item(
+        "projections",
+        self
+          .projections
+          .iter()
+          .map(|val| val.to_object(py))
+          .collect::<Vec<_>>()
+      )
+    })
+  }
+}
+
+impl ToPyObject for ProjectionStatistics {
+  fn to_object(&self, py: Python<'_>) -> PyObject {
+    new_dict(py, |statistics| {
+      statistics.set_item("index", self.index)?;
+      statistics.set_item("width", self.width)?;
+      statistics.set_item("depth", self.depth)?;
+      statistics.set_item("gate_counts", self.gate_counts.to_object(py))?;
+      statistics.set_item("solved", self.solved)?;
+      statistics.set_item("circuits", self.circuits)?;
+      statistics.set_item("backends", self.backends.to_object(py))?;
+      statistics.set_item("qpu_time", self.qpu_time.as_secs_f64())
+    })
+  }
+}
+
+impl ToPyObject for DebugStop {
+  fn to_object(&self, py: Python<'_>) -> PyObject {
+    new_dict(py, |stop| {
+      match &self.reason {
+        StopReason::Breakpoint(breakpoint) => {
+          stop.set_item("reason", "breakpoint")?;
+          stop.set_item("breakpoint", breakpoint.to_string())?;
+        }
+        StopReason::Step => {
+          stop.set_item("reason", "step")?;
+          stop.set_item("breakpoint", py.None())?;
+        }
+      }
+      stop.set_item("graph", &self.graph)?;
+      stop.set_item("node", self.node)?;
+      stop.set_item("instruction", &self.instruction)?;
+      stop.set_item("step", self.step)?;
+      stop.set_item("origin", self.origin.to_object(py))?;
+      stop.set_item("call_stack", self.call_stack.to_object(py))?;
+      stop.set_item("variables", self.variables.to_object(py))?;
+      stop.set_item("active_qubits", self.active_qubits.to_object(py))?;
+      stop.set_item(
+        "projections",
+        self
+          .projections
+          .iter()
+          .map(|projection| {
+            new_dict(py, |pending| {
+              pending.set_item("index", projection.index)?;
+              pending.set_item("qubits", projection.qubits.to_object(py))?;
+              pending.set_item("circuit", projection.circuit.to_object(py))?;
+              pending.set_item("concretized", projection.concretized)
+            })
+          })
+          .collect::<Vec<_>>()
+      )
+    })
+  }
+}
+
+impl ToPyObject for TraceEvent {
+  /// Events become dictionaries of their fields, with their kind under 'event'.
+  fn to_object(&self, py: Python<'_>) -> PyObject {
+    new_dict(py, |event| {
+      event.set_item("event", self.kind())?;
+      for (name, field) in self.fields() {
+        let value = match field {
+          TraceField::Empty => py.None(),
+          TraceField::Int(val) => val.to_object(py),
+          TraceField::Float(val) => val.to_object(py),
+          TraceField::Bool(val) => val.to_object(py),
+          TraceField::String(val) => val.to_object(py),
+          TraceField::Ints(vals) => vals.to_object(py),
+          TraceField::Strings(vals) => vals.to_object(py),
+          TraceField::Counts(vals) => vals.into_iter().collect::<HashMap<_, _>>().to_object(py)
+        };
+        event.set_item(name, value)?;
+      }
+      Ok(())
+    })
+  }
+}
+
+/// Creates a dictionary and fills it in. [`ToPyObject`] can't return errors, and filling a new
+/// dictionary only fails if Python has run out of memory, so that's treated as fatal.
+fn new_dict(py: Python<'_>, fill: impl FnOnce(&PyDict) -> PyResult<()>) -> PyObject {
+  let dict = PyDict::new(py);
+  fill(dict).expect("Couldn't fill in a new dictionary.");
+  dict.into()
+}
+
 impl FromPyObject<'_> for Value {
   fn extract(ob: &PyAny) -> PyResult<Self> {
-    let transformed = if ob.is_instance_of::<PyInt>().is_ok_and(|val| val) {
-      let value: i128 = ob
-        .extract()
-        .unwrap_or_else(|_| panic!("Can't map {ob} to Rasqal value."));
-      Value::Long(value)
-    } else if ob.is_instance_of::<PyFloat>().is_ok_and(|val| val) {
-      let value: f64 = ob
-        .extract()
-        .unwrap_or_else(|_| panic!("Can't map {ob} to Rasqal value."));
-      Value::Float(value)
-    } else if ob.is_instance_of::<PyBool>().is_ok_and(|val| val) {
-      let value: bool = ob
-        .extract()
-        .unwrap_or_else(|_| panic!("Can't map {ob} to Rasqal value."));
-      Value::Bool(value)
-    } else if ob.is_instance_of::<PyString>().is_ok_and(|val| val) {
-      let value: String = ob
-        .extract()
-        .unwrap_or_else(|_| panic!("Can't map {ob} to Rasqal value."));
-      Value::String(value)
+    // Bools are a subclass of int in Python, so need to be checked first.
+    let transformed = if ob.is_instance_of::<PyBool>()? {
+      Value::Bool(ob.extract()?)
+    } else if ob.is_instance_of::<PyInt>()? {
+      Value::Long(ob.extract()?)
+    } else if ob.is_instance_of::<PyFloat>()? {
+      Value::Float(ob.extract()?)
+    } else if ob.is_instance_of::<PyString>()? {
+      Value::String(ob.extract()?)
+    } else if ob.is_instance_of::<PyPauli>()? {
+      Value::Pauli(ob.extract::<PyPauli>()?.as_pauli())
+    } else if ob.is_instance_of::<PyQubit>()? {
+      Value::Qubit(Qubit::new(ob.extract::<PyQubit>()?.index))
+    } else if ob.is_instance_of::<PyList>()? || ob.is_instance_of::<PyTuple>()? {
+      Value::Array(
+        ob.iter()?
+          .map(|val| val.and_then(|val| val.extract::<Value>()).map(Ptr::from))
+          .collect::<PyResult<Vec<_>>>()?
+      )
+    } else if ob.is_instance_of::<PyDict>()? {
+      Value::AnalysisResult(Ptr::from(AnalysisResult::new(ob.extract()?)))
     } else {
-      return Err(PyValueError::new_err(
-        "Can't resolve Python value to Rasqal value."
-      ));
+      return Err(PyValueError::new_err(format!(
+        "Can't resolve Python value {ob} to Rasqal value."
+      )));
     };
 
     Ok(transformed)
@@ -119,6 +357,68 @@ impl Graph {
   }
 }
 
+#[pymethods]
+impl Graph {
+  /// Signature of the entry-point this graph will execute.
+  #[getter]
+  fn signature(&self) -> EntryPoint { EntryPoint::new(&self.wrapped.signature) }
+
+  /// Every entry-point available in the module this graph was built from.
+  #[getter]
+  fn entry_points(&self) -> Vec<EntryPoint> {
+    self
+      .wrapped
+      .entry_points
+      .iter()
+      .map(EntryPoint::new)
+      .collect()
+  }
+}
+
+/// Python view of an [`EntryPointSignature`].
+#[pyclass]
+#[derive(Clone)]
+pub(crate) struct EntryPoint {
+  #[pyo3(get)]
+  pub name: String,
+
+  /// Argument names and types in declaration order.
+  #[pyo3(get)]
+  pub arguments: Vec<(String, String)>,
+  #[pyo3(get)]
+  pub return_type: String,
+  #[pyo3(get)]
+  pub attributes: HashMap<String, String>,
+  #[pyo3(get)]
+  pub required_num_qubits: Option<i64>,
+  #[pyo3(get)]
+  pub required_num_results: Option<i64>,
+  display: String
+}
+
+impl EntryPoint {
+  pub fn new(signature: &EntryPointSignature) -> EntryPoint {
+    EntryPoint {
+      name: signature.name.clone(),
+      arguments: signature
+        .arguments
+        .iter()
+        .map(|(name, arg_type)| (name.clone(), arg_type.to_string()))
+        .collect(),
+      return_type: signature.return_type.to_string(),
+      attributes: signature.attributes.clone(),
+      required_num_qubits: signature.required_qubits(),
+      required_num_results: signature.required_results(),
+      display: signature.to_string()
+    }
+  }
+}
+
+#[pymethods]
+impl EntryPoint {
+  fn __repr__(&self) -> String { self.display.clone() }
+}
+
 /// People should set up loggers before they call our Python bindings, but if they don't we want
 /// to make sure our execution chain still outputs things correctly.
 ///
@@ -136,7 +436,10 @@ fn activate_fallback_logger() {
 
 #[pyclass]
 pub(crate) struct Executor {
-  config: Ptr<RasqalConfig>
+  config: Ptr<RasqalConfig>,
+
+  /// Statistics of the last successful run, or every run in the last batch added together.
+  statistics: Ptr<Option<RunStatistics>>
 }
 
 /// Python binding for allowing consumes to call into the Rust code.
@@ -146,8 +449,19 @@ impl Executor {
   fn new() -> Self {
     // Activate fallback logging if we don't have any.
     activate_fallback_logger();
+
+    // Runs hold the GIL, so periodically let other Python threads in (which may want to cancel
+    // us) and look for Ctrl-C's.
+    let mut config = RasqalConfig::default();
+    config.interrupt_check(|| {
+      Python::with_gil(|py| {
+        py.allow_threads(|| {});
+        py.check_signals().is_err()
+      })
+    });
     Executor {
-      config: Ptr::from(RasqalConfig::default())
+      config: Ptr::from(config),
+      statistics: Ptr::from(None)
     }
   }
 
@@ -157,8 +471,169 @@ impl Executor {
 
   fn trace_graphs(&mut self) { self.config.trace_graphs(); }
 
+  fn trace_solver(&mut self) { self.config.trace_solver(); }
+
   fn step_count_limit(&mut self, limit: i64) { self.config.step_count_limit(limit); }
 
+  fn time_limit(&mut self, seconds: f64) -> PyResult<()> {
+    self.config.time_limit(to_duration(seconds)?);
+    Ok(())
+  }
+
+  /// Stops the run currently in progress, which will raise an error.
+  fn cancel(&self) { self.config.cancellation.cancel(); }
+
+  /// Calls this with a dictionary for every trace event emitted during subsequent runs. Errors
+  /// raised by the callback are printed rather than stopping the run.
+  fn subscribe(&mut self, callback: PyObject) {
+    self.config.subscribe(move |event| {
+      Python::with_gil(|py| {
+        if let Err(err) = callback.call1(py, (event.to_object(py),)) {
+          err.print(py);
+        }
+      });
+    });
+  }
+
+  /// Attaches a debugger which calls the handler with a dictionary describing the runtime
+  /// whenever execution stops. It returns 'step', 'continue' or 'abort', with anything else (or
+  /// an error) aborting the run.
+  fn debug(&mut self, handler: PyObject) {
+    self.config.debug(Debugger::new(move |stop| {
+      Python::with_gil(|py| {
+        handler
+          .call1(py, (stop.to_object(py),))
+          .and_then(|action| {
+            let action: String = action.extract(py)?;
+            DebugAction::from_name(&action).ok_or_else(|| {
+              PyValueError::new_err(format!(
+                "Unknown debugger action '{action}'. Use 'step', 'continue' or 'abort'."
+              ))
+            })
+          })
+          .unwrap_or_else(|err| {
+            err.print(py);
+            DebugAction::Abort
+          })
+      })
+    }));
+  }
+
+  fn break_at_node(&mut self, graph: &str, node: i64) -> PyResult<()> {
+    self.add_breakpoint(Breakpoint::Node(graph.to_string(), node))
+  }
+
+  fn break_at_label(&mut self, label: &str) -> PyResult<()> {
+    self.add_breakpoint(Breakpoint::Label(label.to_string()))
+  }
+
+  fn break_at_function(&mut self, name: &str) -> PyResult<()> {
+    self.add_breakpoint(Breakpoint::Function(name.to_string()))
+  }
+
+  fn clear_breakpoints(&mut self) {
+    if let Some(debugger) = self.config.debugger.as_mut() {
+      debugger.clear_breakpoints();
+    }
+  }
+
+  fn trace_to_file(&mut self, file_path: &str) -> PyResult<()> {
+    self
+      .config
+      .trace_to_file(Path::new(file_path))
+      .map_err(PyValueError::new_err)
+  }
+
+  /// Statistics of the last run as a dictionary, or none if it failed.
+  fn statistics(&self) -> PyObject {
+    Python::with_gil(|py| {
+      self
+        .statistics
+        .deref()
+        .as_ref()
+        .map_or(py.None(), |val| val.to_object(py))
+    })
+  }
+
+  fn activate_solver(&mut self) { self.config.activate_solver(); }
+
+  fn solver_strategy(&mut self, strategy: &str) -> PyResult<()> {
+    let strategy = SolverStrategy::from_name(strategy).ok_or_else(|| {
+      PyValueError::new_err(format!(
+        "Unknown solver strategy '{strategy}'. Use 'entanglement' or 'mps'."
+      ))
+    })?;
+    self.config.solver_strategy(strategy);
+    Ok(())
+  }
+
+  fn max_bond_dimension(&mut self, max_bond_dimension: usize) {
+    self.config.max_bond_dimension(max_bond_dimension);
+  }
+
+  fn parallel_dispatch(&mut self) { self.config.parallel_dispatch(); }
+
+  fn projection_splitting(&mut self) { self.config.projection_splitting(); }
+
+  fn result_reduction(&mut self, reduction: &str, threshold: Option<f64>) -> PyResult<()> {
+    let reduction = ResultReduction::from_name(reduction, threshold).ok_or_else(|| {
+      PyValueError::new_err(format!(
+        "Unknown result reduction '{reduction}'. Use 'majority', 'unanimous', 'per-shot' or \
+         'error-on-ambiguity'."
+      ))
+    })?;
+    self.config.result_reduction(reduction);
+    Ok(())
+  }
+
+  fn qubit_allocation(&mut self, allocation: &str, capacity: Option<usize>) -> PyResult<()> {
+    let allocation = QubitAllocation::from_name(allocation, capacity).ok_or_else(|| {
+      PyValueError::new_err(format!(
+        "Unknown qubit allocation '{allocation}'. Use 'lowest-free', 'after-reset' or 'fixed' \
+         with a capacity."
+      ))
+    })?;
+    self.config.qubit_allocation(allocation);
+    Ok(())
+  }
+
+  fn cache_results(
+    &mut self, time_to_live: Option<f64>, max_entries: Option<usize>, directory: Option<&str>
+  ) -> PyResult<()> {
+    let time_to_live = time_to_live.map(to_duration).transpose()?;
+    let mut cache = ProjectionCache::new();
+    if let Some(directory) = directory {
+      cache = cache.with_directory(Path::new(directory));
+    }
+    self.config.cache_results(cache);
+    self.config.result_cache_limits(time_to_live, max_entries);
+    Ok(())
+  }
+
+  fn execution_options(
+    &mut self, shots: Option<i64>, seed: Option<i64>, optimisation_level: Option<i64>,
+    timeout: Option<f64>
+  ) -> PyResult<()> {
+    self.config.execution_options(to_execution_options(
+      shots,
+      seed,
+      optimisation_level,
+      timeout
+    )?);
+    Ok(())
+  }
+
+  fn projection_options(
+    &mut self, index: usize, shots: Option<i64>, seed: Option<i64>,
+    optimisation_level: Option<i64>, timeout: Option<f64>
+  ) -> PyResult<()> {
+    self.config.projection_options(
+      index,
+      to_execution_options(shots, seed, optimisation_level, timeout)?
+    );
+    Ok(())
+  }
+
   #[allow(clippy::unused_self)]
   fn parse_file(&self, file: &str, entry_point: Option<&str>) -> PyResult<Py<Graph>> {
     Python::with_gil(|py| -> PyResult<Py<Graph>> {
@@ -172,34 +647,115 @@ impl Executor {
     })
   }
 
+  fn parse_files(&self, files: Vec<&str>, entry_point: Option<&str>) -> PyResult<Py<Graph>> {
+    Python::with_gil(|py| -> PyResult<Py<Graph>> {
+      let graph = parse_files(&files, entry_point).map_err(PyValueError::new_err)?;
+      Py::new(py, Graph::new(graph.borrow()))
+    })
+  }
+
+  fn parse_ll(&self, text: &str, entry_point: Option<&str>) -> PyResult<Py<Graph>> {
+    Python::with_gil(|py| -> PyResult<Py<Graph>> {
+      let graph = parse_text(text, entry_point).map_err(PyValueError::new_err)?;
+      Py::new(py, Graph::new(graph.borrow()))
+    })
+  }
+
+  fn parse_bitcode(&self, bitcode: &[u8], entry_point: Option<&str>) -> PyResult<Py<Graph>> {
+    Python::with_gil(|py| -> PyResult<Py<Graph>> {
+      let graph = parse_bitcode(bitcode, entry_point).map_err(PyValueError::new_err)?;
+      Py::new(py, Graph::new(graph.borrow()))
+    })
+  }
+
+  fn run_ll(&self, text: &str, arguments: &PyAny, runtime_adaptor: &PyAny) -> PyResult<PyObject> {
+    Python::with_gil(|py| -> Result<PyObject, PyErr> {
+      let graph = parse_text(text, None).map_err(PyValueError::new_err)?;
+      let runtimes = Executor::runtimes(runtime_adaptor)?;
+      self
+        .run_with_arguments(&graph, arguments, &runtimes)
+        .map(|value| value.map_or(py.None(), |val| val.to_object(py)))
+    })
+  }
+
+  fn run_bitcode(
+    &self, bitcode: &[u8], arguments: &PyAny, runtime_adaptor: &PyAny
+  ) -> PyResult<PyObject> {
+    Python::with_gil(|py| -> Result<PyObject, PyErr> {
+      let graph = parse_bitcode(bitcode, None).map_err(PyValueError::new_err)?;
+      let runtimes = Executor::runtimes(runtime_adaptor)?;
+      self
+        .run_with_arguments(&graph, arguments, &runtimes)
+        .map(|value| value.map_or(py.None(), |val| val.to_object(py)))
+    })
+  }
+
   fn run_graph(
     &self, graph: Py<Graph>, arguments: &PyAny, runtime_adaptor: &PyAny
   ) -> PyResult<PyObject> {
     Python::with_gil(|py| -> Result<PyObject, PyErr> {
-      // We just build a reference directly here so our smart-pointer doesn't auto-drop.
-      let runtimes: Vec<&PyAny> = runtime_adaptor
-        .extract()
-        .expect("Unable to transform runtimes to Rust objects.");
-      let mut collection = Ptr::from(RuntimeCollection::default());
-      for runtime in runtimes {
-        collection.add(&Ptr::from(IntegrationRuntime::Python(PythonRuntime::new(
-          runtime
-        ))));
-      }
+      let runtimes = Executor::runtimes(runtime_adaptor)?;
+      let graph: Graph = graph.extract(py)?;
+      self
+        .run_with_arguments(graph.wrapped.borrow(), arguments, &runtimes)
+        .map(|value| value.map_or(py.None(), |val| val.to_object(py)))
+    })
+  }
+
+  /// Runs the graph once per shot on the built-in simulator, following a single sampled result
+  /// through every branch, and returns how often each final result came up.
+  fn run_trajectories(
+    &self, graph: Py<Graph>, arguments: &PyAny, shots: usize
+  ) -> PyResult<PyObject> {
+    Python::with_gil(|py| -> Result<PyObject, PyErr> {
+      let graph: Graph = graph.extract(py)?;
+      let args = Executor::extract_arguments(graph.wrapped.borrow(), arguments)?;
+      self.config.cancellation.reset();
+      with_mutable_self!(self.statistics = None);
+      let (histogram, statistics) =
+        run_graph_trajectories_with_statistics(graph.wrapped.borrow(), &args, shots, &self.config)
+          .map_err(|err| self.run_error(err))?;
+      with_mutable_self!(self.statistics = Some(statistics));
+      Ok(histogram.distribution.to_object(py))
+    })
+  }
 
-      let graph: Graph = graph.extract(py).expect("Unable to extract graph.");
-      let args: Vec<Value> = arguments
-        .extract()
-        .expect("Unable to transform arguments to Rust objects.");
+  fn run_graph_batch(
+    &self, graph: Py<Graph>, argument_sets: &PyAny, runtime_adaptor: &PyAny
+  ) -> PyResult<PyObject> {
+    Python::with_gil(|py| -> Result<PyObject, PyErr> {
+      let runtimes = Executor::runtimes(runtime_adaptor)?;
+      let graph: Graph = graph.extract(py)?;
 
-      run_graph(
+      // Each set can be positional or named, same as a single run.
+      let argument_sets = argument_sets
+        .extract::<Vec<&PyAny>>()?
+        .into_iter()
+        .map(|arguments| Executor::extract_arguments(graph.wrapped.borrow(), arguments))
+        .collect::<PyResult<Vec<_>>>()?;
+      self.config.cancellation.reset();
+      with_mutable_self!(self.statistics = None);
+      let results = run_graph_batch_with_statistics(
         graph.wrapped.borrow(),
-        args.as_ref(),
-        collection.borrow(),
+        &argument_sets,
+        &runtimes,
         &self.config
       )
-      .map_err(PyValueError::new_err)
-      .map(|value| value.map_or(py.None(), |val| val.to_object(py)))
+      .map_err(|err| self.run_error(err))?;
+
+      let mut statistics = RunStatistics::new();
+      for (_, run) in &results {
+        statistics.merge(run);
+      }
+      with_mutable_self!(self.statistics = Some(statistics));
+
+      Ok(
+        results
+          .iter()
+          .map(|(value, _)| value.as_ref().map_or(py.None(), |val| val.to_object(py)))
+          .collect::<Vec<_>>()
+          .to_object(py)
+      )
     })
   }
 
@@ -215,18 +771,73 @@ impl Executor {
     &self, file: &str, arguments: &PyAny, runtime_adaptor: &PyAny
   ) -> PyResult<PyObject> {
     Python::with_gil(|py| -> Result<PyObject, PyErr> {
-      let runtimes: Vec<&PyAny> = runtime_adaptor.extract()?;
-      let mut collection = Ptr::from(RuntimeCollection::default());
-      for runtime in runtimes {
-        collection.add(&Ptr::from(IntegrationRuntime::Python(PythonRuntime::new(
-          runtime
-        ))));
-      }
-
-      let args: Vec<Value> = arguments.extract()?;
-      run_file(file, &args, collection.borrow(), None, &self.config)
-        .map_err(PyValueError::new_err)
+      let runtimes = Executor::runtimes(runtime_adaptor)?;
+      let graph = parse_file(file, None).map_err(PyValueError::new_err)?;
+      self
+        .run_with_arguments(&graph, arguments, &runtimes)
         .map(|value| value.map_or(py.None(), |val| val.to_object(py)))
     })
   }
 }
+
+impl Executor {
+  fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> PyResult<()> {
+    let debugger = self
+      .config
+      .debugger
+      .as_mut()
+      .ok_or_else(|| PyValueError::new_err("Attach a debugger before adding breakpoints."))?;
+    debugger.add_breakpoint(breakpoint);
+    Ok(())
+  }
+
+  fn runtimes(runtime_adaptor: &PyAny) -> PyResult<Ptr<RuntimeCollection>> {
+    let runtimes: Vec<&PyAny> = runtime_adaptor.extract()?;
+    let mut collection = Ptr::from(RuntimeCollection::default());
+    for runtime in runtimes {
+      collection.add(&Ptr::from(IntegrationRuntime::new(PythonRuntime::new(
+        runtime
+      ))));
+    }
+    Ok(collection)
+  }
+
+  /// Runs the graph with either positional (list) or named (dict) arguments.
+  fn run_with_arguments(
+    &self, graph: &Ptr<ExecutableAnalysisGraph>, arguments: &PyAny,
+    runtimes: &Ptr<RuntimeCollection>
+  ) -> PyResult<Option<Ptr<Value>>> {
+    let args = Executor::extract_arguments(graph, arguments)?;
+    self.config.cancellation.reset();
+    with_mutable_self!(self.statistics = None);
+    let (results, statistics) = run_graph_with_statistics(graph, &args, runtimes, &self.config)
+      .map_err(|err| self.run_error(err))?;
+    with_mutable_self!(self.statistics = Some(statistics));
+    Ok(results)
+  }
+
+  /// Arguments passed either positionally as a list or by name as a dict.
+  fn extract_arguments(
+    graph: &Ptr<ExecutableAnalysisGraph>, arguments: &PyAny
+  ) -> PyResult<Vec<Value>> {
+    if arguments.is_instance_of::<PyDict>()? {
+      // Named arguments need the entry-points signature before we can order them.
+      let named: HashMap<String, Value> = arguments.extract()?;
+      graph
+        .signature
+        .order_arguments(&named)
+        .map_err(PyValueError::new_err)
+    } else {
+      arguments.extract()
+    }
+  }
+
+  /// Raises a failed run as a KeyboardInterrupt if that's what stopped it.
+  fn run_error(&self, message: String) -> PyErr {
+    if self.config.cancellation.was_interrupted() {
+      PyKeyboardInterrupt::new_err(message)
+    } else {
+      PyValueError::new_err(message)
+    }
+  }
+}
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::projections::AnalysisResult;
+use crate::builders::{CircuitBuilder, CircuitOperation, CircuitRecorder, QuantumBackend};
+use crate::config::ExecutionOptions;
+use crate::features::QuantumFeatures;
+use crate::hardware::Qubit;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QasmVersion {
+  V2,
+  V3
+}
+
+impl QasmVersion {
+  pub fn from_name(name: &str) -> Option<QasmVersion> {
+    match name {
+      "2" | "2.0" | "qasm2" => Some(QasmVersion::V2),
+      "3" | "3.0" | "qasm3" => Some(QasmVersion::V3),
+      _ => None
+    }
+  }
+}
+
+/// Emits these operations as an OpenQASM program. Measures are written to classical bits in the
+/// order they happen, so the first measure is the rightmost bit of any results, same as ours.
+///
+/// OpenQASM 2 has no multi-controlled rotations, so circuits which use them will only emit as 3.
+pub fn to_qasm(operations: &Vec<CircuitOperation>, version: QasmVersion) -> Result<String, String> {
+  let mut qubit_count = 0;
+  let mut bit_count = 0;
+  for operation in operations {
+    let qubits = match operation {
+      CircuitOperation::X(qb, _)
+      | CircuitOperation::Y(qb, _)
+      | CircuitOperation::Z(qb, _)
+      | CircuitOperation::Reset(qb) => vec![qb],
+      CircuitOperation::Measure(qb) => {
+        bit_count += 1;
+        vec![qb]
+      }
+      CircuitOperation::CX(controls, target, _)
+      | CircuitOperation::CY(controls, target, _)
+      | CircuitOperation::CZ(controls, target, _) => {
+        controls.iter().chain([target]).collect::<Vec<_>>()
+      }
+    };
+
+    for qb in qubits {
+      qubit_count = qubit_count.max(qb.index + 1);
+    }
+  }
+
+  let mut qasm = String::new();
+  match version {
+    QasmVersion::V2 => {
+      qasm.push_str("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n");
+      if qubit_count > 0 {
+        qasm.push_str(&format!("qreg q[{qubit_count}];\n"));
+      }
+      if bit_count > 0 {
+        qasm.push_str(&format!("creg c[{bit_count}];\n"));
+      }
+    }
+    QasmVersion::V3 => {
+      qasm.push_str("OPENQASM 3.0;\ninclude \"stdgates.inc\";\n");
+      if qubit_count > 0 {
+        qasm.push_str(&format!("qubit[{qubit_count}] q;\n"));
+      }
+      if bit_count > 0 {
+        qasm.push_str(&format!("bit[{bit_count}] c;\n"));
+      }
+    }
+  }
+
+  let mut bit = 0;
+  for operation in operations {
+    let line = match operation {
+      CircuitOperation::X(qb, radians) => format!("rx({radians}) q[{}];", qb.index),
+      CircuitOperation::Y(qb, radians) => format!("ry({radians}) q[{}];", qb.index),
+      CircuitOperation::Z(qb, radians) => format!("rz({radians}) q[{}];", qb.index),
+      CircuitOperation::CX(controls, target, radians) => {
+        controlled("rx", controls, target, *radians, version)?
+      }
+      CircuitOperation::CY(controls, target, radians) => {
+        controlled("ry", controls, target, *radians, version)?
+      }
+      CircuitOperation::CZ(controls, target, radians) => {
+        controlled("rz", controls, target, *radians, version)?
+      }
+      CircuitOperation::Reset(qb) => format!("reset q[{}];", qb.index),
+      CircuitOperation::Measure(qb) => {
+        bit += 1;
+        match version {
+          QasmVersion::V2 => format!("measure q[{}] -> c[{}];", qb.index, bit - 1),
+          QasmVersion::V3 => format!("c[{}] = measure q[{}];", bit - 1, qb.index)
+        }
+      }
+    };
+    qasm.push_str(&line);
+    qasm.push('\n');
+  }
+
+  Ok(qasm)
+}
+
+fn controlled(
+  gate: &str, controls: &Vec<Qubit>, target: &Qubit, radians: f64, version: QasmVersion
+) -> Result<String, String> {
+  let qubits = controls
+    .iter()
+    .chain([target])
+    .map(|qb| format!("q[{}]", qb.index))
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  match (controls.len(), version) {
+    (0, _) => Ok(format!("{gate}({radians}) {qubits};")),
+    (1, _) => Ok(format!("c{gate}({radians}) {qubits};")),
+    (count, QasmVersion::V3) => Ok(format!("ctrl({count}) @ {gate}({radians}) {qubits};")),
+    (count, QasmVersion::V2) => Err(format!(
+      "OpenQASM 2 can't express {gate} with {count} controls, emit as OpenQASM 3 instead."
+    ))
+  }
+}
+
+/// Circuit which has been sent to a backend through an [`ExportRuntime`].
+#[derive(Clone)]
+pub struct ExportedCircuit {
+  pub qasm: String,
+  pub options: ExecutionOptions
+}
+
+/// Runtime which records every circuit dispatched to it as OpenQASM, so what was sent to hardware
+/// can be archived and replayed later. Wraps another backend to actually execute on, otherwise
+/// returns empty results.
+///
+/// Clones share their recordings, so keep one around before registering it to read them back.
+#[derive(Clone)]
+pub struct ExportRuntime {
+  version: QasmVersion,
+  backend: Option<Arc<dyn QuantumBackend>>,
+  circuits: Arc<Mutex<Vec<ExportedCircuit>>>
+}
+
+impl ExportRuntime {
+  pub fn new(version: QasmVersion) -> ExportRuntime {
+    ExportRuntime {
+      version,
+      backend: None,
+      circuits: Arc::new(Mutex::new(Vec::new()))
+    }
+  }
+
+  /// Records circuits then forwards them on to this backend for execution.
+  pub fn wrapping(backend: impl QuantumBackend + 'static, version: QasmVersion) -> ExportRuntime {
+    let mut runtime = ExportRuntime::new(version);
+    runtime.backend = Some(Arc::new(backend));
+    runtime
+  }
+
+  /// Every circuit recorded so far, in the order they were executed.
+  pub fn circuits(&self) -> Vec<ExportedCircuit> { self.circuits.lock().unwrap().clone() }
+
+  pub fn clear(&self) { self.circuits.lock().unwrap().clear(); }
+}
+
+impl QuantumBackend for ExportRuntime {
+  fn identity(&self) -> String {
+    self
+      .backend
+      .as_ref()
+      .map_or(String::from("rasqal.export"), |backend| {
+        format!("rasqal.export.{}", backend.identity())
+      })
+  }
+
+  fn is_valid(&self) -> bool {
+    self
+      .backend
+      .as_ref()
+      .map_or(true, |backend| backend.is_valid())
+  }
+
+  fn features(&self) -> QuantumFeatures {
+    self
+      .backend
+      .as_ref()
+      .map_or(QuantumFeatures::default(), |backend| backend.features())
+  }
+
+  fn has_features(&self, required: &QuantumFeatures) -> bool {
+    // OpenQASM 2 has no way to write rotations with more than one control.
+    if self.version == QasmVersion::V2 && required.controls > 1 {
+      return false;
+    }
+
+    self
+      .backend
+      .as_ref()
+      .map_or(true, |backend| backend.has_features(required))
+  }
+
+  fn create_builder(&self) -> Box<dyn CircuitBuilder> { Box::new(CircuitRecorder::new()) }
+
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+    let circuit = builder
+      .downcast::<CircuitRecorder>()
+      .expect("Runtime/Builder execution type mismatch.");
+    let qasm = to_qasm(&circuit.operations(), self.version).unwrap_or_else(|err| panic!("{err}"));
+    self.circuits.lock().unwrap().push(ExportedCircuit {
+      qasm,
+      options: options.clone()
+    });
+
+    let Some(backend) = &self.backend else {
+      return AnalysisResult::empty();
+    };
+
+    // The wrapped backend gets its own builder, so replay what was recorded into it.
+    let replay = backend.create_builder();
+    for operation in circuit.operations().iter() {
+      match operation {
+        CircuitOperation::X(qb, radians) => replay.x(qb, *radians),
+        CircuitOperation::Y(qb, radians) => replay.y(qb, *radians),
+        CircuitOperation::Z(qb, radians) => replay.z(qb, *radians),
+        CircuitOperation::CX(controls, target, radians) => replay.cx(controls, target, *radians),
+        CircuitOperation::CY(controls, target, radians) => replay.cy(controls, target, *radians),
+        CircuitOperation::CZ(controls, target, radians) => replay.cz(controls, target, *radians),
+        CircuitOperation::Reset(qb) => replay.reset(qb),
+        CircuitOperation::Measure(qb) => replay.measure(qb)
+      }
+    }
+    backend.execute(replay.as_ref(), options)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::builders::{CircuitBuilder, CircuitRecorder, QuantumBackend};
+  use crate::config::ExecutionOptions;
+  use crate::features::QuantumFeatures;
+  use crate::hardware::Qubit;
+  use crate::qasm::{to_qasm, ExportRuntime, QasmVersion};
+  use crate::simulators::SimulatorRuntime;
+  use std::f64::consts::PI;
+
+  fn bell() -> CircuitRecorder {
+    let circuit = CircuitRecorder::new();
+    let (first, second) = (Qubit::new(0), Qubit::new(1));
+    circuit.x(&first, PI);
+    circuit.cx(&vec![first.clone()], &second, PI);
+    circuit.measure(&first);
+    circuit.measure(&second);
+    circuit
+  }
+
+  #[test]
+  fn emit_qasm() {
+    let circuit = bell();
+    assert_eq!(
+      to_qasm(&circuit.operations(), QasmVersion::V2).unwrap(),
+      "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[2];\ncreg c[2];\n\
+       rx(3.141592653589793) q[0];\ncrx(3.141592653589793) q[0], q[1];\n\
+       measure q[0] -> c[0];\nmeasure q[1] -> c[1];\n"
+    );
+    assert_eq!(
+      to_qasm(&circuit.operations(), QasmVersion::V3).unwrap(),
+      "OPENQASM 3.0;\ninclude \"stdgates.inc\";\nqubit[2] q;\nbit[2] c;\n\
+       rx(3.141592653589793) q[0];\ncrx(3.141592653589793) q[0], q[1];\n\
+       c[0] = measure q[0];\nc[1] = measure q[1];\n"
+    );
+  }
+
+  #[test]
+  fn multi_controlled() {
+    let circuit = CircuitRecorder::new();
+    circuit.ccnot(&Qubit::new(0), &Qubit::new(1), &Qubit::new(2), PI);
+    assert!(to_qasm(&circuit.operations(), QasmVersion::V2).is_err());
+    assert_eq!(
+      to_qasm(&circuit.operations(), QasmVersion::V3).unwrap(),
+      "OPENQASM 3.0;\ninclude \"stdgates.inc\";\nqubit[3] q;\n\
+       ctrl(2) @ rx(3.141592653589793) q[0], q[1], q[2];\n"
+    );
+
+    // OpenQASM 2 exports turn these circuits away rather than failing on them.
+    let required = QuantumFeatures::new(3).with_controls(2);
+    assert!(!ExportRuntime::new(QasmVersion::V2).has_features(&required));
+    assert!(ExportRuntime::new(QasmVersion::V3).has_features(&required));
+  }
+
+  #[test]
+  fn export_runtime() {
+    let export = ExportRuntime::wrapping(SimulatorRuntime::default(), QasmVersion::V3);
+    let handle = export.clone();
+    let results = export.execute(&bell(), &ExecutionOptions::new().with_shots(10));
+    assert_eq!(results.distribution.get("11"), Some(&10));
+
+    let circuits = handle.circuits();
+    assert_eq!(circuits.len(), 1);
+    assert_eq!(circuits[0].options.shots, Some(10));
+    assert_eq!(
+      circuits[0].qasm,
+      to_qasm(&bell().operations(), QasmVersion::V3).unwrap()
+    );
+    assert_eq!(export.identity(), "rasqal.export.rasqal.simulators.mps");
+  }
+}
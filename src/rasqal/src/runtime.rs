@@ -1,24 +1,34 @@
 // SPDX-License-Identifier: BSD-3-Clause
 // Copyright (c) 2024 Oxford Quantum Circuits Ltd
 
-use crate::analysis::projections::QuantumProjection;
+use crate::analysis::cache::ProjectionCache;
+use crate::analysis::projections::{QuantumProjection, ResultReduction};
+use crate::analysis::sampling::ShotSampler;
 use crate::config::RasqalConfig;
+use crate::debugger::{DebugAction, DebugStop, PendingProjection, StopReason};
 use crate::evaluator::EvaluationContext;
+use crate::events::TraceEvent;
+use crate::exceptions::catch_panics;
 use crate::execution::RuntimeCollection;
 use crate::graphs::{walk_logical_paths, AnalysisGraph, ExecutableAnalysisGraph, Node};
-use crate::hardware::Qubit;
+use crate::hardware::{Qubit, QubitAllocator};
 use crate::instructions::{
   Condition, Equalities, Expression, Gate, Instruction, LambdaModifier, Operator, Pauli, Value
 };
 use crate::smart_pointers::*;
+use crate::statistics::RunStatistics;
 use crate::with_mutable;
 use bitflags::bitflags;
 use log::{log, Level};
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::zip;
+use std::mem::discriminant;
 use std::ops::{AddAssign, Deref, DerefMut};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Assign an order to nodes so we're able to tell trivially when one is further in the graph
 /// or not.
@@ -212,8 +222,8 @@ fn follow_reference(qx: &Ptr<Value>, context: &Ptr<RuntimeContext>) -> Ptr<Value
 impl Expression {
   /// Execute this expression using the passed-in context. Returns the result, which varies
   /// should be inserted into the context if a variable is assigned.
-  pub fn execute(&self, context: &Ptr<RuntimeContext>) -> Ptr<Value> {
-    match self {
+  pub fn execute(&self, context: &Ptr<RuntimeContext>) -> Result<Ptr<Value>, String> {
+    Ok(match self {
       Expression::Clone(value) => follow_reference(&Ptr::from(value), context).clone(),
       Expression::Length(value) => {
         let followed_ref = follow_reference(&Ptr::from(value), context);
@@ -285,7 +295,21 @@ impl Expression {
 
         Ptr::from(val.clone())
       }
-    }
+      Expression::ReadResult(value) => {
+        let followed = follow_reference(&Ptr::from(value), context);
+        let address = if let Value::Int(index) = followed.deref() {
+          Some(*index)
+        } else {
+          None
+        };
+
+        let result = match address {
+          Some(index) => context.read_result(index)?,
+          None => followed
+        };
+        Ptr::from(Value::Result(result.as_bool()))
+      }
+    })
   }
 }
 
@@ -328,13 +352,144 @@ impl TracingModule {
   pub fn has(&self, check_against: ActiveTracers) -> bool { self.tracers.contains(check_against) }
 }
 
+/// How often, in steps, the configs interrupt check gets called. It can be expensive (it may need
+/// to acquire the GIL), so it isn't done every step.
+const INTERRUPT_CHECK_INTERVAL: i64 = 1000;
+
+/// How many states get remembered per loop header before they're forgotten, so long-running loops
+/// don't grow memory forever. Infinite loops which take more iterations than this to repeat
+/// themselves are left to the step and time limits.
+const MAX_LOOP_STATES: usize = 4096;
+
+/// Limits on a single execution, checked before every step it takes.
 #[derive(Clone, Default)]
 struct RuntimeConstraints {
-  step_limit: Option<i64>
+  step_limit: Option<i64>,
+  time_limit: Option<Duration>,
+  started: Option<Instant>
 }
 
 impl RuntimeConstraints {
-  pub fn new(step_limit: Option<i64>) -> RuntimeConstraints { RuntimeConstraints { step_limit } }
+  pub fn new(step_limit: Option<i64>, time_limit: Option<Duration>) -> RuntimeConstraints {
+    RuntimeConstraints {
+      step_limit,
+      time_limit,
+      started: Some(Instant::now())
+    }
+  }
+
+  /// Returns an error if taking another step would break these constraints.
+  pub fn check(&self, steps: i64) -> Result<(), String> {
+    if let Some(limit) = &self.step_limit {
+      if steps > *limit {
+        return Err(format!(
+          "Execution step count limitation of {limit} exceeded."
+        ));
+      }
+    }
+
+    if let (Some(limit), Some(started)) = (&self.time_limit, &self.started) {
+      if started.elapsed() > *limit {
+        return Err(format!(
+          "Execution time limit of {}ms exceeded.",
+          limit.as_millis()
+        ));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Records a loop headers state, returning false if it's been seen before. Forgets everything
+/// once [`MAX_LOOP_STATES`] have been recorded.
+fn remember_state(seen: &mut HashSet<u64>, fingerprint: u64) -> bool {
+  if seen.contains(&fingerprint) {
+    return false;
+  }
+
+  if seen.len() >= MAX_LOOP_STATES {
+    seen.clear();
+  }
+  seen.insert(fingerprint)
+}
+
+/// Hash of every value in this context that can change during execution. If a loop gets back to
+/// its start with the same fingerprint nothing it does can go differently, so it'll never end.
+fn state_fingerprint(context: &Ptr<RuntimeContext>) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  for variables in [&context.variables, context.globals.deref()] {
+    let mut keys = variables.keys().collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+      key.hash(&mut hasher);
+      hash_value(
+        &follow_reference(&variables[key], context),
+        context,
+        &mut hasher
+      );
+    }
+  }
+
+  // Measurements against static result addresses don't go through variables.
+  let mut addresses = context.results.keys().collect::<Vec<_>>();
+  addresses.sort();
+  for address in addresses {
+    address.hash(&mut hasher);
+    hash_value(&context.results[address], context, &mut hasher);
+  }
+
+  // Quantum operations change the state even if no variables do, such as in a
+  // repeat-until-success loop.
+  let mut projections = context
+    .projections
+    .iter()
+    .map(|(index, proj)| (*index, Ptr::as_address(proj), proj.instruction_count()))
+    .collect::<Vec<_>>();
+  projections.sort_unstable();
+  projections.hash(&mut hasher);
+  context.projection_count.deref().hash(&mut hasher);
+  context.active_qubits.len().hash(&mut hasher);
+  hasher.finish()
+}
+
+fn hash_value(value: &Value, context: &Ptr<RuntimeContext>, hasher: &mut DefaultHasher) {
+  discriminant(value).hash(hasher);
+  match value {
+    Value::Empty => {}
+    Value::Byte(val) => val.hash(hasher),
+    Value::Short(val) => val.hash(hasher),
+    Value::Int(val) => val.hash(hasher),
+    Value::Long(val) => val.hash(hasher),
+    Value::Bool(val) | Value::Result(val) => val.hash(hasher),
+    Value::Float(val) => val.to_bits().hash(hasher),
+    Value::String(val) => val.hash(hasher),
+    Value::Pauli(val) => val.to_string().hash(hasher),
+    Value::Qubit(qb) => qb.index.hash(hasher),
+    Value::Array(arr) => {
+      for val in arr {
+        hash_value(&follow_reference(val, context), context, hasher);
+      }
+    }
+    Value::QuantumPromise(qbs, proj) => {
+      for qb in qbs {
+        qb.index.hash(hasher);
+      }
+      Ptr::as_address(proj).hash(hasher);
+    }
+    Value::AnalysisResult(res) => {
+      let mut distribution = res.distribution.iter().collect::<Vec<_>>();
+      distribution.sort_unstable();
+      distribution.hash(hasher);
+    }
+    Value::Ref(ref_, additional) => {
+      ref_.hash(hasher);
+      if let Some(additional) = additional {
+        hash_value(additional, context, hasher);
+      }
+    }
+    Value::Callable(callable) => Ptr::as_address(callable).hash(hasher)
+  }
 }
 
 /// A runtime monitors, executes and maintains a cluster of graphs against the backend instances it
@@ -342,7 +497,29 @@ impl RuntimeConstraints {
 pub struct QuantumRuntime {
   engines: Ptr<RuntimeCollection>,
   trace_module: Ptr<TracingModule>,
-  config: Ptr<RasqalConfig>
+  config: Ptr<RasqalConfig>,
+  projection_cache: Option<Ptr<ProjectionCache>>,
+  constraints: RuntimeConstraints,
+  statistics: Ptr<RunStatistics>,
+
+  /// Picks the shots followed by per-shot result reduction, reseeded every execution.
+  sampler: Ptr<ShotSampler>,
+
+  /// How results get reduced when branched on. The configs, unless following a single shot.
+  reduction: ResultReduction,
+
+  /// Seeds the sampler instead of the execution options seed when set.
+  seed: Option<i64>,
+
+  /// Whether the debugger asked to stop at the next node.
+  stepping: bool,
+
+  /// Every function currently being executed, outermost first.
+  call_stack: Vec<String>,
+
+  /// Instruction being executed, the innermost one if inside a call. Used to say where errors
+  /// happened.
+  current_node: Option<Ptr<Node>>
 }
 
 impl QuantumRuntime {
@@ -350,10 +527,29 @@ impl QuantumRuntime {
     QuantumRuntime {
       engines: engines.clone(),
       config: config.clone(),
-      trace_module: Ptr::from(TracingModule::with(config.debug_tracers.clone()))
+      trace_module: Ptr::from(TracingModule::with(config.debug_tracers.clone())),
+      projection_cache: config.result_cache.clone(),
+      constraints: RuntimeConstraints::default(),
+      statistics: Ptr::from(RunStatistics::new()),
+      sampler: Ptr::from(ShotSampler::with_seed(config.execution_options.seed)),
+      reduction: config.result_reduction.clone(),
+      seed: None,
+      stepping: false,
+      call_stack: Vec::new(),
+      current_node: None
     }
   }
 
+  /// Statistics for the most recent execution.
+  pub fn statistics(&self) -> RunStatistics { self.statistics.deref().clone() }
+
+  /// Makes executions follow a single shot picked using this seed, whatever result reduction
+  /// the config has.
+  pub fn follow_shot(&mut self, seed: i64) {
+    self.reduction = ResultReduction::PerShot;
+    self.seed = Some(seed);
+  }
+
   /// Do we currently have runtime tracing active.
   fn is_tracing(&self) -> bool { self.trace_module.has(ActiveTracers::Runtime) }
 
@@ -361,51 +557,60 @@ impl QuantumRuntime {
   pub fn execute(
     &mut self, exe_graph: &Ptr<ExecutableAnalysisGraph>, arguments: &Vec<Value>
   ) -> Result<Option<Ptr<Value>>, String> {
+    self.statistics = Ptr::from(RunStatistics::new());
+    self.sampler = Ptr::from(ShotSampler::with_seed(
+      self.seed.or(self.config.execution_options.seed)
+    ));
     let mut context = exe_graph
       .context
       .attach_runtime(&Ptr::from(self.borrow_mut()));
 
     // Assign the initial arguments going in. Just treat it like a normal method call based
-    // on ordinal positioning. We don't really need the input to include names.
-
-    if exe_graph.callable_graph.argument_mappings.len() != arguments.len() {
-      let mut required_arguments = exe_graph
-        .callable_graph
-        .argument_mappings
-        .keys()
-        .cloned()
-        .collect::<Vec<_>>()
-        .join(", ");
-
-      if required_arguments.is_empty() {
-        required_arguments = String::from("no");
-      }
+    // on ordinal positioning, using the entry-point signature for the order and types. Graphs
+    // built without one have no types to check against, so they're bound in the order their
+    // parameters were declared.
+    if exe_graph.signature.is_empty() {
+      let parameters = &exe_graph.callable_graph.parameters;
+      if parameters.len() != arguments.len() {
+        let mut required_arguments = parameters.join(", ");
+        if required_arguments.is_empty() {
+          required_arguments = String::from("no");
+        }
 
-      let mut supplied_arguments = arguments
-        .iter()
-        .map(|val| val.to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
-      if supplied_arguments.is_empty() {
-        supplied_arguments = String::from("none");
-      }
+        let mut supplied_arguments = arguments
+          .iter()
+          .map(|val| val.to_string())
+          .collect::<Vec<_>>()
+          .join(", ");
+        if supplied_arguments.is_empty() {
+          supplied_arguments = String::from("none");
+        }
 
-      panic!("Root graph requires {required_arguments} arguments to execute. Got given: {supplied_arguments}.")
-    }
+        return Err(format!(
+          "Root graph requires {required_arguments} arguments to execute. Got given: {supplied_arguments}."
+        ));
+      }
 
-    let mut index = 0;
-    for (key, _) in &exe_graph.callable_graph.argument_mappings {
-      if let Some(value) = arguments.get(index as usize) {
-        context.add(key, &Ptr::from(value));
+      for (name, value) in zip(parameters, arguments) {
+        context.add(name, &Ptr::from(value));
+      }
+    } else {
+      exe_graph.signature.validate(arguments)?;
+      for ((name, _), value) in zip(&exe_graph.signature.arguments, arguments) {
+        context.add(name, &Ptr::from(value));
       }
-      index += 1;
     }
 
-    // Loop through active graphs in this execution and perform pre-execution analysis.
+    // Loop through active graphs in this execution and perform pre-execution analysis. This
+    // only needs doing once per graph, subsequent runs get their own copy of the results.
     // TODO: Should do this outside the executor, probably.
-    for subgraph in context.method_graphs.values() {
-      order_nodes(subgraph);
-      scope_variables(subgraph, &context);
+    if !exe_graph.is_analysed {
+      for subgraph in context.method_graphs.values() {
+        order_nodes(subgraph);
+        scope_variables(subgraph, &exe_graph.context);
+      }
+      with_mutable!(exe_graph.is_analysed = true);
+      context.scopes = exe_graph.context.copy_scopes();
     }
 
     if self.trace_module.has(ActiveTracers::Graphs) {
@@ -418,8 +623,12 @@ impl QuantumRuntime {
       exe_graph.callable_graph.analysis_graph.identity
     );
     let start = Instant::now();
+    self.constraints =
+      RuntimeConstraints::new(self.config.step_count_limit, self.config.time_limit);
+    self.stepping = false;
+    self.call_stack = vec![exe_graph.callable_graph.analysis_graph.identity.clone()];
     let results = self
-      ._execute(
+      .execute_located(
         exe_graph.callable_graph.analysis_graph.borrow(),
         &mut context
       )
@@ -427,6 +636,21 @@ impl QuantumRuntime {
         val.as_ref()?;
         let val = follow_reference(&val.unwrap(), &context);
 
+        // If we're returning multiple results from different projections, execute them together.
+        if self.config.parallel_dispatch {
+          if let Value::Array(arr) = val.deref() {
+            let projections = arr
+              .iter()
+              .map(|val| follow_reference(val, &context))
+              .filter_map(|val| match val.deref() {
+                Value::QuantumPromise(_, proj) => Some(proj.clone()),
+                _ => None
+              })
+              .collect::<Vec<_>>();
+            QuantumProjection::concretize_all(&projections);
+          }
+        }
+
         // TODO: Centralize resolution, think we already have this elsewhere.
         Some(match val.deref() {
           Value::QuantumPromise(qbs, proj) => Ptr::from(Value::AnalysisResult(Ptr::from(
@@ -449,6 +673,9 @@ impl QuantumRuntime {
       });
 
     let took = start.elapsed();
+    self.statistics.total_time = took;
+    self.statistics.evaluation_time = exe_graph.evaluation_time;
+    self.statistics.steps = *context.step_count;
     log!(
       Level::Info,
       "Run {} after {:?}ms with {} steps taken.",
@@ -461,9 +688,118 @@ impl QuantumRuntime {
       context.step_count
     );
 
+    if self.trace_module.has(ActiveTracers::Runtime) {
+      log!(Level::Info, "{}", self.statistics.to_string().trim_end());
+    }
+
     results
   }
 
+  /// Stops execution if it's been cancelled or has gone beyond its constraints.
+  fn check_constraints(&self, steps: i64) -> Result<(), String> {
+    if steps % INTERRUPT_CHECK_INTERVAL == 0 {
+      if let Some(check) = &self.config.interrupt_check {
+        if check() {
+          self.config.cancellation.interrupt();
+        }
+      }
+    }
+
+    if self.config.cancellation.is_cancelled() {
+      return Err(String::from("Execution was cancelled."));
+    }
+
+    self.constraints.check(steps)
+  }
+
+  /// Executes the root graph, pointing any error at where the node which was executing came
+  /// from if that's known.
+  fn execute_located(
+    &mut self, graph: &Ptr<AnalysisGraph>, context: &mut Ptr<RuntimeContext>
+  ) -> Result<Option<Ptr<Value>>, String> {
+    self.current_node = None;
+    catch_panics(|| self._execute(graph, context)).map_err(|err| {
+      match self
+        .current_node
+        .as_ref()
+        .and_then(|node| node.origin.as_ref())
+      {
+        Some(origin) => format!("{} At {origin}.", err.trim_end()),
+        None => err
+      }
+    })
+  }
+
+  /// Stops at this node if the debugger has a breakpoint on it or is stepping, then does what
+  /// the debugger says.
+  fn debug_node(
+    &mut self, graph: &Ptr<AnalysisGraph>, node: &Ptr<Node>, context: &Ptr<RuntimeContext>,
+    entry: bool
+  ) -> Result<(), String> {
+    let Some(debugger) = self.config.debugger.as_ref() else {
+      return Ok(());
+    };
+
+    let order = node.order.expect("Node ordering required.");
+    let reason = match debugger.breakpoint_at(&graph.identity, order, &node.instruction, entry) {
+      Some(breakpoint) => StopReason::Breakpoint(breakpoint.clone()),
+      None if self.stepping => StopReason::Step,
+      None => return Ok(())
+    };
+
+    let mut projections: Vec<(Ptr<QuantumProjection>, PendingProjection)> = Vec::new();
+    for (qubit, projection) in context.projections.iter() {
+      if let Some((_, pending)) = projections
+        .iter_mut()
+        .find(|(val, _)| Ptr::eq(val, projection))
+      {
+        pending.qubits.push(*qubit);
+      } else {
+        projections.push((projection.clone(), PendingProjection {
+          index: projection.index(),
+          qubits: vec![*qubit],
+          circuit: projection.circuit(),
+          concretized: projection.is_concretized()
+        }));
+      }
+    }
+
+    let mut projections = projections
+      .into_iter()
+      .map(|(_, mut pending)| {
+        pending.qubits.sort_unstable();
+        pending
+      })
+      .collect::<Vec<_>>();
+    projections.sort_by_key(|pending| pending.index);
+    let mut active_qubits = context.active_qubits.keys().copied().collect::<Vec<_>>();
+    active_qubits.sort_unstable();
+
+    let stop = DebugStop {
+      reason,
+      graph: graph.identity.clone(),
+      node: order,
+      instruction: node.instruction.to_string(),
+      step: *context.step_count,
+      origin: node.origin.as_ref().map(ToString::to_string),
+      call_stack: self.call_stack.clone(),
+      variables: context
+        .variables
+        .iter()
+        .map(|(key, value)| (key.clone(), follow_reference(value, context).to_string()))
+        .collect(),
+      active_qubits,
+      projections
+    };
+
+    match debugger.stopped(&stop) {
+      DebugAction::Step => self.stepping = true,
+      DebugAction::Continue => self.stepping = false,
+      DebugAction::Abort => return Err(String::from("Execution aborted by debugger."))
+    }
+    Ok(())
+  }
+
   fn _execute(
     &mut self, graph: &Ptr<AnalysisGraph>, context: &mut Ptr<RuntimeContext>
   ) -> Result<Option<Ptr<Value>>, String> {
@@ -483,22 +819,21 @@ impl QuantumRuntime {
       follow_reference(val, context).as_float()
     }
 
-    // Endlessly loop until the graph completes execution.
-    // TODO: Need infinite loop check-and-break.
+    // Loop until the graph completes execution, we're stopped, or we find ourselves looping in
+    // exactly the same state as before.
     let mut old_variables: HashMap<String, Ptr<Value>> = HashMap::new();
     let mut available_scopes = with_mutable!(context.scopes.get_mut(&graph.identity));
     let mut seen_nodes = HashSet::new();
+    let mut loop_headers: HashMap<usize, bool> = HashMap::new();
+    let mut seen_states: HashMap<usize, HashSet<u64>> = HashMap::new();
+    let mut entry = true;
     loop {
       context.step_count.add_assign(1);
-      if let Some(limit) = &self.config.step_count_limit {
-        if context.step_count.deref() > limit {
-          return Err(String::from(format!(
-            "Execution step count limitation of {limit} exceeded."
-          )));
-        }
-      }
+      self.statistics.record_step(&graph.identity);
+      self.check_constraints(*context.step_count)?;
 
-      if self.is_tracing() {
+      let tracing = self.is_tracing();
+      if tracing || self.config.events.is_active() {
         let mut changed_variables = Vec::new();
         let mut updated_variables = HashMap::new();
         for (key, value) in &context.variables {
@@ -511,6 +846,11 @@ impl QuantumRuntime {
           };
 
           if changed {
+            self.config.events.emit(|| TraceEvent::VariableChanged {
+              graph: graph.identity.clone(),
+              variable: key.clone(),
+              value: followed_value.to_string()
+            });
             changed_variables.push(format!("({} = {})", key.clone(), followed_value));
           }
 
@@ -527,14 +867,24 @@ impl QuantumRuntime {
         }
 
         old_variables = updated_variables;
-        log!(
-          Level::Info,
-          "{} :: {}",
-          current_node.to_string().as_str(),
-          changed_variables.join(", ")
-        );
+        if tracing {
+          log!(
+            Level::Info,
+            "{} :: {}",
+            current_node.to_string().as_str(),
+            changed_variables.join(", ")
+          );
+        }
       }
 
+      self.config.events.emit(|| TraceEvent::NodeEntered {
+        graph: graph.identity.clone(),
+        node: current_node.id(),
+        instruction: current_node.instruction.to_string(),
+        origin: current_node.origin.as_ref().map(ToString::to_string),
+        step: *context.step_count
+      });
+
       let node_id = current_node.id();
       if let Some(scopes) = available_scopes.as_mut() {
         if let Some(scope) = scopes.get_mut(&current_node.order.expect("Node ordering required.")) {
@@ -574,8 +924,34 @@ impl QuantumRuntime {
         }
       }
 
+      // Only loop headers (nodes with a backward jump into them) need checking, as any loop has
+      // to go through one.
+      if seen_nodes.contains(&node_id)
+        && *loop_headers.entry(node_id).or_insert_with(|| {
+          let order = current_node.order.expect("Node ordering required.");
+          current_node
+            .incoming_nodes()
+            .iter()
+            .any(|(_, node)| node.order.expect("Node ordering required.") > order)
+        })
+        && !remember_state(
+          seen_states.entry(node_id).or_default(),
+          state_fingerprint(context)
+        )
+      {
+        return Err(format!(
+          "Infinite loop detected at {current_node} in {}, its state hasn't changed since it \
+           was last visited.",
+          graph.identity
+        ));
+      }
+
       seen_nodes.insert(node_id);
+      self.debug_node(graph, &current_node, context, entry)?;
+      entry = false;
 
+      // Only errors from the instruction itself are its fault, not limits or the debugger.
+      self.current_node = Some(current_node.clone());
       let instruction = &current_node.instruction;
       match instruction.deref() {
         Instruction::Return(results) => {
@@ -619,7 +995,19 @@ impl QuantumRuntime {
             log!(Level::Info, "{} -->", subgraph.analysis_graph.identity);
           }
 
+          self.config.events.emit(|| TraceEvent::SubgraphCalled {
+            caller: graph.identity.clone(),
+            callee: subgraph.analysis_graph.identity.clone()
+          });
+          self
+            .call_stack
+            .push(subgraph.analysis_graph.identity.clone());
           let results = self._execute(subgraph.analysis_graph.borrow(), subcontext.borrow_mut())?;
+          self.call_stack.pop();
+          self.config.events.emit(|| TraceEvent::SubgraphReturned {
+            caller: graph.identity.clone(),
+            callee: subgraph.analysis_graph.identity.clone()
+          });
           if let Some(target) = var {
             let results = results.map_or(Ptr::from(Value::Empty), |val| val.clone());
             with_mutable!(context.add(target, results.borrow()));
@@ -685,29 +1073,19 @@ impl QuantumRuntime {
         Instruction::DeactivateQubit(qb) => {
           let qb = follow_reference(qb, context);
           match qb.deref() {
-            Value::Qubit(qb) => {
-              let mut current_projection = context.activate_projection(&qb);
-              current_projection.Reset(vec![qb.clone()]);
-              context.deactivate_qubit(&qb);
-            }
+            Value::Qubit(qb) => context.free_qubit(qb),
             Value::Array(array) => {
               for value in array {
                 let followed = follow_reference(value, context);
-                let qubit = followed.as_qubit();
-
-                let mut current_projection = context.activate_projection(qubit);
-                current_projection.Reset(vec![qubit.clone()]);
-                context.deactivate_qubit(qubit);
+                context.free_qubit(followed.as_qubit());
               }
             }
             _ => panic!("Not a qubit or an array of them. Can't deactivate.")
           }
         }
         Instruction::Reset(qb) => {
-          let qb = qb.as_qubit();
-          let proj = context.activate_projection(&qb);
-
-          with_mutable!(proj.Reset(vec![qb.clone()]));
+          let qb = follow_qubit(qb, context);
+          context.reset_qubit(&qb);
         }
         Instruction::Gate(gate) => {
           match gate.deref() {
@@ -772,7 +1150,9 @@ impl QuantumRuntime {
                 _ => Vec::new()
               };
 
-              let mut projection = context.activate_projection(&qubit);
+              let mut qubits = controls.clone();
+              qubits.push(qubit.clone());
+              let mut projection = context.activate_projections(&qubits);
               match pauli {
                 Pauli::I => {}
                 Pauli::X => {
@@ -787,10 +1167,8 @@ impl QuantumRuntime {
               }
             }
             Gate::CX(control, target, radii) => {
-              // TODO: Multi qubit activation, deal with.
               let followed = follow_qubit(target, context);
               let rotation = follow_float(radii, context);
-              let mut projection = context.activate_projection(&followed);
               let controls = match follow_reference(control, context).deref() {
                 Value::Qubit(qb) => vec![qb.clone()],
                 Value::Array(arr) => arr
@@ -800,13 +1178,14 @@ impl QuantumRuntime {
                 _ => Vec::new()
               };
 
+              let mut qubits = controls.clone();
+              qubits.push(followed.clone());
+              let mut projection = context.activate_projections(&qubits);
               projection.CX(controls, followed.clone(), rotation);
             }
             Gate::CZ(control, target, radii) => {
-              // TODO: Multi qubit activation, deal with.
               let followed = follow_qubit(target, context);
               let rotation = follow_float(radii, context);
-              let mut projection = context.activate_projection(&followed);
               let controls = match follow_reference(control, context).deref() {
                 Value::Qubit(qb) => vec![qb.clone()],
                 Value::Array(arr) => arr
@@ -816,13 +1195,14 @@ impl QuantumRuntime {
                 _ => Vec::new()
               };
 
+              let mut qubits = controls.clone();
+              qubits.push(followed.clone());
+              let mut projection = context.activate_projections(&qubits);
               projection.CZ(controls, followed.clone(), rotation);
             }
             Gate::CY(control, target, radii) => {
-              // TODO: Multi qubit activation, deal with.
               let followed = follow_qubit(target, context);
               let rotation = follow_float(radii, context);
-              let mut projection = context.activate_projection(&followed);
               let controls = match follow_reference(control, context).deref() {
                 Value::Qubit(qb) => vec![qb.clone()],
                 Value::Array(arr) => arr
@@ -832,6 +1212,9 @@ impl QuantumRuntime {
                 _ => Vec::new()
               };
 
+              let mut qubits = controls.clone();
+              qubits.push(followed.clone());
+              let mut projection = context.activate_projections(&qubits);
               projection.CY(controls, followed.clone(), rotation);
             }
             Gate::Measure(pauli, qbs, var) => {
@@ -846,36 +1229,45 @@ impl QuantumRuntime {
                 _ => panic!("Invalid qubit.")
               };
 
-              let mut projection = context.activate_projection(
-                &qubits
-                  .first()
-                  .expect("Should have at least one qubit to measure.")
-              );
-              projection.Measure(qubits.clone());
-
+              let as_pauli = |val: &Value| match val {
+                Value::Pauli(pauli) => pauli.clone(),
+                val => Pauli::from_qir(val.as_int())
+              };
               let paulis = match follow_reference(pauli, context).deref() {
                 Value::Array(array) => array
                   .iter()
-                  .map(|val| follow_reference(val, context).as_pauli())
+                  .map(|val| as_pauli(follow_reference(val, context).deref()))
                   .collect::<Vec<_>>(),
-                val => vec![val.as_pauli()]
+                val => vec![as_pauli(val)]
               };
+              if paulis.len() != qubits.len() {
+                panic!(
+                  "Measure needs a basis for every qubit, got {} for {} qubits.",
+                  paulis.len(),
+                  qubits.len()
+                );
+              }
+
+              if qubits.is_empty() {
+                panic!("Should have at least one qubit to measure.");
+              }
 
-              let promise = Ptr::from(Value::QuantumPromise(qubits, projection.clone()));
+              let mut projection = context.activate_projections(&qubits);
+              let measured = projection.MeasurePauli(paulis, qubits);
+              let promise = Ptr::from(Value::QuantumPromise(measured, projection.clone()));
 
+              // Results are either a variable, or a static address in the classical register.
               let followed_var = follow_reference(var, context);
-              let variable = if let Value::String(val) = followed_var.deref() {
-                val.clone()
+              if let Value::String(variable) = followed_var.deref() {
+                with_mutable!(context.add(variable, promise.borrow()));
               } else {
-                format!("%cr_{followed_var}")
-              };
-
-              with_mutable!(context.add(&variable, promise.borrow()));
+                with_mutable!(context.write_result(followed_var.as_int(), &promise));
+              }
             }
           }
         }
         Instruction::Expression(expr, assign) => {
-          let result = expr.execute(context);
+          let result = expr.execute(context)?;
           if let Some(variable) = assign {
             with_mutable!(context.add(variable, result.borrow()));
           }
@@ -884,6 +1276,7 @@ impl QuantumRuntime {
         // Purposefully empty.
         Instruction::NoOp | Instruction::Initialize() => {}
       }
+      self.current_node = None;
 
       // If our node has no outward edges, we've finished.
       if current_node.is_exit_node() {
@@ -910,6 +1303,7 @@ impl QuantumRuntime {
   }
 }
 
+#[derive(Clone)]
 pub struct VariableScopes {
   captured_variables: HashSet<String>,
 
@@ -956,9 +1350,20 @@ pub struct RuntimeContext {
   pub variables: HashMap<String, Ptr<Value>>,
   pub method_graphs: Ptr<HashMap<String, Ptr<AnalysisGraph>>>,
   pub active_qubits: Ptr<HashMap<i64, Ptr<Qubit>>>,
+
+  /// Picks the index of every qubit the program allocates, reusing released ones as the
+  /// configured strategy allows.
+  pub qubit_allocator: Ptr<QubitAllocator>,
   pub is_base_profile: bool,
   pub step_count: Ptr<i64>,
 
+  /// How many projections have been created so far during this run.
+  pub projection_count: Ptr<usize>,
+
+  /// Classical register holding measurements made against static result addresses, such as
+  /// `%Result* inttoptr (i64 1 to %Result*)`, keyed by that address.
+  pub results: Ptr<HashMap<i64, Ptr<Value>>>,
+
   // TODO: Don't like this being everywhere, but it is a core object.
   //  Potentially change this back to POD object.
   pub associated_runtime: Ptr<QuantumRuntime>,
@@ -980,11 +1385,14 @@ impl Default for RuntimeContext {
       variables: HashMap::default(),
       projections: Ptr::from(HashMap::new()),
       active_qubits: Ptr::from(HashMap::new()),
+      qubit_allocator: Ptr::from(QubitAllocator::default()),
       scopes: Ptr::from(HashMap::new()),
       method_graphs: Ptr::from(HashMap::new()),
       associated_runtime: Ptr::default(),
       is_base_profile: false,
-      step_count: Ptr::from(0)
+      step_count: Ptr::from(0),
+      projection_count: Ptr::from(0),
+      results: Ptr::from(HashMap::new())
     }
   }
 }
@@ -998,11 +1406,14 @@ impl RuntimeContext {
       variables: HashMap::default(),
       projections: Ptr::from(HashMap::new()),
       active_qubits: Ptr::from(HashMap::new()),
+      qubit_allocator: Ptr::from(QubitAllocator::default()),
       scopes: Ptr::from(HashMap::new()),
       method_graphs: context.method_graphs.clone(),
       associated_runtime: Ptr::default(),
       is_base_profile: *context.is_base_profile.deref(),
-      step_count: Ptr::from(0)
+      step_count: Ptr::from(0),
+      projection_count: Ptr::from(0),
+      results: Ptr::from(HashMap::new())
     }
   }
 
@@ -1012,31 +1423,49 @@ impl RuntimeContext {
       variables: HashMap::default(),
       projections: self.projections.clone(),
       active_qubits: self.active_qubits.clone(),
+      qubit_allocator: self.qubit_allocator.clone(),
       scopes: self.scopes.clone(),
       method_graphs: self.method_graphs.clone(),
       associated_runtime: self.associated_runtime.clone(),
       is_base_profile: self.is_base_profile,
-      step_count: self.step_count.clone()
+      step_count: self.step_count.clone(),
+      projection_count: self.projection_count.clone(),
+      results: self.results.clone()
     }
   }
 
-  /// Create new subcontext associated with runtime.
+  /// Create new subcontext associated with runtime. Everything that changes during a run is
+  /// fresh, so the same graph can be executed multiple times without runs interfering.
   pub fn attach_runtime(&self, runtime: &Ptr<QuantumRuntime>) -> Ptr<RuntimeContext> {
     let mut new_context = self.create_subcontext();
     new_context.associated_runtime = runtime.clone();
+    new_context.projections = Ptr::from(HashMap::new());
+    new_context.active_qubits = Ptr::from(HashMap::new());
+    new_context.qubit_allocator =
+      Ptr::from(QubitAllocator::new(runtime.config.qubit_allocation.clone()));
+    new_context.step_count = Ptr::from(0);
+    new_context.projection_count = Ptr::from(0);
+    new_context.results = Ptr::from(HashMap::new());
+    new_context.scopes = self.copy_scopes();
     Ptr::from(new_context)
   }
 
+  /// Deep copy of the variable scopes, which get modified as execution progresses.
+  fn copy_scopes(&self) -> Ptr<HashMap<String, Ptr<HashMap<i64, VariableScopes>>>> {
+    Ptr::from(
+      self
+        .scopes
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone_inner()))
+        .collect::<HashMap<_, _>>()
+    )
+  }
+
   /// Get the next free qubit and then activate it.
   fn get_free_qubit(&mut self) -> Ptr<Qubit> {
-    // TODO: Brute-force so needs improvement but finding qubit gaps is important.
-    let mut inc: i64 = 0;
-    while self.active_qubits.contains_key(&inc) {
-      inc += 1;
-    }
-
-    let new_qubit = Ptr::from(Qubit::new(inc));
-    self.active_qubits.insert(inc, new_qubit.clone());
+    let index = self.qubit_allocator.allocate(self.active_qubits.deref());
+    let new_qubit = Ptr::from(Qubit::new(index));
+    self.active_qubits.insert(index, new_qubit.clone());
     new_qubit
   }
 
@@ -1073,6 +1502,22 @@ impl RuntimeContext {
     )
   }
 
+  /// Stores a measurement at this address in the classical register, replacing whatever was
+  /// there before.
+  pub fn write_result(&mut self, index: i64, result: &Ptr<Value>) {
+    self.results.insert(index, result.clone());
+  }
+
+  /// Measurement stored at this address in the classical register. Errors if nothing has been
+  /// measured into it yet.
+  pub fn read_result(&self, index: i64) -> Result<Ptr<Value>, String> {
+    self
+      .results
+      .get(&index)
+      .cloned()
+      .ok_or_else(|| format!("Result {index} was read before anything was measured into it."))
+  }
+
   /// Activates a qubit and associates it to the current projection.
   /// If no projection exists it creates one.
   pub fn activate_qubit(&mut self) -> Ptr<Qubit> {
@@ -1081,6 +1526,34 @@ impl RuntimeContext {
     new_qubit
   }
 
+  /// Resets a qubit the program has finished with and deactivates it, letting the allocator know
+  /// whether the program had already reset it.
+  pub fn free_qubit(&mut self, qb: &Qubit) {
+    // Qubits which were split off from their projection got reset as they left it.
+    let is_reset = if self.projections.contains_key(&qb.index) || !self.splits_projections() {
+      let mut projection = self.activate_projection(qb);
+      let is_reset = self.qubit_allocator.verifies_resets() && projection.is_reset(qb);
+      projection.Reset(vec![qb.clone()]);
+      is_reset
+    } else {
+      true
+    };
+    self.qubit_allocator.release(qb.index, is_reset);
+    self.deactivate_qubit(qb);
+  }
+
+  /// Resets this qubit. When projections are being split that leaves it independent of everything
+  /// it did before, so it's moved out of its projection and whatever it's used for next starts a
+  /// new one. Measures don't split, as the qubit is left in whichever state it collapsed to, which
+  /// is correlated with the results of everything it was entangled with.
+  pub fn reset_qubit(&mut self, qb: &Qubit) {
+    let mut projection = self.activate_projection(qb);
+    projection.Reset(vec![qb.clone()]);
+    if self.splits_projections() {
+      self.deactivate_projection(qb);
+    }
+  }
+
   /// Deactivates a qubit from the context and removes its projection association.
   pub fn deactivate_qubit(&mut self, qb: &Qubit) {
     self.release_qubit(qb);
@@ -1094,14 +1567,40 @@ impl RuntimeContext {
       return proj.clone();
     }
 
-    // In general running a single projection covers all current qubits, so we
-    // steal the one that's currently active if it's there.
-    let projection = if self.projections.is_empty() {
-      Ptr::from(QuantumProjection::with_tracer_and_config(
+    // Unless projections are being split, running a single projection covers all current
+    // qubits, so we steal the one that's currently active if it's there.
+    let projection = if self.projections.is_empty() || self.splits_projections() {
+      let mut projection = QuantumProjection::with_tracer_and_config(
         &self.associated_runtime.engines,
         &self.associated_runtime.trace_module,
         &self.associated_runtime.config
-      ))
+      );
+      if let Some(cache) = self.associated_runtime.projection_cache.as_ref() {
+        projection.attach_cache(cache);
+      }
+
+      projection.attach_sampler(&self.associated_runtime.sampler);
+      projection.attach_step_count(&self.step_count);
+      projection.set_reduction(self.associated_runtime.reduction.clone());
+      let statistics = &self.associated_runtime.statistics;
+      projection.attach_statistics(statistics, *self.projection_count);
+      with_mutable!(statistics.projections_created += 1);
+      self
+        .associated_runtime
+        .config
+        .events
+        .emit(|| TraceEvent::ProjectionCreated {
+          projection: *self.projection_count
+        });
+
+      projection.set_options(
+        self
+          .associated_runtime
+          .config
+          .options_for(*self.projection_count)
+      );
+      self.projection_count.add_assign(1);
+      Ptr::from(projection)
     } else {
       self.projections.values().next().unwrap().clone()
     };
@@ -1110,13 +1609,56 @@ impl RuntimeContext {
     projection
   }
 
+  /// Activates the projections of all these qubits, such as for a gate acting on them together.
+  /// If they're spread across more than one, the rest get merged into the oldest and every qubit
+  /// they held moves over to it.
+  pub fn activate_projections(&mut self, qbs: &Vec<Qubit>) -> Ptr<QuantumProjection> {
+    let mut projections: Vec<Ptr<QuantumProjection>> = Vec::new();
+    for qb in qbs {
+      let projection = self.activate_projection(qb);
+      if !projections.iter().any(|val| Ptr::eq(val, &projection)) {
+        projections.push(projection);
+      }
+    }
+
+    let target = projections
+      .iter()
+      .min_by_key(|val| val.index())
+      .expect("Should have at least one qubit to activate.")
+      .clone();
+    if projections.len() > 1 {
+      for projection in projections.iter() {
+        QuantumProjection::absorb(&target, projection);
+      }
+
+      for projection in self.projections.values_mut() {
+        if projections.iter().any(|val| Ptr::eq(val, projection)) {
+          *projection = target.clone();
+        }
+      }
+    }
+    target
+  }
+
   /// Removes this qubits projection association.
   pub fn deactivate_projection(&mut self, qb: &Qubit) { self.projections.remove(&qb.index); }
+
+  /// Whether qubits only share a projection once something entangles them. Base profiles always
+  /// use a single one, as their results are every qubit at once.
+  fn splits_projections(&self) -> bool {
+    self.associated_runtime.config.projection_splitting && !self.is_base_profile
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::runtime::{ActiveTracers, TracingModule};
+  use crate::instructions::Value;
+  use crate::runtime::{
+    remember_state, state_fingerprint, ActiveTracers, RuntimeContext, TracingModule,
+    MAX_LOOP_STATES
+  };
+  use crate::smart_pointers::Ptr;
+  use std::collections::HashSet;
 
   #[test]
   fn tracers_all() {
@@ -1144,4 +1686,36 @@ mod tests {
       }
     }
   }
+
+  #[test]
+  fn loop_states_are_bounded() {
+    let mut seen = HashSet::new();
+    let limit = MAX_LOOP_STATES as u64;
+    for fingerprint in 0..limit {
+      assert!(remember_state(&mut seen, fingerprint));
+    }
+    assert!(!remember_state(&mut seen, limit - 1));
+    assert_eq!(seen.len(), MAX_LOOP_STATES);
+
+    // Full, so everything is forgotten before the next state is recorded.
+    assert!(remember_state(&mut seen, limit));
+    assert_eq!(seen.len(), 1);
+  }
+
+  /// Loops which only measure into static result addresses still need to tell iterations apart.
+  #[test]
+  fn fingerprint_includes_results() {
+    let mut context = Ptr::from(RuntimeContext::new());
+    let empty = state_fingerprint(&context);
+
+    context.write_result(1, &Ptr::from(Value::Bool(true)));
+    let written = state_fingerprint(&context);
+    assert_ne!(empty, written);
+
+    context.write_result(1, &Ptr::from(Value::Bool(false)));
+    assert_ne!(written, state_fingerprint(&context));
+
+    context.write_result(1, &Ptr::from(Value::Bool(true)));
+    assert_eq!(written, state_fingerprint(&context));
+  }
 }
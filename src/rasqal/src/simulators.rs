@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::analysis::mps::MatrixProductState;
+use crate::analysis::projections::{AnalysisResult, QuantumProjection};
+use crate::analysis::solver::{
+  CircuitSolver, QuantumSolver, SolverStrategy, DEFAULT_BOND_DIMENSION
+};
+use crate::builders::{CircuitBuilder, CircuitOperation, CircuitRecorder, QuantumBackend};
+use crate::config::ExecutionOptions;
+use std::collections::HashMap;
+
+const DEFAULT_SHOTS: i64 = 1024;
+
+/// Native simulator which runs circuits through one of the solvers. Returns the expected counts
+/// for the requested shots rather than sampling, so results are stable across runs.
+pub struct SimulatorRuntime {
+  strategy: SolverStrategy,
+  max_bond_dimension: usize
+}
+
+impl SimulatorRuntime {
+  pub fn new(strategy: SolverStrategy) -> SimulatorRuntime {
+    SimulatorRuntime {
+      strategy,
+      max_bond_dimension: DEFAULT_BOND_DIMENSION
+    }
+  }
+
+  pub fn with_max_bond_dimension(mut self, max_bond_dimension: usize) -> SimulatorRuntime {
+    self.max_bond_dimension = max_bond_dimension;
+    self
+  }
+}
+
+impl Default for SimulatorRuntime {
+  fn default() -> Self { SimulatorRuntime::new(SolverStrategy::MatrixProductState) }
+}
+
+impl QuantumBackend for SimulatorRuntime {
+  fn identity(&self) -> String {
+    match self.strategy {
+      SolverStrategy::Entanglement => String::from("rasqal.simulators.entanglement"),
+      SolverStrategy::MatrixProductState => String::from("rasqal.simulators.mps")
+    }
+  }
+
+  fn create_builder(&self) -> Box<dyn CircuitBuilder> { Box::new(CircuitRecorder::new()) }
+
+  fn execute(&self, builder: &dyn CircuitBuilder, options: &ExecutionOptions) -> AnalysisResult {
+    let circuit = builder
+      .downcast::<CircuitRecorder>()
+      .expect("Runtime/Builder execution type mismatch.");
+    let solver: Box<dyn CircuitSolver> = match self.strategy {
+      SolverStrategy::Entanglement => Box::new(QuantumSolver::new()),
+      SolverStrategy::MatrixProductState => {
+        Box::new(MatrixProductState::new(self.max_bond_dimension))
+      }
+    };
+
+    let mut measured = false;
+    for operation in circuit.operations().iter() {
+      match operation {
+        CircuitOperation::X(qb, radians) => solver.X(qb, radians),
+        CircuitOperation::Y(qb, radians) => solver.Y(qb, radians),
+        CircuitOperation::Z(qb, radians) => solver.Z(qb, radians),
+        CircuitOperation::CX(controls, target, radians) => solver.CX(controls, target, radians),
+        CircuitOperation::CY(controls, target, radians) => solver.CY(controls, target, radians),
+        CircuitOperation::CZ(controls, target, radians) => solver.CZ(controls, target, radians),
+        CircuitOperation::Reset(qb) => solver.reset(qb),
+        CircuitOperation::Measure(qb) => {
+          solver.measure(qb);
+          measured = true;
+        }
+      }
+    }
+
+    if !measured {
+      return AnalysisResult::empty();
+    }
+
+    // Solver bitstrings have the first measure leftmost, whereas results have it rightmost.
+    let mut probabilities = HashMap::new();
+    for result in solver.solve() {
+      if !result.bitstring.contains('X') {
+        let key = result.bitstring.chars().rev().collect::<String>();
+        *probabilities.entry(key).or_insert(0.) += result.probability;
+      }
+    }
+
+    // Anything below the solver's cutoff is dropped, so share the shots out over what's left.
+    let total = probabilities.values().sum::<f64>();
+    if total <= 0. {
+      return AnalysisResult::empty();
+    }
+    probabilities
+      .values_mut()
+      .for_each(|probability| *probability /= total);
+
+    let shots = options.shots.unwrap_or(DEFAULT_SHOTS);
+    AnalysisResult::new(QuantumProjection::apportion(probabilities, shots))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::solver::SolverStrategy;
+  use crate::builders::{CircuitBuilder, CircuitRecorder, QuantumBackend};
+  use crate::config::ExecutionOptions;
+  use crate::hardware::Qubit;
+  use crate::simulators::SimulatorRuntime;
+  use std::f64::consts::PI;
+
+  fn bell() -> CircuitRecorder {
+    let circuit = CircuitRecorder::new();
+    let (first, second) = (Qubit::new(0), Qubit::new(1));
+    circuit.had(&first);
+    circuit.cx(&vec![first.clone()], &second, PI);
+    circuit.measure(&first);
+    circuit.measure(&second);
+    circuit
+  }
+
+  #[test]
+  fn simulated_bell() {
+    let results = SimulatorRuntime::new(SolverStrategy::MatrixProductState)
+      .execute(&bell(), &ExecutionOptions::new());
+    assert_eq!(results.distribution.get("00"), Some(&512));
+    assert_eq!(results.distribution.get("11"), Some(&512));
+    assert_eq!(results.distribution.len(), 2);
+  }
+
+  #[test]
+  fn respects_shots() {
+    let results =
+      SimulatorRuntime::default().execute(&bell(), &ExecutionOptions::new().with_shots(100));
+    assert_eq!(results.distribution.values().sum::<i64>(), 100);
+  }
+
+  #[test]
+  fn counts_add_up_to_shots() {
+    let circuit = CircuitRecorder::new();
+    let (first, second) = (Qubit::new(0), Qubit::new(1));
+    circuit.had(&first);
+    circuit.had(&second);
+    circuit.measure(&first);
+    circuit.measure(&second);
+
+    // Four even outcomes can't split ten shots evenly, rounding each one would give twelve.
+    let results =
+      SimulatorRuntime::default().execute(&circuit, &ExecutionOptions::new().with_shots(10));
+    assert_eq!(results.distribution.values().sum::<i64>(), 10);
+    assert_eq!(results.distribution.len(), 4);
+  }
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: BSD-3-Clause
+// Copyright (c) 2024 Oxford Quantum Circuits Ltd
+
+use crate::builders::CircuitOperation;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Shape of the circuit a single projection built, and how its results were got.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProjectionStatistics {
+  /// Order the projection was created in during the run, starting at 0.
+  pub index: usize,
+
+  /// Number of distinct qubits the circuit touches.
+  pub width: usize,
+  pub depth: usize,
+
+  /// Gate counts keyed by lower-case operation name (x, cx, measure, etc).
+  pub gate_counts: BTreeMap<String, usize>,
+
+  /// Whether the solver calculated the results so no backend was needed.
+  pub solved: bool,
+
+  /// Circuits built for backends, including any served from the result cache. More than one if
+  /// the projection was split up for parallel dispatch.
+  pub circuits: usize,
+
+  /// Identities of every backend the circuits were sent to.
+  pub backends: Vec<String>,
+
+  /// Time spent waiting on backends. When projections are dispatched together every one of them
+  /// reports the full time.
+  pub qpu_time: Duration
+}
+
+impl ProjectionStatistics {
+  pub fn from_operations(index: usize, operations: &Vec<CircuitOperation>) -> ProjectionStatistics {
+    let mut depths: HashMap<i64, usize> = HashMap::new();
+    let mut gate_counts = BTreeMap::new();
+    for operation in operations {
+      let (name, qubits) = match operation {
+        CircuitOperation::X(qb, _) => ("x", vec![qb]),
+        CircuitOperation::Y(qb, _) => ("y", vec![qb]),
+        CircuitOperation::Z(qb, _) => ("z", vec![qb]),
+        CircuitOperation::CX(controls, target, _) => {
+          ("cx", controls.iter().chain([target]).collect())
+        }
+        CircuitOperation::CY(controls, target, _) => {
+          ("cy", controls.iter().chain([target]).collect())
+        }
+        CircuitOperation::CZ(controls, target, _) => {
+          ("cz", controls.iter().chain([target]).collect())
+        }
+        CircuitOperation::Reset(qb) => ("reset", vec![qb]),
+        CircuitOperation::Measure(qb) => ("measure", vec![qb])
+      };
+      *gate_counts.entry(name.to_string()).or_insert(0) += 1;
+
+      // Operations go in the first layer after everything their qubits are already involved in.
+      let layer = qubits
+        .iter()
+        .map(|qb| depths.get(&qb.index).copied().unwrap_or(0))
+        .max()
+        .unwrap_or(0)
+        + 1;
+      for qb in qubits {
+        depths.insert(qb.index, layer);
+      }
+    }
+
+    ProjectionStatistics {
+      index,
+      width: depths.len(),
+      depth: depths.values().copied().max().unwrap_or(0),
+      gate_counts,
+      ..ProjectionStatistics::default()
+    }
+  }
+
+  pub fn gate_count(&self) -> usize { self.gate_counts.values().sum() }
+}
+
+/// Numbers collected across a single run, used for profiling and for accounting of QPU usage.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunStatistics {
+  /// Wall-clock time of the whole run, not including evaluation.
+  pub total_time: Duration,
+
+  /// Time taken to evaluate the QIR into the graph which was run.
+  pub evaluation_time: Duration,
+  pub solver_time: Duration,
+
+  /// Time spent waiting on backends. Concurrently-dispatched circuits are only counted once.
+  pub qpu_time: Duration,
+
+  pub steps: i64,
+  pub steps_per_graph: HashMap<String, i64>,
+  pub projections_created: usize,
+
+  /// Projections folded into another as gates entangled their qubits.
+  pub projections_merged: usize,
+  pub projections_concretized: usize,
+  pub solver_attempts: usize,
+  pub solver_hits: usize,
+  pub circuits_executed: usize,
+  pub cache_hits: usize,
+
+  /// Every concretized projection, in the order it was concretized.
+  pub projections: Vec<ProjectionStatistics>
+}
+
+impl RunStatistics {
+  pub fn new() -> RunStatistics { RunStatistics::default() }
+
+  /// Time spent in the runtime itself, outside of the solver or waiting on backends.
+  pub fn runtime_time(&self) -> Duration {
+    self
+      .total_time
+      .saturating_sub(self.solver_time + self.qpu_time)
+  }
+
+  /// Fraction of solver attempts which calculated the results outright.
+  pub fn solver_hit_rate(&self) -> f64 {
+    if self.solver_attempts == 0 {
+      0.0
+    } else {
+      self.solver_hits as f64 / self.solver_attempts as f64
+    }
+  }
+
+  pub(crate) fn record_step(&mut self, graph: &String) {
+    if let Some(steps) = self.steps_per_graph.get_mut(graph) {
+      *steps += 1;
+    } else {
+      self.steps_per_graph.insert(graph.clone(), 1);
+    }
+  }
+
+  pub(crate) fn record_solver(&mut self, took: Duration, solved: bool) {
+    self.solver_time += took;
+    self.solver_attempts += 1;
+    if solved {
+      self.solver_hits += 1;
+    }
+  }
+
+  pub(crate) fn record_dispatch(&mut self, took: Duration, circuits: usize, cache_hits: usize) {
+    self.qpu_time += took;
+    self.circuits_executed += circuits - cache_hits;
+    self.cache_hits += cache_hits;
+  }
+
+  pub(crate) fn record_projection(&mut self, projection: ProjectionStatistics) {
+    self.projections_concretized += 1;
+    self.projections.push(projection);
+  }
+
+  /// Adds another runs numbers onto these, such as when totalling up a batch. Batched runs share
+  /// the graph they ran, so its evaluation time is only counted once.
+  pub fn merge(&mut self, other: &RunStatistics) {
+    self.total_time += other.total_time;
+    self.evaluation_time = self.evaluation_time.max(other.evaluation_time);
+    self.solver_time += other.solver_time;
+    self.qpu_time += other.qpu_time;
+    self.steps += other.steps;
+    for (graph, steps) in &other.steps_per_graph {
+      *self.steps_per_graph.entry(graph.clone()).or_insert(0) += steps;
+    }
+    self.projections_created += other.projections_created;
+    self.projections_merged += other.projections_merged;
+    self.projections_concretized += other.projections_concretized;
+    self.solver_attempts += other.solver_attempts;
+    self.solver_hits += other.solver_hits;
+    self.circuits_executed += other.circuits_executed;
+    self.cache_hits += other.cache_hits;
+    self.projections.extend(other.projections.iter().cloned());
+  }
+}
+
+impl Display for RunStatistics {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    writeln!(
+      f,
+      "Run took {:.2}ms: runtime {:.2}ms, solver {:.2}ms, QPU {:.2}ms (evaluation {:.2}ms).",
+      millis(self.total_time),
+      millis(self.runtime_time()),
+      millis(self.solver_time),
+      millis(self.qpu_time),
+      millis(self.evaluation_time)
+    )?;
+
+    let mut graphs = self.steps_per_graph.iter().collect::<Vec<_>>();
+    graphs.sort_by(|left, right| right.1.cmp(left.1).then(left.0.cmp(right.0)));
+    writeln!(f, "{} steps taken:", self.steps)?;
+    for (graph, steps) in graphs {
+      writeln!(f, "  {graph}: {steps}")?;
+    }
+
+    writeln!(
+      f,
+      "{} projections created, {} merged, {} concretized. Solver hit {} of {} ({:.0}%). \
+       {} circuits executed, {} served from cache.",
+      self.projections_created,
+      self.projections_merged,
+      self.projections_concretized,
+      self.solver_hits,
+      self.solver_attempts,
+      self.solver_hit_rate() * 100.0,
+      self.circuits_executed,
+      self.cache_hits
+    )?;
+
+    for projection in &self.projections {
+      writeln!(
+        f,
+        "  Projection {}: width {}, depth {}, {} gates [{}], {}",
+        projection.index,
+        projection.width,
+        projection.depth,
+        projection.gate_count(),
+        projection
+          .gate_counts
+          .iter()
+          .map(|(name, count)| format!("{name}: {count}"))
+          .collect::<Vec<_>>()
+          .join(", "),
+        if projection.solved {
+          String::from("solved")
+        } else {
+          format!(
+            "{} circuits on {} in {:.2}ms",
+            projection.circuits,
+            projection.backends.join(", "),
+            millis(projection.qpu_time)
+          )
+        }
+      )?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::builders::{CircuitBuilder, CircuitRecorder};
+  use crate::hardware::Qubit;
+  use crate::statistics::{ProjectionStatistics, RunStatistics};
+  use std::f64::consts::PI;
+  use std::time::Duration;
+
+  #[test]
+  fn circuit_shape() {
+    let circuit = CircuitRecorder::new();
+    let (first, second, third) = (Qubit::new(0), Qubit::new(1), Qubit::new(2));
+    circuit.had(&first);
+    circuit.x(&third, PI);
+    circuit.cnot(&first, &second, PI);
+    circuit.measure(&first);
+    circuit.measure(&second);
+
+    let statistics = ProjectionStatistics::from_operations(2, &circuit.operations());
+    assert_eq!(statistics.index, 2);
+    assert_eq!(statistics.width, 3);
+
+    // Hadamard is two rotations, then the cnot, then the measure.
+    assert_eq!(statistics.depth, 4);
+    assert_eq!(statistics.gate_counts.get("cx"), Some(&1));
+    assert_eq!(statistics.gate_counts.get("measure"), Some(&2));
+    assert_eq!(statistics.gate_count(), 6);
+  }
+
+  #[test]
+  fn merge_and_rates() {
+    let mut first = RunStatistics::new();
+    first.total_time = Duration::from_millis(10);
+    first.qpu_time = Duration::from_millis(4);
+    first.evaluation_time = Duration::from_millis(3);
+    first.record_solver(Duration::from_millis(1), true);
+    first.record_step(&String::from("main"));
+
+    let mut second = RunStatistics::new();
+    second.total_time = Duration::from_millis(5);
+    second.evaluation_time = Duration::from_millis(3);
+    second.record_solver(Duration::from_millis(1), false);
+    second.record_dispatch(Duration::from_millis(2), 3, 1);
+    second.record_step(&String::from("main"));
+
+    first.merge(&second);
+    assert_eq!(first.runtime_time(), Duration::from_millis(7));
+    assert_eq!(first.evaluation_time, Duration::from_millis(3));
+    assert_eq!(first.solver_hit_rate(), 0.5);
+    assert_eq!(first.circuits_executed, 2);
+    assert_eq!(first.cache_hits, 1);
+    assert_eq!(first.steps_per_graph.get("main"), Some(&2));
+  }
+}
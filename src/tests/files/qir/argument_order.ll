@@ -0,0 +1,7 @@
+define i64 @Arguments__Subtract__body(i64 %minuend, i64 %subtrahend) #0 {
+entry:
+  %0 = sub i64 %minuend, %subtrahend
+  ret i64 %0
+}
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,11 @@
+%Array = type opaque
+
+define i64 @Arguments__CountPaulis__body(%Array* %paulis) #0 {
+entry:
+  %0 = call i64 @__quantum__rt__array_get_size_1d(%Array* %paulis)
+  ret i64 %0
+}
+
+declare i64 @__quantum__rt__array_get_size_1d(%Array*)
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,54 @@
+; ModuleID = 'debug_info'
+source_filename = "Debug.qs"
+
+%String = type opaque
+
+@0 = internal constant [14 x i8] c"Out of range.\00"
+
+; Same counting loop as in loops.ll, but with the source locations a compiler would attach.
+define i64 @Debug__Count__body() #0 !dbg !4 {
+entry:
+  br label %header, !dbg !5
+
+header:
+  %i = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp sge i64 %i, 3, !dbg !6
+  br i1 %done, label %exit, label %body, !dbg !6
+
+body:
+  %next = add i64 %i, 1, !dbg !7
+  br label %header, !dbg !7
+
+exit:
+  ret i64 %i, !dbg !8
+}
+
+; Fails outright, so errors can be traced back to the line that threw.
+define void @Debug__Fail__body() #0 !dbg !9 {
+entry:
+  %0 = call %String* @__quantum__rt__string_create(i8* getelementptr inbounds ([14 x i8], [14 x i8]* @0, i32 0, i32 0)), !dbg !10
+  call void @__quantum__rt__fail(%String* %0), !dbg !11
+  unreachable
+}
+
+declare %String* @__quantum__rt__string_create(i8*)
+
+declare void @__quantum__rt__fail(%String*)
+
+attributes #0 = { "EntryPoint" }
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!2}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_C, file: !1, producer: "qsc", isOptimized: false, runtimeVersion: 0, emissionKind: LineTablesOnly)
+!1 = !DIFile(filename: "Debug.qs", directory: "/src")
+!2 = !{i32 2, !"Debug Info Version", i32 3}
+!3 = !DISubroutineType(types: !{})
+!4 = distinct !DISubprogram(name: "Count", scope: !1, file: !1, line: 3, type: !3, scopeLine: 3, spFlags: DISPFlagDefinition, unit: !0)
+!5 = !DILocation(line: 4, column: 9, scope: !4)
+!6 = !DILocation(line: 5, column: 15, scope: !4)
+!7 = !DILocation(line: 6, column: 13, scope: !4)
+!8 = !DILocation(line: 8, column: 9, scope: !4)
+!9 = distinct !DISubprogram(name: "Fail", scope: !1, file: !1, line: 11, type: !3, scopeLine: 11, spFlags: DISPFlagDefinition, unit: !0)
+!10 = !DILocation(line: 12, column: 19, scope: !9)
+!11 = !DILocation(line: 12, column: 9, scope: !9)
@@ -0,0 +1,28 @@
+%Qubit = type opaque
+%Result = type opaque
+
+; Flips a qubit if asked to, then returns whether it was measured as one.
+define i1 @Arguments__Flip__body(i1 %flip) #0 {
+entry:
+  br i1 %flip, label %then, label %measure
+
+then:
+  call void @__quantum__qis__x__body(%Qubit* null)
+  br label %measure
+
+measure:
+  %result = call %Result* @__quantum__qis__m__body(%Qubit* null)
+  %one = call %Result* @__quantum__rt__result_get_one()
+  %is_one = call i1 @__quantum__rt__result_equal(%Result* %result, %Result* %one)
+  ret i1 %is_one
+}
+
+declare void @__quantum__qis__x__body(%Qubit*)
+
+declare %Result* @__quantum__qis__m__body(%Qubit*)
+
+declare %Result* @__quantum__rt__result_get_one()
+
+declare i1 @__quantum__rt__result_equal(%Result*, %Result*)
+
+attributes #0 = { "EntryPoint" }
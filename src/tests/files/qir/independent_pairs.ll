@@ -0,0 +1,40 @@
+; ModuleID = 'independent_pairs'
+source_filename = "independent_pairs"
+
+%Qubit = type opaque
+%Result = type opaque
+
+; Entangles two pairs of qubits which never interact with each other, then returns whether a
+; qubit from each pair measured one. Both always do.
+define i1 @Pairs__Independent__body() #0 {
+entry:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  %c = call %Qubit* @__quantum__rt__qubit_allocate()
+  %d = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__x__body(%Qubit* %a)
+  call void @__quantum__qis__cnot__body(%Qubit* %a, %Qubit* %b)
+  call void @__quantum__qis__x__body(%Qubit* %c)
+  call void @__quantum__qis__cnot__body(%Qubit* %c, %Qubit* %d)
+  %first = call %Result* @__quantum__qis__m__body(%Qubit* %b)
+  %second = call %Result* @__quantum__qis__m__body(%Qubit* %d)
+  %one = call %Result* @__quantum__rt__result_get_one()
+  %first_one = call i1 @__quantum__rt__result_equal(%Result* %first, %Result* %one)
+  %second_one = call i1 @__quantum__rt__result_equal(%Result* %second, %Result* %one)
+  %both = and i1 %first_one, %second_one
+  ret i1 %both
+}
+
+declare %Qubit* @__quantum__rt__qubit_allocate()
+
+declare void @__quantum__qis__x__body(%Qubit*)
+
+declare void @__quantum__qis__cnot__body(%Qubit*, %Qubit*)
+
+declare %Result* @__quantum__qis__m__body(%Qubit*)
+
+declare %Result* @__quantum__rt__result_get_one()
+
+declare i1 @__quantum__rt__result_equal(%Result*, %Result*)
+
+attributes #0 = { "EntryPoint" }
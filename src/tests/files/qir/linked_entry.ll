@@ -0,0 +1,11 @@
+%Array = type opaque
+
+define i64 @Linked__CountPaulis__body(%Array* %paulis) #0 {
+entry:
+  %0 = call i64 @Library__Count__body(%Array* %paulis)
+  ret i64 %0
+}
+
+declare i64 @Library__Count__body(%Array*)
+
+attributes #0 = { "EntryPoint" }
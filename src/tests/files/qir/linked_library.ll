@@ -0,0 +1,9 @@
+%Array = type opaque
+
+define i64 @Library__Count__body(%Array* %items) {
+entry:
+  %0 = call i64 @__quantum__rt__array_get_size_1d(%Array* %items)
+  ret i64 %0
+}
+
+declare i64 @__quantum__rt__array_get_size_1d(%Array*)
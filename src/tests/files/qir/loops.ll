@@ -0,0 +1,58 @@
+; ModuleID = 'loops'
+source_filename = "loops"
+
+; Never finishes and never changes state, as the counter doesn't move.
+define i64 @Loops__Spin__body() #0 {
+entry:
+  br label %header
+
+header:
+  %i = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp sge i64 %i, 5
+  br i1 %done, label %exit, label %body
+
+body:
+  %next = add i64 %i, 0
+  br label %header
+
+exit:
+  ret i64 %i
+}
+
+; Same loop as above but which actually counts up, so finishes.
+define i64 @Loops__Count__body() #0 {
+entry:
+  br label %header
+
+header:
+  %i = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp sge i64 %i, 5
+  br i1 %done, label %exit, label %body
+
+body:
+  %next = add i64 %i, 1
+  br label %header
+
+exit:
+  ret i64 %i
+}
+
+; Changes state every iteration but will effectively never finish.
+define i64 @Loops__Forever__body() #0 {
+entry:
+  br label %header
+
+header:
+  %i = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp slt i64 %i, 0
+  br i1 %done, label %exit, label %body
+
+body:
+  %next = add i64 %i, 1
+  br label %header
+
+exit:
+  ret i64 %i
+}
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,98 @@
+; ModuleID = 'pauli_measures'
+source_filename = "pauli_measures"
+
+%Qubit = type opaque
+%Result = type opaque
+%Array = type opaque
+
+define %Result* @Paulis__MeasureX__body() #0 {
+entry:
+  %q = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__h__body(%Qubit* %q)
+  %result = call %Result* @Paulis__Measure__body(i2 1, i2 0, %Qubit* %q, %Qubit* null, i64 1)
+  ret %Result* %result
+}
+
+define %Result* @Paulis__MeasureY__body() #0 {
+entry:
+  %q = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__h__body(%Qubit* %q)
+  call void @__quantum__qis__s__adj(%Qubit* %q)
+  %result = call %Result* @Paulis__Measure__body(i2 -1, i2 0, %Qubit* %q, %Qubit* null, i64 1)
+  ret %Result* %result
+}
+
+define %Result* @Paulis__MeasureXX__body() #0 {
+entry:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__h__body(%Qubit* %a)
+  call void @__quantum__qis__x__body(%Qubit* %b)
+  call void @__quantum__qis__h__body(%Qubit* %b)
+  %result = call %Result* @Paulis__Measure__body(i2 1, i2 1, %Qubit* %a, %Qubit* %b, i64 2)
+  ret %Result* %result
+}
+
+define %Result* @Paulis__MeasureYY__body() #0 {
+entry:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__h__body(%Qubit* %a)
+  call void @__quantum__qis__s__adj(%Qubit* %a)
+  call void @__quantum__qis__h__body(%Qubit* %b)
+  call void @__quantum__qis__s__adj(%Qubit* %b)
+  %result = call %Result* @Paulis__Measure__body(i2 -1, i2 -1, %Qubit* %a, %Qubit* %b, i64 2)
+  ret %Result* %result
+}
+
+define %Result* @Paulis__MeasureZZ__body() #0 {
+entry:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__x__body(%Qubit* %a)
+  %result = call %Result* @Paulis__Measure__body(i2 -2, i2 -2, %Qubit* %a, %Qubit* %b, i64 2)
+  ret %Result* %result
+}
+
+define internal %Result* @Paulis__Measure__body(i2 %first_basis, i2 %second_basis, %Qubit* %first, %Qubit* %second, i64 %count) {
+entry:
+  %bases = call %Array* @__quantum__rt__array_create_1d(i32 1, i64 %count)
+  %qubits = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 %count)
+  %0 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %bases, i64 0)
+  %1 = bitcast i8* %0 to i2*
+  store i2 %first_basis, i2* %1, align 1
+  %2 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %qubits, i64 0)
+  %3 = bitcast i8* %2 to %Qubit**
+  store %Qubit* %first, %Qubit** %3, align 8
+  %4 = icmp eq i64 %count, 2
+  br i1 %4, label %then, label %continue
+
+then:
+  %5 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %bases, i64 1)
+  %6 = bitcast i8* %5 to i2*
+  store i2 %second_basis, i2* %6, align 1
+  %7 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %qubits, i64 1)
+  %8 = bitcast i8* %7 to %Qubit**
+  store %Qubit* %second, %Qubit** %8, align 8
+  br label %continue
+
+continue:
+  %result = call %Result* @__quantum__qis__measure__body(%Array* %bases, %Array* %qubits)
+  ret %Result* %result
+}
+
+declare %Qubit* @__quantum__rt__qubit_allocate()
+
+declare void @__quantum__qis__h__body(%Qubit*)
+
+declare void @__quantum__qis__s__adj(%Qubit*)
+
+declare void @__quantum__qis__x__body(%Qubit*)
+
+declare %Array* @__quantum__rt__array_create_1d(i32, i64)
+
+declare i8* @__quantum__rt__array_get_element_ptr_1d(%Array*, i64)
+
+declare %Result* @__quantum__qis__measure__body(%Array*, %Array*)
+
+attributes #0 = { "EntryPoint" }
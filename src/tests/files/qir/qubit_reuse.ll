@@ -0,0 +1,101 @@
+; ModuleID = 'qubit_reuse'
+source_filename = "qubit_reuse"
+
+%Qubit = type opaque
+%Result = type opaque
+
+; Entangles a fresh pair of qubits every round then releases them without resetting them, so
+; never has more than two qubits live at once. Measures a final qubit, which is zero as long as
+; whatever it reused was cleaned up.
+define i1 @Reuse__Released__body() #0 {
+entry:
+  br label %header
+
+header:
+  %round = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp sge i64 %round, 4
+  br i1 %done, label %exit, label %body
+
+body:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__x__body(%Qubit* %a)
+  call void @__quantum__qis__cnot__body(%Qubit* %a, %Qubit* %b)
+  call void @__quantum__rt__qubit_release(%Qubit* %a)
+  call void @__quantum__rt__qubit_release(%Qubit* %b)
+  %next = add i64 %round, 1
+  br label %header
+
+exit:
+  %c = call %Qubit* @__quantum__rt__qubit_allocate()
+  %result = call %Result* @__quantum__qis__m__body(%Qubit* %c)
+  %one = call %Result* @__quantum__rt__result_get_one()
+  %is_one = call i1 @__quantum__rt__result_equal(%Result* %result, %Result* %one)
+  call void @__quantum__rt__qubit_release(%Qubit* %c)
+  ret i1 %is_one
+}
+
+; Same as above, but the program resets its qubits itself before releasing them.
+define i1 @Reuse__Reset__body() #0 {
+entry:
+  br label %header
+
+header:
+  %round = phi i64 [ 0, %entry ], [ %next, %body ]
+  %done = icmp sge i64 %round, 4
+  br i1 %done, label %exit, label %body
+
+body:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__x__body(%Qubit* %a)
+  call void @__quantum__qis__cnot__body(%Qubit* %a, %Qubit* %b)
+  call void @__quantum__qis__reset__body(%Qubit* %a)
+  call void @__quantum__qis__reset__body(%Qubit* %b)
+  call void @__quantum__rt__qubit_release(%Qubit* %a)
+  call void @__quantum__rt__qubit_release(%Qubit* %b)
+  %next = add i64 %round, 1
+  br label %header
+
+exit:
+  %c = call %Qubit* @__quantum__rt__qubit_allocate()
+  %result = call %Result* @__quantum__qis__m__body(%Qubit* %c)
+  %one = call %Result* @__quantum__rt__result_get_one()
+  %is_one = call i1 @__quantum__rt__result_equal(%Result* %result, %Result* %one)
+  call void @__quantum__rt__qubit_release(%Qubit* %c)
+  ret i1 %is_one
+}
+
+; Measures a flipped qubit then releases it without a reset, so after-reset retires its index and
+; the next qubit gets a new one. Both are still built onto the same backend qubit, so their results
+; should differ.
+define i1 @Reuse__Shared__body() #0 {
+entry:
+  %a = call %Qubit* @__quantum__rt__qubit_allocate()
+  call void @__quantum__qis__x__body(%Qubit* %a)
+  %first = call %Result* @__quantum__qis__m__body(%Qubit* %a)
+  call void @__quantum__rt__qubit_release(%Qubit* %a)
+  %b = call %Qubit* @__quantum__rt__qubit_allocate()
+  %second = call %Result* @__quantum__qis__m__body(%Qubit* %b)
+  %same = call i1 @__quantum__rt__result_equal(%Result* %first, %Result* %second)
+  call void @__quantum__rt__qubit_release(%Qubit* %b)
+  ret i1 %same
+}
+
+declare %Qubit* @__quantum__rt__qubit_allocate()
+
+declare void @__quantum__rt__qubit_release(%Qubit*)
+
+declare void @__quantum__qis__x__body(%Qubit*)
+
+declare void @__quantum__qis__cnot__body(%Qubit*, %Qubit*)
+
+declare void @__quantum__qis__reset__body(%Qubit*)
+
+declare %Result* @__quantum__qis__m__body(%Qubit*)
+
+declare %Result* @__quantum__rt__result_get_one()
+
+declare i1 @__quantum__rt__result_equal(%Result*, %Result*)
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,87 @@
+; ModuleID = 'result_reduction'
+source_filename = "result_reduction"
+
+%Qubit = type opaque
+%Result = type opaque
+
+; Fair coin flip, read straight out of the result register.
+define i1 @Reduction__Coin__body() #0 {
+entry:
+  call void @__quantum__qis__h__body(%Qubit* null)
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  %0 = call i1 @__quantum__qir__read_result(%Result* null)
+  ret i1 %0
+}
+
+; Entangles two qubits then flips the second back if the first was one, so the second should
+; always match the untouched third.
+define i1 @Reduction__Correct__body() #0 {
+entry:
+  call void @__quantum__qis__h__body(%Qubit* null)
+  call void @__quantum__qis__cnot__body(%Qubit* null, %Qubit* nonnull inttoptr (i64 1 to %Qubit*))
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  %0 = call i1 @__quantum__qir__read_result(%Result* null)
+  br i1 %0, label %then, label %continue
+
+then:
+  call void @__quantum__qis__x__body(%Qubit* nonnull inttoptr (i64 1 to %Qubit*))
+  br label %continue
+
+continue:
+  call void @__quantum__qis__mz__body(%Qubit* nonnull inttoptr (i64 1 to %Qubit*), %Result* nonnull inttoptr (i64 1 to %Result*))
+  call void @__quantum__qis__mz__body(%Qubit* nonnull inttoptr (i64 2 to %Qubit*), %Result* nonnull inttoptr (i64 2 to %Result*))
+  %1 = call i1 @__quantum__rt__result_equal(%Result* nonnull inttoptr (i64 1 to %Result*), %Result* nonnull inttoptr (i64 2 to %Result*))
+  ret i1 %1
+}
+
+; Flips a qubit and measures it, then resets and measures it again. The two results never match.
+define i1 @Reduction__Remeasure__body() #0 {
+entry:
+  call void @__quantum__qis__x__body(%Qubit* null)
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  call void @__quantum__qis__reset__body(%Qubit* null)
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* nonnull inttoptr (i64 1 to %Result*))
+  %0 = call i1 @__quantum__rt__result_equal(%Result* null, %Result* nonnull inttoptr (i64 1 to %Result*))
+  ret i1 %0
+}
+
+; Repeats a coin flip on the same qubit until it comes up one.
+define i1 @Reduction__Repeat__body() #0 {
+entry:
+  br label %attempt
+
+attempt:
+  call void @__quantum__qis__reset__body(%Qubit* null)
+  call void @__quantum__qis__h__body(%Qubit* null)
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  %0 = call i1 @__quantum__qir__read_result(%Result* null)
+  br i1 %0, label %done, label %attempt
+
+done:
+  ret i1 %0
+}
+
+; Reads a result address nothing was ever measured into.
+define i1 @Reduction__Unwritten__body() #0 {
+entry:
+  call void @__quantum__qis__h__body(%Qubit* null)
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  %0 = call i1 @__quantum__qir__read_result(%Result* nonnull inttoptr (i64 3 to %Result*))
+  ret i1 %0
+}
+
+declare void @__quantum__qis__h__body(%Qubit*)
+
+declare void @__quantum__qis__x__body(%Qubit*)
+
+declare void @__quantum__qis__cnot__body(%Qubit*, %Qubit*)
+
+declare void @__quantum__qis__reset__body(%Qubit*)
+
+declare void @__quantum__qis__mz__body(%Qubit*, %Result* writeonly)
+
+declare i1 @__quantum__qir__read_result(%Result*)
+
+declare i1 @__quantum__rt__result_equal(%Result*, %Result*)
+
+attributes #0 = { "EntryPoint" }
@@ -1,3 +1,6 @@
+import json
+import tempfile
+import threading
 import unittest
 from os.path import abspath, dirname, join
 
@@ -5,7 +8,10 @@ from rasqal.routing import apply_routing, build_ring_architecture
 from .file_utils import get_qir_path
 from rasqal.simulators import fetch_qasm_runner
 from rasqal.adaptors import BuilderAdaptor, RuntimeAdaptor
+from rasqal.config import ExecutionSettings, RasqalConfig
+from rasqal.debugger import Debugger
 from rasqal.runtime import RasqalRunner
+from rasqal._native import Pauli, Qubit
 
 
 def fetch_project_ll(proj_name: str):
@@ -83,9 +89,11 @@ class BuilderMock(BuilderAdaptor):
 class RuntimeMock(RuntimeAdaptor):
     def __init__(self):
         self.executed = []
+        self.options = []
 
-    def execute(self, builder: BuilderMock):
+    def execute(self, builder: BuilderMock, options):
         self.executed.append(builder)
+        self.options.append(options)
         return dict()
 
     def create_builder(self) -> BuilderAdaptor:
@@ -101,7 +109,7 @@ class RuntimeMock(RuntimeAdaptor):
 
 
 class RuntimeErrorMock(RuntimeMock):
-    def execute(self, builder: BuilderMock):
+    def execute(self, builder: BuilderMock, options):
         raise ValueError("Unable to execute.")
 
 
@@ -201,6 +209,109 @@ class TestRasqal(unittest.TestCase):
         runner.run(qir, [False])
         assert runtime.builder_instructions == ["measure 0"]
 
+    def test_batch_minified_generator(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+
+        runtime, runner = fetch_mock_runner()
+        results = runner.run_batch(qir, [[True], [False], [True]])
+        assert len(results) == 3
+
+        # Runs are independent unless a cache is configured, so every circuit is executed.
+        assert len(runtime.executed) == 3
+        assert runtime.executed[0].gates == ["x 0 3.141592653589793", "measure 0"]
+        assert runtime.executed[1].gates == ["measure 0"]
+        assert runtime.executed[2].gates == ["x 0 3.141592653589793", "measure 0"]
+
+        # With one, the third run is identical to the first so its circuit is reused.
+        runtime, runner = fetch_mock_runner()
+        runner.cache_results()
+        runner.run_batch(qir, [[True], [False], [True]])
+        assert len(runtime.executed) == 2
+
+    def test_cached_results(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+
+        runtime, runner = fetch_mock_runner()
+        runner.cache_results(max_entries=10)
+        runner.run(qir, [True])
+        runner.run(qir, [False])
+        runner.run(qir, [True])
+        assert len(runtime.executed) == 2
+
+    def test_cache_identity(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+
+        class ConfiguredMock(RuntimeMock):
+            def __init__(self, device):
+                super().__init__()
+                self.device = device
+
+            def identity(self):
+                return f"configured-mock.{self.device}"
+
+        with tempfile.TemporaryDirectory() as directory:
+
+            def run_on(device):
+                runtime = ConfiguredMock(device)
+                runner = RasqalRunner(runtime).cache_results(directory=directory)
+                runner.run(qir, [True])
+                return runtime
+
+            # Same class, but differently-configured instances never share results.
+            assert len(run_on("first").executed) == 1
+            assert len(run_on("second").executed) == 1
+            assert len(run_on("first").executed) == 0
+
+    def test_execution_options(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+
+        runtime, runner = fetch_mock_runner()
+        runner.execution_options(shots=100, seed=5)
+        runner.projection_options(0, shots=2000)
+        runner.run(qir, [True])
+
+        options = runtime.options[0]
+        assert options.shots == 2000
+        assert options.seed == 5
+        assert options.timeout is None
+
+        with self.assertRaises(ValueError):
+            runner.execution_options(timeout=-1.0)
+
+    def test_builder_only_execute(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+
+        class BuilderOnlyMock(RuntimeMock):
+            def execute(self, builder):
+                self.executed.append(builder)
+                return dict()
+
+        runtime = BuilderOnlyMock()
+        RasqalRunner(runtime).run(qir, [True])
+        assert len(runtime.executed) == 1
+
+    def test_shared_config(self):
+        qir = fetch_project_ll("minified-oracle-generator")
+        config = RasqalConfig(
+            step_count_limit=10000,
+            execution_options=ExecutionSettings(shots=500),
+            projection_options={0: ExecutionSettings(seed=3)},
+        )
+
+        for _ in range(2):
+            runtime = RuntimeMock()
+            RasqalRunner(runtime, config).run(qir, [True])
+            assert runtime.options[0].shots == 500
+            assert runtime.options[0].seed == 3
+
+    def test_solver_options(self):
+        runtime, runner = fetch_mock_runner()
+        runner.activate_solver().solver_strategy("mps").max_bond_dimension(8)
+        runner.run(get_qir_path("bell_psi_plus.ll"))
+
+        with self.assertRaises(ValueError):
+            runner.solver_strategy("unknown")
+
     def test_simplified_generator(self):
         qir = fetch_project_ll("simplified-oracle-generator")
         runtime, runner = fetch_mock_runner()
@@ -273,6 +384,258 @@ class TestRasqal(unittest.TestCase):
             "measure 1",
         ]
 
+    def test_run_statistics(self):
+        runtime, runner = fetch_mock_runner()
+        assert runner.statistics() is None
+        runner.run(get_qir_path("bell_int_return.ll"))
+
+        statistics = runner.statistics()
+        assert statistics["steps"] > 0
+        assert statistics["projections_concretized"] == 1
+        assert statistics["circuits_executed"] == 1
+        assert statistics["total_time"] >= statistics["qpu_time"]
+
+        projection = statistics["projections"][0]
+        assert projection["width"] == 2
+        assert projection["gate_counts"]["cx"] == 1
+        assert projection["gate_counts"]["measure"] == 2
+        assert not projection["solved"]
+
+    def test_trace_events(self):
+        runtime, runner = fetch_mock_runner()
+        events = []
+        runner.subscribe(events.append)
+        runner.run(get_qir_path("bell_int_return.ll"))
+
+        kinds = {event["event"] for event in events}
+        assert {"node_entered", "variable_changed", "projection_created"} <= kinds
+        gates = [event for event in events if event["event"] == "gate_added"]
+        assert [gate["qubits"] for gate in gates if gate["gate"] == "cx"] == [[0, 1]]
+        assert sum(len(gate["qubits"]) for gate in gates if gate["gate"] == "measure") == 2
+
+        concretized = [
+            event for event in events if event["event"] == "projection_concretized"
+        ]
+        assert len(concretized) == 1
+        assert not concretized[0]["solved"]
+
+    def test_trace_to_file(self):
+        runtime, runner = fetch_mock_runner()
+        with tempfile.TemporaryDirectory() as directory:
+            trace_file = join(directory, "trace.jsonl")
+            runner.with_config(RasqalConfig(trace_file=trace_file))
+            runner.run(get_qir_path("bell_int_return.ll"))
+
+            with open(trace_file, "r") as f:
+                events = [json.loads(line) for line in f]
+
+        assert events[0]["event"] == "node_entered"
+        assert any(event["event"] == "projection_concretized" for event in events)
+
+    def test_debug_info_origins(self):
+        runtime, runner = fetch_mock_runner()
+        events = []
+        runner.subscribe(events.append)
+        runner.run_files(
+            [get_qir_path("debug_info.ll")], entry_point="Debug__Count__body"
+        )
+
+        origins = {event["origin"] for event in events if event["event"] == "node_entered"}
+        assert "Debug.qs:6:13 (Debug__Count__body, body)" in origins
+
+        with self.assertRaises(ValueError) as thrown:
+            runner.run_files(
+                [get_qir_path("debug_info.ll")], entry_point="Debug__Fail__body"
+            )
+
+        assert "Debug.qs:12:9 (Debug__Fail__body, entry)" in str(thrown.exception)
+
+    def test_result_reduction(self):
+        class SkewedRuntime(RuntimeMock):
+            def execute(self, builder: BuilderMock, options):
+                super().execute(builder, options)
+                return {"1": 51, "0": 49}
+
+        def coin(reduction=None, threshold=None):
+            runner = RasqalRunner(SkewedRuntime())
+            if reduction is not None:
+                runner.result_reduction(reduction, threshold)
+            return runner.run_files(
+                [get_qir_path("result_reduction.ll")], entry_point="Reduction__Coin__body"
+            )
+
+        assert coin() is True
+        assert coin("error-on-ambiguity", 0.5) is True
+
+        with self.assertRaises(ValueError) as thrown:
+            coin("unanimous")
+        assert "isn't unanimous" in str(thrown.exception)
+
+        with self.assertRaises(ValueError) as thrown:
+            coin("error-on-ambiguity")
+        assert "51 shots were one and 49 zero" in str(thrown.exception)
+
+        with self.assertRaises(ValueError):
+            coin("coin-flip")
+
+        runner = RasqalRunner(SkewedRuntime()).result_reduction("per-shot")
+        runner.execution_options(seed=4)
+        picks = {
+            runner.run_files(
+                [get_qir_path("result_reduction.ll")], entry_point="Reduction__Coin__body"
+            )
+            for _ in range(3)
+        }
+        assert len(picks) == 1
+
+    def test_qubit_allocation(self):
+        class SizedRuntime(RuntimeMock):
+            def __init__(self):
+                super().__init__()
+                self.required = []
+
+            def has_features(self, required_features):
+                self.required.append(required_features.qubit_count)
+                return required_features.qubit_count <= 2
+
+        def run(entry_point, allocation=None, capacity=None):
+            runtime = SizedRuntime()
+            runner = RasqalRunner(runtime)
+            if allocation is not None:
+                runner.qubit_allocation(allocation, capacity)
+            runner.run_files([get_qir_path("qubit_reuse.ll")], entry_point=entry_point)
+            return runtime
+
+        runtime = run("Reuse__Released__body")
+        assert runtime.required == [1]
+        runtime = run("Reuse__Reset__body", "after-reset")
+        assert runtime.required == [1]
+
+        # Retired qubits still get higher indices, but circuits are built on compacted qubits.
+        runtime = run("Reuse__Released__body", "after-reset")
+        assert runtime.required == [1]
+
+        with self.assertRaises(ValueError) as thrown:
+            run("Reuse__Released__body", "fixed", 1)
+        assert "Ran out of qubits" in str(thrown.exception)
+
+        with self.assertRaises(ValueError):
+            run("Reuse__Released__body", "fixed")
+
+    def test_projection_splitting(self):
+        runtime, runner = fetch_mock_runner()
+        runner.run(get_qir_path("independent_pairs.ll"))
+        assert len(runtime.executed) == 1
+
+        runtime, runner = fetch_mock_runner()
+        runner.projection_splitting().run(get_qir_path("independent_pairs.ll"))
+        assert runtime.executed[0].gates == [
+            "x 0 3.141592653589793",
+            "cx [0] 1 3.141592653589793",
+            "measure 1",
+        ]
+        assert runtime.executed[1].gates == [
+            "x 2 3.141592653589793",
+            "cx [2] 3 3.141592653589793",
+            "measure 3",
+        ]
+        assert runner.statistics()["projections_merged"] == 2
+
+    def test_trajectories(self):
+        runtime, runner = fetch_mock_runner()
+        runner.execution_options(seed=11)
+        coin = runner.run_trajectories(
+            get_qir_path("result_reduction.ll"), 40, entry_point="Reduction__Coin__body"
+        )
+        assert set(coin.keys()) == {"true", "false"}
+        assert sum(coin.values()) == 40
+
+        correct = runner.run_trajectories(
+            get_qir_path("result_reduction.ll"), 40, entry_point="Reduction__Correct__body"
+        )
+        assert correct == {"true": 40}
+        assert runner.statistics()["cache_hits"] > 0
+
+        # Shots run on the built-in simulator, never the runners own runtimes.
+        assert runtime.executed == []
+
+    def test_array_arguments(self):
+        runtime, runner = fetch_mock_runner()
+        results = runner.run(
+            get_qir_path("array_argument.ll"), [[Pauli.X, Pauli.Z, Pauli.Y]]
+        )
+        assert results == 3
+
+        results = runner.run(get_qir_path("array_argument.ll"), [(1, 2)])
+        assert results == 2
+
+    def test_named_arguments(self):
+        runtime, runner = fetch_mock_runner()
+        results = runner.run(get_qir_path("array_argument.ll"), {"paulis": [Pauli.X]})
+        assert results == 1
+
+        results = runner.run(
+            get_qir_path("array_argument.ll"), {"%paulis": [Pauli.X, Pauli.Y]}
+        )
+        assert results == 2
+
+        with self.assertRaises(ValueError):
+            runner.run(get_qir_path("array_argument.ll"), {"other": 1})
+
+        with self.assertRaises(ValueError):
+            runner.run(get_qir_path("array_argument.ll"), {"paulis": True})
+
+        results = runner.run_batch(
+            get_qir_path("array_argument.ll"),
+            [{"paulis": [Pauli.X]}, [[Pauli.X, Pauli.Y, Pauli.Z]]],
+        )
+        assert results == [1, 3]
+
+    def test_in_memory_qir(self):
+        runtime, runner = fetch_mock_runner()
+        with open(get_qir_path("array_argument.ll"), "r") as f:
+            ll_string = f.read()
+
+        assert runner.run_ll(ll_string, [[Pauli.X, Pauli.Z]]) == 2
+
+        graph = runner.executor.parse_ll(ll_string, None)
+        assert graph.signature.name == "Arguments__CountPaulis__body"
+
+        with self.assertRaises(ValueError):
+            runner.run_ll("not llvm")
+
+        with self.assertRaises(ValueError):
+            runner.run_bitcode(ll_string.encode())
+
+    def test_linked_files(self):
+        runtime, runner = fetch_mock_runner()
+        files = [get_qir_path("linked_entry.ll"), get_qir_path("linked_library.ll")]
+        assert runner.run_files(files, [[Pauli.X, Pauli.Y, Pauli.Z]]) == 3
+
+        graph = runner.executor.parse_files(files, None)
+        assert graph.signature.name == "Linked__CountPaulis__body"
+
+        with self.assertRaises(ValueError):
+            runner.executor.parse_files([], None)
+
+    def test_entry_point_signature(self):
+        runtime, runner = fetch_mock_runner()
+        graph = runner.executor.parse_file(get_qir_path("array_argument.ll"), None)
+
+        signature = graph.signature
+        assert signature.name == "Arguments__CountPaulis__body"
+        assert signature.arguments == [("%paulis", "Array")]
+        assert signature.return_type == "Int"
+        assert [val.name for val in graph.entry_points] == [signature.name]
+
+        graph = runner.executor.parse_file(get_qir_path("base_profile_ops.ll"), None)
+        assert graph.signature.required_num_qubits == 2
+
+    def test_unsupported_argument(self):
+        runtime, runner = fetch_mock_runner()
+        with self.assertRaises(ValueError):
+            runner.run(get_qir_path("array_argument.ll"), [object()])
+
     def test_routed_bell_psi_plus(self):
         runtime, runner = fetch_mock_runner()
 
@@ -347,6 +710,76 @@ class TestRasqal(unittest.TestCase):
 
         assert "step count" in str(thrown.exception)
 
+    def test_infinite_loop_detection(self):
+        runtime, runner = fetch_mock_runner()
+        with self.assertRaises(ValueError) as thrown:
+            runner.run_files([get_qir_path("loops.ll")], entry_point="Loops__Spin__body")
+
+        assert "Infinite loop detected" in str(thrown.exception)
+        assert runner.run_files([get_qir_path("loops.ll")], entry_point="Loops__Count__body") == 5
+
+    def test_time_limit(self):
+        runtime, runner = fetch_mock_runner()
+        runner.time_limit(0.05)
+        with self.assertRaises(ValueError) as thrown:
+            runner.run_files([get_qir_path("loops.ll")], entry_point="Loops__Forever__body")
+
+        assert "time limit" in str(thrown.exception)
+
+        with self.assertRaises(ValueError):
+            runner.time_limit(-1.0)
+
+    def test_cancel(self):
+        runtime, runner = fetch_mock_runner()
+
+        # Time limit is a fallback so a broken cancel doesn't hang the tests.
+        runner.time_limit(60)
+        threading.Timer(0.1, runner.cancel).start()
+        with self.assertRaises(ValueError) as thrown:
+            runner.run_files([get_qir_path("loops.ll")], entry_point="Loops__Forever__body")
+
+        assert "cancelled" in str(thrown.exception)
+
+    def test_debugger_breakpoints(self):
+        runtime, runner = fetch_mock_runner()
+        debugger = Debugger(runner).break_at_label("body")
+        result = runner.run_files(
+            [get_qir_path("loops.ll")], entry_point="Loops__Count__body"
+        )
+
+        assert result == 5
+        assert len(debugger.stops) == 5
+        for stop in debugger.stops:
+            assert stop["reason"] == "breakpoint"
+            assert stop["breakpoint"] == "label body"
+            assert stop["call_stack"] == ["Loops__Count__body"]
+        assert "4" in debugger.stops[4]["variables"].values()
+
+    def test_debugger_inspection(self):
+        runtime, runner = fetch_mock_runner()
+
+        # Step from the very start, stopping when the first measure is about to happen.
+        def on_stop(stop):
+            return Debugger.ABORT if "measure" in stop["instruction"] else Debugger.STEP
+
+        qir = get_qir_path("bell_int_return.ll")
+        entry_point = runner.executor.parse_file(qir, None).signature.name
+        debugger = Debugger(runner, on_stop).break_at_function(entry_point)
+        with self.assertRaises(ValueError) as thrown:
+            runner.run(qir)
+
+        assert "aborted by debugger" in str(thrown.exception)
+        assert not runtime.executed
+
+        stop = debugger.stops[-1]
+        assert debugger.stops[0]["reason"] == "breakpoint"
+        assert all(val["reason"] == "step" for val in debugger.stops[1:])
+        assert stop["active_qubits"] == [0, 1]
+        assert len(stop["projections"]) == 1
+        assert stop["projections"][0]["qubits"] == [0, 1]
+        assert not stop["projections"][0]["concretized"]
+        assert any("CX" in val for val in stop["projections"][0]["circuit"])
+
     def test_python_exception_propagation(self):
         runner = RasqalRunner(RuntimeErrorMock())
         with self.assertRaises(ValueError) as thrown: